@@ -0,0 +1,10 @@
+//! Image entièrement arbitraire : Boot Sector, FAT et répertoires.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_project::device::block_device::MemoryBlockDevice;
+use rust_project::testing::explore;
+
+fuzz_target!(|data: &[u8]| {
+    explore(&MemoryBlockDevice::new(data));
+});
@@ -0,0 +1,21 @@
+//! Volume d’exemple sain, modifié par des écritures de 4 octets : chaque
+//! groupe de 6 octets de l’entrée donne un offset (u16) et une valeur
+//! (u32). Atteint la FAT et les répertoires bien plus vite que `mount`.
+#![no_main]
+
+use std::sync::LazyLock;
+
+use libfuzzer_sys::fuzz_target;
+use rust_project::device::block_device::RamBlockDevice;
+use rust_project::testing::{explore, ImageFixture};
+
+static SAMPLE: LazyLock<Vec<u8>> = LazyLock::new(|| ImageFixture::sample().build());
+
+fuzz_target!(|data: &[u8]| {
+    let mut image = SAMPLE.clone();
+    for patch in data.chunks_exact(6) {
+        let offset = u16::from_le_bytes([patch[0], patch[1]]) as usize % (image.len() - 3);
+        image[offset..offset + 4].copy_from_slice(&patch[2..6]);
+    }
+    explore(&RamBlockDevice::new(image));
+});
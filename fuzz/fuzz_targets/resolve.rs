@@ -0,0 +1,27 @@
+//! Chemins arbitraires résolus dans le volume d’exemple.
+#![no_main]
+
+use std::sync::LazyLock;
+
+use libfuzzer_sys::fuzz_target;
+use rust_project::device::block_device::MemoryBlockDevice;
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::testing::ImageFixture;
+
+static SAMPLE: LazyLock<Vec<u8>> = LazyLock::new(|| ImageFixture::sample().build());
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(path) = std::str::from_utf8(data) else {
+        return;
+    };
+    let device = MemoryBlockDevice::new(&SAMPLE);
+    let fs = FileSystem::mount(&device).unwrap();
+    let root = fs.root_cluster();
+
+    let _ = fs.list(Some(path), root);
+    let _ = fs.stat(path, root);
+    let _ = fs.read_file(path, root);
+    if let Ok(cluster) = fs.change_dir(path, root) {
+        let _ = fs.change_dir(path, cluster);
+    }
+});
@@ -0,0 +1,62 @@
+//! Périphérique de stockage asynchrone.
+//!
+//! Pendant de `BlockDevice` pour les exécuteurs asynchrones : le contrat est
+//! le même (offset en octets, tampon rempli entièrement, écriture
+//! optionnelle), mais les accès rendent la main à l’exécuteur au lieu de
+//! bloquer le thread. Le trait ne dépend d’aucun exécuteur et reste
+//! utilisable sans `std`.
+
+use crate::device::block_device::{BlockDevice, BlockDeviceError, BlockDeviceResult};
+
+/// Trait représentant un périphérique de stockage lisible de façon
+/// asynchrone.
+///
+/// Les futures ne sont pas tenues d’être `Send` : les exécuteurs locaux et
+/// embarqués n’en ont pas besoin.
+#[allow(async_fn_in_trait)]
+pub trait AsyncBlockDevice {
+    async fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()>;
+
+    /// Taille du périphérique en octets, si elle est connue (voir
+    /// `BlockDevice::size`).
+    fn size(&self) -> Option<u64> {
+        None
+    }
+
+    /// Écrit entièrement `buf` à l’offset donné.
+    async fn write_at(&self, _offset: u64, _buf: &[u8]) -> BlockDeviceResult<()> {
+        Err(BlockDeviceError::ReadOnly)
+    }
+}
+
+impl<T: AsyncBlockDevice + ?Sized> AsyncBlockDevice for &T {
+    async fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        (**self).read_at(offset, buf).await
+    }
+
+    fn size(&self) -> Option<u64> {
+        (**self).size()
+    }
+
+    async fn write_at(&self, offset: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        (**self).write_at(offset, buf).await
+    }
+}
+
+/// Présente un périphérique synchrone comme asynchrone : chaque accès se
+/// termine dès le premier appel, en bloquant le temps de l’accès.
+pub struct Blocking<D: BlockDevice>(pub D);
+
+impl<D: BlockDevice> AsyncBlockDevice for Blocking<D> {
+    async fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        self.0.read_at(offset, buf)
+    }
+
+    fn size(&self) -> Option<u64> {
+        self.0.size()
+    }
+
+    async fn write_at(&self, offset: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        self.0.write_at(offset, buf)
+    }
+}
@@ -1,17 +1,22 @@
 //! Abstraction d’un périphérique de stockage lisible par offset.
 //!
 //! Ce module ne dépend pas de FAT32.
-//! Il définit un contrat minimal pour lire des octets depuis une source quelconque.
+//! Il définit un contrat minimal pour lire (et éventuellement écrire) des octets
+//! depuis une source quelconque.
 
+use alloc::vec::Vec;
+use core::cell::RefCell;
 use core::fmt;
 
 /// Erreurs possibles lors d’une lecture sur un périphérique de stockage.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BlockDeviceError {
     /// Lecture demandée en dehors des limites du stockage.
     OutOfBounds,
     /// Erreur générique d’entrée/sortie.
     IoError,
+    /// Écriture demandée sur un périphérique en lecture seule.
+    ReadOnly,
 }
 
 impl fmt::Display for BlockDeviceError {
@@ -19,13 +24,23 @@ impl fmt::Display for BlockDeviceError {
         match self {
             BlockDeviceError::OutOfBounds => write!(f, "read out of bounds"),
             BlockDeviceError::IoError => write!(f, "I/O error"),
+            BlockDeviceError::ReadOnly => write!(f, "device is read-only"),
         }
     }
 }
 
+impl core::error::Error for BlockDeviceError {}
+
 /// Résultat d’une opération sur un périphérique de stockage.
 pub type BlockDeviceResult<T> = Result<T, BlockDeviceError>;
 
+/// Type d’accès à un périphérique (journaux, traces).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
 /// Trait représentant un périphérique de stockage lisible.
 ///
 /// # Contrat
@@ -33,8 +48,58 @@ pub type BlockDeviceResult<T> = Result<T, BlockDeviceError>;
 /// - la fonction doit remplir entièrement `buf`
 /// - aucune allocation ne doit être faite
 /// - aucune panique ne doit se produire
+///
+/// L’écriture est optionnelle : par défaut `write_at` renvoie
+/// `BlockDeviceError::ReadOnly`. Elle prend `&self` afin que les lecteurs
+/// (`Fat`, `ClusterReader`, ...) puissent partager le même périphérique.
 pub trait BlockDevice {
     fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()>;
+
+    /// Taille du périphérique en octets, si elle est connue.
+    ///
+    /// Elle permet de vérifier au montage que le volume tient sur le
+    /// périphérique ; `None` (par défaut) désactive cette vérification.
+    fn size(&self) -> Option<u64> {
+        None
+    }
+
+    /// Lit plusieurs plages en une requête : chaque `(offset, buf)` est
+    /// rempli comme par `read_at`.
+    ///
+    /// Par défaut, les plages sont lues une à une avec `read_at` ; un
+    /// périphérique peut regrouper les accès (fichier, cache, ...). En cas
+    /// d’erreur, le contenu des tampons est indéterminé.
+    fn read_vectored_at(&self, requests: &mut [(u64, &mut [u8])]) -> BlockDeviceResult<()> {
+        for (offset, buf) in requests.iter_mut() {
+            self.read_at(*offset, buf)?;
+        }
+        Ok(())
+    }
+
+    /// Écrit entièrement `buf` à l’offset donné.
+    fn write_at(&self, _offset: u64, _buf: &[u8]) -> BlockDeviceResult<()> {
+        Err(BlockDeviceError::ReadOnly)
+    }
+}
+
+/// Un périphérique emprunté reste un périphérique : les enveloppes
+/// (`OverlayDevice`, ...) peuvent ainsi travailler sur un emprunt.
+impl<T: BlockDevice + ?Sized> BlockDevice for &T {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        (**self).read_at(offset, buf)
+    }
+
+    fn size(&self) -> Option<u64> {
+        (**self).size()
+    }
+
+    fn read_vectored_at(&self, requests: &mut [(u64, &mut [u8])]) -> BlockDeviceResult<()> {
+        (**self).read_vectored_at(requests)
+    }
+
+    fn write_at(&self, offset: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        (**self).write_at(offset, buf)
+    }
 }
 
 /// Implémentation mémoire d’un périphérique de stockage.
@@ -63,4 +128,55 @@ impl<'a> BlockDevice for MemoryBlockDevice<'a> {
         buf.copy_from_slice(&self.data[offset..end]);
         Ok(())
     }
+
+    fn size(&self) -> Option<u64> {
+        Some(self.data.len() as u64)
+    }
+}
+
+/// Implémentation mémoire inscriptible d’un périphérique de stockage.
+///
+/// Possède ses données ; utilisée pour les réparations et les tests d’écriture.
+pub struct RamBlockDevice {
+    data: RefCell<Vec<u8>>,
+}
+
+impl RamBlockDevice {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self { data: RefCell::new(data) }
+    }
+
+    /// Crée un périphérique de `size` octets remplis de zéros.
+    pub fn zeroed(size: usize) -> Self {
+        Self::new(alloc::vec![0u8; size])
+    }
+
+    /// Rend les données sous-jacentes.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data.into_inner()
+    }
+}
+
+impl BlockDevice for RamBlockDevice {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        MemoryBlockDevice::new(&self.data.borrow()).read_at(offset, buf)
+    }
+
+    fn size(&self) -> Option<u64> {
+        Some(self.data.borrow().len() as u64)
+    }
+
+    fn write_at(&self, offset: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        let mut data = self.data.borrow_mut();
+        let offset = offset as usize;
+        let end = offset.checked_add(buf.len())
+            .ok_or(BlockDeviceError::OutOfBounds)?;
+
+        if end > data.len() {
+            return Err(BlockDeviceError::OutOfBounds);
+        }
+
+        data[offset..end].copy_from_slice(buf);
+        Ok(())
+    }
 }
@@ -0,0 +1,168 @@
+//! Supports qui ne font que des accès par blocs entiers.
+//!
+//! Cartes SD en SPI, disques NVMe ou 4Kn ne savent lire et écrire que des
+//! blocs complets, alignés. `BlockStorage` décrit ce contrat, et
+//! `BlockAdapter` présente un tel support comme un `BlockDevice` adressé à
+//! l’octet : les accès partiels passent par un bloc tampon, relu puis
+//! réécrit (read-modify-write) pour une écriture.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::device::block_device::{BlockDevice, BlockDeviceError, BlockDeviceResult};
+
+/// Trait représentant un support accessible par blocs.
+///
+/// # Contrat
+/// - `first` est un numéro de bloc
+/// - `buf.len()` est un multiple de `block_size()`, et la fonction le
+///   remplit (ou l’écrit) entièrement
+/// - un accès au-delà de `num_blocks()` renvoie `OutOfBounds`
+///
+/// Comme pour `BlockDevice`, l’écriture est optionnelle et prend `&self`.
+pub trait BlockStorage {
+    /// Taille d’un bloc en octets (non nulle).
+    fn block_size(&self) -> usize;
+
+    /// Nombre de blocs du support.
+    fn num_blocks(&self) -> u64;
+
+    fn read_blocks(&self, first: u64, buf: &mut [u8]) -> BlockDeviceResult<()>;
+
+    /// Écrit `buf.len() / block_size()` blocs à partir du bloc `first`.
+    fn write_blocks(&self, _first: u64, _buf: &[u8]) -> BlockDeviceResult<()> {
+        Err(BlockDeviceError::ReadOnly)
+    }
+}
+
+impl<T: BlockStorage + ?Sized> BlockStorage for &T {
+    fn block_size(&self) -> usize {
+        (**self).block_size()
+    }
+
+    fn num_blocks(&self) -> u64 {
+        (**self).num_blocks()
+    }
+
+    fn read_blocks(&self, first: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        (**self).read_blocks(first, buf)
+    }
+
+    fn write_blocks(&self, first: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        (**self).write_blocks(first, buf)
+    }
+}
+
+/// Présente un `BlockStorage` comme un `BlockDevice` adressé à l’octet.
+///
+/// Les blocs entièrement couverts par un accès sont transférés directement,
+/// en une requête ; seuls le premier et le dernier bloc d’un accès non
+/// aligné passent par le tampon.
+pub struct BlockAdapter<S: BlockStorage> {
+    storage: S,
+    /// Bloc tampon des accès partiels, alloué une fois pour toutes.
+    scratch: RefCell<Vec<u8>>,
+}
+
+impl<S: BlockStorage> BlockAdapter<S> {
+    /// # Panics
+    ///
+    /// Si la taille de bloc du support est nulle.
+    pub fn new(storage: S) -> Self {
+        let block_size = storage.block_size();
+        assert!(block_size > 0, "block size must not be zero");
+        Self {
+            storage,
+            scratch: RefCell::new(vec![0u8; block_size]),
+        }
+    }
+
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+
+    /// Taille du support en octets.
+    pub fn len(&self) -> u64 {
+        self.storage.num_blocks().saturating_mul(self.storage.block_size() as u64)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fin de l’accès, s’il tient dans le support.
+    fn end(&self, offset: u64, len: usize) -> BlockDeviceResult<u64> {
+        let end = offset
+            .checked_add(len as u64)
+            .ok_or(BlockDeviceError::OutOfBounds)?;
+        if end > self.len() {
+            return Err(BlockDeviceError::OutOfBounds);
+        }
+        Ok(end)
+    }
+}
+
+impl<S: BlockStorage> BlockDevice for BlockAdapter<S> {
+    fn size(&self) -> Option<u64> {
+        Some(self.len())
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        let end = self.end(offset, buf.len())?;
+        let size = self.storage.block_size() as u64;
+        let mut scratch = self.scratch.borrow_mut();
+
+        let mut pos = offset;
+        while pos < end {
+            let block = pos / size;
+            let within = pos % size;
+            let at = (pos - offset) as usize;
+
+            if within == 0 && end - pos >= size {
+                let n = ((end - pos) / size * size) as usize;
+                self.storage.read_blocks(block, &mut buf[at..at + n])?;
+                pos += n as u64;
+            } else {
+                let n = (size - within).min(end - pos) as usize;
+                self.storage.read_blocks(block, &mut scratch)?;
+                buf[at..at + n].copy_from_slice(&scratch[within as usize..within as usize + n]);
+                pos += n as u64;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_at(&self, offset: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        let end = self.end(offset, buf.len())?;
+        let size = self.storage.block_size() as u64;
+        let mut scratch = self.scratch.borrow_mut();
+
+        let mut pos = offset;
+        while pos < end {
+            let block = pos / size;
+            let within = pos % size;
+            let at = (pos - offset) as usize;
+
+            if within == 0 && end - pos >= size {
+                let n = ((end - pos) / size * size) as usize;
+                self.storage.write_blocks(block, &buf[at..at + n])?;
+                pos += n as u64;
+            } else {
+                // Bloc partiel : relu, complété, puis réécrit en entier.
+                let n = (size - within).min(end - pos) as usize;
+                self.storage.read_blocks(block, &mut scratch)?;
+                scratch[within as usize..within as usize + n].copy_from_slice(&buf[at..at + n]);
+                self.storage.write_blocks(block, &scratch)?;
+                pos += n as u64;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+//! Périphérique qui injecte des défauts, pour les tests de robustesse.
+//!
+//! `FaultDevice` enveloppe un périphérique et perturbe certains accès :
+//! erreur d’entrée/sortie, écriture interrompue à mi-chemin, bit inversé ou
+//! latence. Les accès perturbés sont choisis par des règles (type d’accès,
+//! plage d’offsets, nombre d’accès à laisser passer) ou tirés au sort à
+//! partir d’une graine : un même scénario rejoue toujours les mêmes défauts.
+//! Chaque accès est noté dans un journal, avec le défaut éventuel.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::ops::Range;
+use core::time::Duration;
+
+use crate::device::block_device::{Access, BlockDevice, BlockDeviceError, BlockDeviceResult};
+
+/// Défaut injecté sur un accès.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// L’accès échoue sans rien transférer.
+    Error(BlockDeviceError),
+    /// Écriture interrompue : seuls les `written` premiers octets atteignent
+    /// la base, puis l’accès échoue avec `IoError`. Sans effet sur une lecture.
+    Torn { written: usize },
+    /// L’accès réussit, mais le bit `bit` des données (modulo leur taille)
+    /// est inversé : dans le tampon rendu pour une lecture, sur le support
+    /// pour une écriture.
+    BitFlip { bit: u64 },
+    /// L’accès réussit après ce délai.
+    Delay(Duration),
+}
+
+/// Règle de déclenchement d’un défaut.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaultRule {
+    fault: Fault,
+    access: Option<Access>,
+    range: Range<u64>,
+    skip: u32,
+    times: Option<u32>,
+}
+
+impl FaultRule {
+    /// Défaut appliqué à tous les accès, lectures et écritures.
+    pub fn new(fault: Fault) -> Self {
+        Self {
+            fault,
+            access: None,
+            range: 0..u64::MAX,
+            skip: 0,
+            times: None,
+        }
+    }
+
+    /// Limite la règle aux lectures.
+    pub fn reads(mut self) -> Self {
+        self.access = Some(Access::Read);
+        self
+    }
+
+    /// Limite la règle aux écritures.
+    pub fn writes(mut self) -> Self {
+        self.access = Some(Access::Write);
+        self
+    }
+
+    /// Limite la règle aux accès qui touchent `range` (en octets).
+    pub fn range(mut self, range: Range<u64>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Laisse passer les `count` premiers accès concernés.
+    pub fn skip(mut self, count: u32) -> Self {
+        self.skip = count;
+        self
+    }
+
+    /// Ne se déclenche que `count` fois.
+    pub fn times(mut self, count: u32) -> Self {
+        self.times = Some(count);
+        self
+    }
+
+    fn matches(&self, access: Access, offset: u64, len: usize) -> bool {
+        let end = offset.saturating_add(len.max(1) as u64);
+        self.access.is_none_or(|a| a == access) && offset < self.range.end && self.range.start < end
+    }
+}
+
+/// Défauts tirés au sort, accès par accès.
+///
+/// Les taux sont en millièmes et s’additionnent : un accès subit au plus un
+/// défaut tiré au sort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomFaults {
+    pub seed: u64,
+    pub errors: u16,
+    pub torn_writes: u16,
+    pub bit_flips: u16,
+    pub delays: u16,
+    /// Latence des défauts `delays`.
+    pub delay: Duration,
+}
+
+impl RandomFaults {
+    /// Aucun défaut : les taux sont à fixer.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            errors: 0,
+            torn_writes: 0,
+            bit_flips: 0,
+            delays: 0,
+            delay: Duration::from_millis(1),
+        }
+    }
+}
+
+/// Accès noté dans le journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessRecord {
+    pub access: Access,
+    pub offset: u64,
+    pub len: usize,
+    pub fault: Option<Fault>,
+}
+
+/// Règle et nombre d’accès concernés jusqu’ici.
+struct RuleState {
+    rule: FaultRule,
+    seen: u32,
+    fired: u32,
+}
+
+/// Périphérique qui perturbe les accès à sa base.
+pub struct FaultDevice<B: BlockDevice> {
+    base: B,
+    rules: RefCell<Vec<RuleState>>,
+    random: Option<RandomFaults>,
+    rng: Cell<u64>,
+    sleep: fn(Duration),
+    delayed: Cell<Duration>,
+    log: RefCell<Vec<AccessRecord>>,
+}
+
+impl<B: BlockDevice> FaultDevice<B> {
+    /// Enveloppe sans défaut : tous les accès passent et sont journalisés.
+    ///
+    /// Avec la feature `std`, une latence endort le thread ; sinon elle est
+    /// seulement comptée (`delayed`).
+    pub fn new(base: B) -> Self {
+        Self {
+            base,
+            rules: RefCell::new(Vec::new()),
+            random: None,
+            rng: Cell::new(0),
+            sleep: default_sleep,
+            delayed: Cell::new(Duration::ZERO),
+            log: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Ajoute une règle ; la première règle qui se déclenche l’emporte sur
+    /// les suivantes et sur le tirage au sort.
+    pub fn rule(self, rule: FaultRule) -> Self {
+        self.rules.borrow_mut().push(RuleState {
+            rule,
+            seen: 0,
+            fired: 0,
+        });
+        self
+    }
+
+    /// Tire des défauts au sort pour les accès qu’aucune règle ne perturbe.
+    pub fn random(mut self, random: RandomFaults) -> Self {
+        // xorshift : la graine ne doit pas être nulle.
+        self.rng.set(random.seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+        self.random = Some(random);
+        self
+    }
+
+    /// Remplace la fonction d’attente des latences.
+    pub fn sleep_with(mut self, sleep: fn(Duration)) -> Self {
+        self.sleep = sleep;
+        self
+    }
+
+    pub fn base(&self) -> &B {
+        &self.base
+    }
+
+    pub fn into_inner(self) -> B {
+        self.base
+    }
+
+    /// Journal des accès, dans l’ordre.
+    pub fn log(&self) -> Vec<AccessRecord> {
+        self.log.borrow().clone()
+    }
+
+    pub fn clear_log(&self) {
+        self.log.borrow_mut().clear();
+    }
+
+    /// Accès perturbés depuis le début (ou le dernier `clear_log`).
+    pub fn faults(&self) -> usize {
+        self.log.borrow().iter().filter(|r| r.fault.is_some()).count()
+    }
+
+    /// Latence totale injectée.
+    pub fn delayed(&self) -> Duration {
+        self.delayed.get()
+    }
+
+    /// Défaut à appliquer à cet accès.
+    fn fault(&self, access: Access, offset: u64, len: usize) -> Option<Fault> {
+        let mut rules = self.rules.borrow_mut();
+        for state in rules.iter_mut() {
+            let rule = &state.rule;
+            let applies = rule.matches(access, offset, len)
+                && !(access == Access::Read && matches!(rule.fault, Fault::Torn { .. }));
+            if !applies || rule.times.is_some_and(|times| state.fired >= times) {
+                continue;
+            }
+
+            state.seen += 1;
+            if state.seen > rule.skip {
+                state.fired += 1;
+                return Some(rule.fault);
+            }
+        }
+        drop(rules);
+
+        let random = self.random?;
+        let draw = (self.next() % 1000) as u16;
+        let bits = (len as u64 * 8).max(1);
+        let mut threshold = random.errors;
+        if draw < threshold {
+            return Some(Fault::Error(BlockDeviceError::IoError));
+        }
+        // Une lecture ne peut pas être déchirée : la bande des écritures
+        // déchirées n’existe que pour les écritures.
+        if access == Access::Write {
+            threshold += random.torn_writes;
+            if draw < threshold {
+                return Some(Fault::Torn {
+                    written: (self.next() % len.max(1) as u64) as usize,
+                });
+            }
+        }
+        threshold += random.bit_flips;
+        if draw < threshold {
+            return Some(Fault::BitFlip { bit: self.next() % bits });
+        }
+        threshold += random.delays;
+        if draw < threshold {
+            return Some(Fault::Delay(random.delay));
+        }
+        None
+    }
+
+    fn next(&self) -> u64 {
+        let mut x = self.rng.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng.set(x);
+        x
+    }
+
+    fn record(&self, access: Access, offset: u64, len: usize, fault: Option<Fault>) {
+        self.log.borrow_mut().push(AccessRecord {
+            access,
+            offset,
+            len,
+            fault,
+        });
+
+        if let Some(Fault::Delay(delay)) = fault {
+            self.delayed.set(self.delayed.get() + delay);
+            (self.sleep)(delay);
+        }
+    }
+}
+
+impl<B: BlockDevice> BlockDevice for FaultDevice<B> {
+    fn size(&self) -> Option<u64> {
+        self.base.size()
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        let fault = self.fault(Access::Read, offset, buf.len());
+        self.record(Access::Read, offset, buf.len(), fault);
+
+        match fault {
+            Some(Fault::Error(e)) => Err(e),
+            Some(Fault::BitFlip { bit }) => {
+                self.base.read_at(offset, buf)?;
+                flip(buf, bit);
+                Ok(())
+            }
+            _ => self.base.read_at(offset, buf),
+        }
+    }
+
+    fn write_at(&self, offset: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        let fault = self.fault(Access::Write, offset, buf.len());
+        self.record(Access::Write, offset, buf.len(), fault);
+
+        match fault {
+            Some(Fault::Error(e)) => Err(e),
+            Some(Fault::Torn { written }) => {
+                self.base.write_at(offset, &buf[..written.min(buf.len())])?;
+                Err(BlockDeviceError::IoError)
+            }
+            Some(Fault::BitFlip { bit }) => {
+                let mut data = vec![0u8; buf.len()];
+                data.copy_from_slice(buf);
+                flip(&mut data, bit);
+                self.base.write_at(offset, &data)
+            }
+            _ => self.base.write_at(offset, buf),
+        }
+    }
+}
+
+/// Inverse le bit `bit` (modulo la taille) de `data`.
+fn flip(data: &mut [u8], bit: u64) {
+    if !data.is_empty() {
+        let bit = bit % (data.len() as u64 * 8);
+        data[(bit / 8) as usize] ^= 1 << (bit % 8);
+    }
+}
+
+#[cfg(feature = "std")]
+fn default_sleep(delay: Duration) {
+    std::thread::sleep(delay);
+}
+
+#[cfg(not(feature = "std"))]
+fn default_sleep(_delay: Duration) {}
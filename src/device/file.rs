@@ -0,0 +1,120 @@
+//! Périphérique adossé à un fichier image de l’hôte (feature `std`).
+
+use core::cell::RefCell;
+use std::fs::{File, OpenOptions};
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::device::block_device::{BlockDevice, BlockDeviceError, BlockDeviceResult};
+
+/// Image disque stockée dans un fichier.
+pub struct FileBlockDevice {
+    file: RefCell<File>,
+    len: u64,
+    writable: bool,
+}
+
+impl FileBlockDevice {
+    /// Ouvre une image en lecture seule.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_file(File::open(path)?, false)
+    }
+
+    /// Ouvre une image existante en lecture/écriture.
+    pub fn open_rw<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Self::from_file(file, true)
+    }
+
+    /// Crée (ou tronque) une image de `size` octets remplie de zéros.
+    pub fn create<P: AsRef<Path>>(path: P, size: u64) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(size)?;
+        Self::from_file(file, true)
+    }
+
+    fn from_file(file: File, writable: bool) -> io::Result<Self> {
+        let len = file.metadata()?.len();
+        Ok(Self {
+            file: RefCell::new(file),
+            len,
+            writable,
+        })
+    }
+
+    /// Taille de l’image en octets.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn check_bounds(&self, offset: u64, len: usize) -> BlockDeviceResult<()> {
+        let end = offset
+            .checked_add(len as u64)
+            .ok_or(BlockDeviceError::OutOfBounds)?;
+
+        if end > self.len {
+            return Err(BlockDeviceError::OutOfBounds);
+        }
+        Ok(())
+    }
+}
+
+impl BlockDevice for FileBlockDevice {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        self.check_bounds(offset, buf.len())?;
+
+        let mut file = self.file.borrow_mut();
+        file.seek(SeekFrom::Start(offset)).map_err(io_error)?;
+        file.read_exact(buf).map_err(io_error)
+    }
+
+    fn size(&self) -> Option<u64> {
+        Some(self.len)
+    }
+
+    /// Les plages qui se suivent sont lues sans repositionnement ; aucune
+    /// n’est lue si l’une d’elles sort de l’image.
+    fn read_vectored_at(&self, requests: &mut [(u64, &mut [u8])]) -> BlockDeviceResult<()> {
+        for (offset, buf) in requests.iter() {
+            self.check_bounds(*offset, buf.len())?;
+        }
+
+        let mut file = self.file.borrow_mut();
+        let mut position = None;
+        for (offset, buf) in requests.iter_mut() {
+            if position != Some(*offset) {
+                file.seek(SeekFrom::Start(*offset)).map_err(io_error)?;
+            }
+            file.read_exact(buf).map_err(io_error)?;
+            position = Some(*offset + buf.len() as u64);
+        }
+        Ok(())
+    }
+
+    fn write_at(&self, offset: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        if !self.writable {
+            return Err(BlockDeviceError::ReadOnly);
+        }
+        self.check_bounds(offset, buf.len())?;
+
+        let mut file = self.file.borrow_mut();
+        file.seek(SeekFrom::Start(offset)).map_err(io_error)?;
+        file.write_all(buf).map_err(io_error)
+    }
+}
+
+fn io_error(e: io::Error) -> BlockDeviceError {
+    match e.kind() {
+        ErrorKind::UnexpectedEof => BlockDeviceError::OutOfBounds,
+        _ => BlockDeviceError::IoError,
+    }
+}
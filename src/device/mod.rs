@@ -1 +1,8 @@
+pub mod async_device;
 pub mod block_device;
+pub mod blocks;
+pub mod fault;
+pub mod overlay;
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod file;
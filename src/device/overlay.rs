@@ -0,0 +1,307 @@
+//! Périphérique copie-sur-écriture au-dessus d’un périphérique de base.
+//!
+//! Les écritures ne touchent jamais la base : chaque secteur modifié est
+//! copié dans un delta en mémoire, et les lectures le superposent aux
+//! données de la base. Le delta peut ensuite être abandonné, appliqué à la
+//! base, ou exporté dans un patch pour être rechargé plus tard.
+//!
+//! Format du patch (entiers petit-boutistes) :
+//!
+//! | Offset | Taille | Contenu                                   |
+//! |--------|--------|-------------------------------------------|
+//! | 0      | 8      | `FATDELTA`                                |
+//! | 8      | 4      | taille de secteur                         |
+//! | 12     | 4      | nombre de secteurs                        |
+//! | 16     | …      | par secteur : numéro (8 octets) et données |
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt;
+use core::ops::Range;
+
+use crate::device::block_device::{BlockDevice, BlockDeviceError, BlockDeviceResult};
+
+const PATCH_MAGIC: &[u8; 8] = b"FATDELTA";
+const PATCH_HEADER: usize = 16;
+
+/// Erreur de chargement d’un patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    /// En-tête absent ou signature inconnue.
+    InvalidHeader,
+    /// Le patch a été produit avec une autre taille de secteur.
+    SectorSize { patch: u32, device: u32 },
+    /// Le patch s’arrête au milieu d’un secteur.
+    Truncated,
+    /// Un secteur du patch est illisible sur la base (hors du volume).
+    Device { sector: u64, source: BlockDeviceError },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::InvalidHeader => write!(f, "not a patch file"),
+            PatchError::SectorSize { patch, device } => write!(
+                f,
+                "patch uses {}-byte sectors, overlay uses {}",
+                patch, device
+            ),
+            PatchError::Truncated => write!(f, "patch is truncated"),
+            PatchError::Device { sector, .. } => {
+                write!(f, "patch sector {} is outside the image", sector)
+            }
+        }
+    }
+}
+
+impl core::error::Error for PatchError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            PatchError::Device { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Périphérique qui garde ses écritures dans un delta, sans modifier la base.
+///
+/// La base n’a besoin d’être inscriptible que pour `commit`. Sa taille doit
+/// être un multiple de la taille de secteur, le dernier secteur étant lu en
+/// entier avant sa première modification.
+pub struct OverlayDevice<B: BlockDevice> {
+    base: B,
+    sector_size: usize,
+    /// Secteurs modifiés, par numéro.
+    delta: RefCell<BTreeMap<u64, Box<[u8]>>>,
+}
+
+impl<B: BlockDevice> OverlayDevice<B> {
+    /// Overlay par secteurs de 512 octets.
+    pub fn new(base: B) -> Self {
+        Self::with_sector_size(base, 512)
+    }
+
+    /// Overlay par secteurs de `sector_size` octets (puissance de deux).
+    ///
+    /// # Panics
+    ///
+    /// Si `sector_size` n’est pas une puissance de deux.
+    pub fn with_sector_size(base: B, sector_size: usize) -> Self {
+        assert!(sector_size.is_power_of_two(), "sector size must be a power of two");
+        Self {
+            base,
+            sector_size,
+            delta: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn base(&self) -> &B {
+        &self.base
+    }
+
+    pub fn sector_size(&self) -> usize {
+        self.sector_size
+    }
+
+    /// Rend la base ; le delta est perdu.
+    pub fn into_inner(self) -> B {
+        self.base
+    }
+
+    /// Vrai si au moins un secteur a été modifié.
+    pub fn is_dirty(&self) -> bool {
+        !self.delta.borrow().is_empty()
+    }
+
+    /// Nombre de secteurs modifiés.
+    pub fn dirty_sectors(&self) -> usize {
+        self.delta.borrow().len()
+    }
+
+    /// Plages d’octets modifiées, triées ; les secteurs contigus sont
+    /// regroupés.
+    pub fn dirty_ranges(&self) -> Vec<Range<u64>> {
+        let size = self.sector_size as u64;
+        let mut ranges: Vec<Range<u64>> = Vec::new();
+
+        for &sector in self.delta.borrow().keys() {
+            let start = sector * size;
+            match ranges.last_mut() {
+                Some(last) if last.end == start => last.end += size,
+                _ => ranges.push(start..start + size),
+            }
+        }
+        ranges
+    }
+
+    /// Abandonne toutes les modifications.
+    pub fn discard(&self) {
+        self.delta.borrow_mut().clear();
+    }
+
+    /// Écrit les secteurs modifiés sur la base, puis les retire du delta.
+    ///
+    /// En cas d’erreur, les secteurs pas encore écrits restent dans le delta.
+    pub fn commit(&self) -> BlockDeviceResult<()> {
+        let mut delta = self.delta.borrow_mut();
+        while let Some((sector, data)) = delta.pop_first() {
+            if let Err(e) = self.base.write_at(sector * self.sector_size as u64, &data) {
+                delta.insert(sector, data);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Exporte le delta sous forme de patch.
+    pub fn export_patch(&self) -> Vec<u8> {
+        let delta = self.delta.borrow();
+        let mut patch = Vec::with_capacity(PATCH_HEADER + delta.len() * (8 + self.sector_size));
+
+        patch.extend_from_slice(PATCH_MAGIC);
+        patch.extend_from_slice(&(self.sector_size as u32).to_le_bytes());
+        patch.extend_from_slice(&(delta.len() as u32).to_le_bytes());
+        for (sector, data) in delta.iter() {
+            patch.extend_from_slice(&sector.to_le_bytes());
+            patch.extend_from_slice(data);
+        }
+        patch
+    }
+
+    /// Ajoute au delta les secteurs d’un patch, qui remplacent ceux déjà
+    /// modifiés. Le delta n’est pas touché si le patch est invalide.
+    pub fn load_patch(&self, patch: &[u8]) -> Result<(), PatchError> {
+        let header = patch.get(..PATCH_HEADER).ok_or(PatchError::InvalidHeader)?;
+        if &header[..8] != PATCH_MAGIC {
+            return Err(PatchError::InvalidHeader);
+        }
+
+        let sector_size = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+        if sector_size as usize != self.sector_size {
+            return Err(PatchError::SectorSize {
+                patch: sector_size,
+                device: self.sector_size as u32,
+            });
+        }
+
+        let count = u32::from_le_bytes([header[12], header[13], header[14], header[15]]) as usize;
+        let records = patch[PATCH_HEADER..].chunks(8 + self.sector_size);
+        if records.len() != count {
+            return Err(PatchError::Truncated);
+        }
+
+        let mut sectors = Vec::with_capacity(count);
+        let mut probe = vec![0u8; self.sector_size];
+        for record in records {
+            let (number, data) = record.split_at(8.min(record.len()));
+            if data.len() != self.sector_size {
+                return Err(PatchError::Truncated);
+            }
+            let mut raw = [0u8; 8];
+            raw.copy_from_slice(number);
+            let sector = u64::from_le_bytes(raw);
+
+            sector
+                .checked_mul(self.sector_size as u64)
+                .ok_or(BlockDeviceError::OutOfBounds)
+                .and_then(|offset| self.base.read_at(offset, &mut probe))
+                .map_err(|source| PatchError::Device { sector, source })?;
+            sectors.push((sector, Box::from(data)));
+        }
+
+        self.delta.borrow_mut().extend(sectors);
+        Ok(())
+    }
+}
+
+impl<B: BlockDevice> BlockDevice for OverlayDevice<B> {
+    fn size(&self) -> Option<u64> {
+        self.base.size()
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        let delta = self.delta.borrow();
+        if delta.is_empty() {
+            return self.base.read_at(offset, buf);
+        }
+
+        let size = self.sector_size as u64;
+        let end = offset
+            .checked_add(buf.len() as u64)
+            .ok_or(BlockDeviceError::OutOfBounds)?;
+
+        let mut pos = offset;
+        while pos < end {
+            let sector = pos / size;
+            let at = (pos - offset) as usize;
+
+            if let Some(data) = delta.get(&sector) {
+                let within = (pos % size) as usize;
+                let n = (size - pos % size).min(end - pos) as usize;
+                buf[at..at + n].copy_from_slice(&data[within..within + n]);
+                pos += n as u64;
+            } else {
+                // Jusqu’au prochain secteur modifié, en une seule lecture.
+                let next = delta
+                    .range(sector..)
+                    .next()
+                    .map_or(end, |(&dirty, _)| (dirty * size).min(end));
+                self.base.read_at(pos, &mut buf[at..at + (next - pos) as usize])?;
+                pos = next;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_vectored_at(&self, requests: &mut [(u64, &mut [u8])]) -> BlockDeviceResult<()> {
+        if self.delta.borrow().is_empty() {
+            return self.base.read_vectored_at(requests);
+        }
+        for (offset, buf) in requests.iter_mut() {
+            self.read_at(*offset, buf)?;
+        }
+        Ok(())
+    }
+
+    fn write_at(&self, offset: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        let size = self.sector_size as u64;
+        let end = offset
+            .checked_add(buf.len() as u64)
+            .ok_or(BlockDeviceError::OutOfBounds)?;
+        if buf.is_empty() {
+            return Ok(());
+        }
+
+        let mut delta = self.delta.borrow_mut();
+
+        // Copie préalable des secteurs touchés pour la première fois : une
+        // écriture hors de la base échoue sans rien modifier.
+        let mut copies = Vec::new();
+        for sector in offset / size..=(end - 1) / size {
+            if !delta.contains_key(&sector) {
+                let mut data = vec![0u8; self.sector_size].into_boxed_slice();
+                self.base.read_at(sector * size, &mut data)?;
+                copies.push((sector, data));
+            }
+        }
+        delta.extend(copies);
+
+        let mut pos = offset;
+        while pos < end {
+            let sector = pos / size;
+            let within = (pos % size) as usize;
+            let n = (size - pos % size).min(end - pos) as usize;
+            let at = (pos - offset) as usize;
+
+            let data = delta.get_mut(&sector).expect("sector copied above");
+            data[within..within + n].copy_from_slice(&buf[at..at + n]);
+            pos += n as u64;
+        }
+
+        Ok(())
+    }
+}
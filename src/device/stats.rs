@@ -0,0 +1,252 @@
+//! Périphérique qui compte ses accès, et peut en garder la trace.
+//!
+//! `StatsDevice` enveloppe un périphérique sans rien changer aux accès :
+//! nombre de lectures et d’écritures, octets transférés, secteurs distincts
+//! touchés, et part des accès séquentiels (qui commencent là où le précédent
+//! s’est arrêté). La trace optionnelle se sauvegarde en texte, une ligne
+//! `R|W <offset> <longueur>` par accès, et se rejoue sur un autre
+//! périphérique.
+
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::fmt::{self, Write};
+
+use crate::device::block_device::{Access, BlockDevice, BlockDeviceResult};
+
+/// Compteurs d’accès.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IoStats {
+    pub reads: u64,
+    pub writes: u64,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+    /// Secteurs distincts lus ou écrits.
+    pub sectors: u64,
+    /// Accès commençant exactement à la fin du précédent.
+    pub sequential: u64,
+}
+
+impl IoStats {
+    pub fn accesses(&self) -> u64 {
+        self.reads + self.writes
+    }
+
+    /// Accès non séquentiels.
+    pub fn random(&self) -> u64 {
+        self.accesses() - self.sequential
+    }
+}
+
+impl fmt::Display for IoStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} reads ({} bytes), {} writes ({} bytes), {} sectors, {} sequential / {} random",
+            self.reads,
+            self.bytes_read,
+            self.writes,
+            self.bytes_written,
+            self.sectors,
+            self.sequential,
+            self.random()
+        )
+    }
+}
+
+/// Accès enregistré dans une trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceRecord {
+    pub access: Access,
+    pub offset: u64,
+    pub len: usize,
+}
+
+/// Ligne invalide dans une trace texte (numérotée à partir de 1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceError {
+    pub line: usize,
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: expected \"R|W <offset> <length>\"", self.line)
+    }
+}
+
+impl core::error::Error for TraceError {}
+
+/// Périphérique qui compte les accès à sa base.
+pub struct StatsDevice<B: BlockDevice> {
+    base: B,
+    sector_size: u64,
+    stats: RefCell<IoStats>,
+    touched: RefCell<BTreeSet<u64>>,
+    /// Fin du dernier accès, pour repérer les accès séquentiels.
+    last_end: Cell<Option<u64>>,
+    trace: Option<RefCell<Vec<TraceRecord>>>,
+}
+
+impl<B: BlockDevice> StatsDevice<B> {
+    /// Compte les secteurs par 512 octets, sans trace.
+    pub fn new(base: B) -> Self {
+        Self {
+            base,
+            sector_size: 512,
+            stats: RefCell::new(IoStats::default()),
+            touched: RefCell::new(BTreeSet::new()),
+            last_end: Cell::new(None),
+            trace: None,
+        }
+    }
+
+    /// Taille de secteur pour le compte des secteurs distincts.
+    pub fn sector_size(mut self, size: u64) -> Self {
+        self.sector_size = size.max(1);
+        self
+    }
+
+    /// Garde la trace de chaque accès.
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(RefCell::new(Vec::new()));
+        self
+    }
+
+    pub fn base(&self) -> &B {
+        &self.base
+    }
+
+    pub fn into_inner(self) -> B {
+        self.base
+    }
+
+    pub fn stats(&self) -> IoStats {
+        self.stats.borrow().clone()
+    }
+
+    /// Trace depuis le début (ou le dernier `reset`) ; vide sans `with_trace`.
+    pub fn trace(&self) -> Vec<TraceRecord> {
+        self.trace.as_ref().map(|t| t.borrow().clone()).unwrap_or_default()
+    }
+
+    /// Remet les compteurs et la trace à zéro.
+    pub fn reset(&self) {
+        *self.stats.borrow_mut() = IoStats::default();
+        self.touched.borrow_mut().clear();
+        self.last_end.set(None);
+        if let Some(trace) = &self.trace {
+            trace.borrow_mut().clear();
+        }
+    }
+
+    fn record(&self, access: Access, offset: u64, len: usize) {
+        let mut stats = self.stats.borrow_mut();
+        match access {
+            Access::Read => {
+                stats.reads += 1;
+                stats.bytes_read += len as u64;
+            }
+            Access::Write => {
+                stats.writes += 1;
+                stats.bytes_written += len as u64;
+            }
+        }
+
+        if self.last_end.get() == Some(offset) {
+            stats.sequential += 1;
+        }
+        let end = offset.saturating_add(len as u64);
+        self.last_end.set(Some(end));
+
+        if len > 0 {
+            let mut touched = self.touched.borrow_mut();
+            touched.extend(offset / self.sector_size..=(end - 1) / self.sector_size);
+            stats.sectors = touched.len() as u64;
+        }
+
+        if let Some(trace) = &self.trace {
+            trace.borrow_mut().push(TraceRecord { access, offset, len });
+        }
+    }
+}
+
+impl<B: BlockDevice> BlockDevice for StatsDevice<B> {
+    fn size(&self) -> Option<u64> {
+        self.base.size()
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        self.record(Access::Read, offset, buf.len());
+        self.base.read_at(offset, buf)
+    }
+
+    /// Chaque plage compte pour une lecture ; la requête est transmise
+    /// groupée à la base.
+    fn read_vectored_at(&self, requests: &mut [(u64, &mut [u8])]) -> BlockDeviceResult<()> {
+        for (offset, buf) in requests.iter() {
+            self.record(Access::Read, *offset, buf.len());
+        }
+        self.base.read_vectored_at(requests)
+    }
+
+    fn write_at(&self, offset: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        self.record(Access::Write, offset, buf.len());
+        self.base.write_at(offset, buf)
+    }
+}
+
+/// Trace au format texte, une ligne par accès.
+pub fn format_trace(trace: &[TraceRecord]) -> String {
+    let mut text = String::new();
+    for record in trace {
+        let op = match record.access {
+            Access::Read => 'R',
+            Access::Write => 'W',
+        };
+        let _ = writeln!(text, "{} {} {}", op, record.offset, record.len);
+    }
+    text
+}
+
+/// Relit une trace produite par `format_trace` ; les lignes vides sont
+/// ignorées.
+pub fn parse_trace(text: &str) -> Result<Vec<TraceRecord>, TraceError> {
+    let mut trace = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let record = match fields.as_slice() {
+            [] => continue,
+            [op, offset, len] => {
+                let access = match *op {
+                    "R" => Some(Access::Read),
+                    "W" => Some(Access::Write),
+                    _ => None,
+                };
+                access.zip(offset.parse().ok()).zip(len.parse().ok())
+            }
+            _ => None,
+        };
+
+        let ((access, offset), len) = record.ok_or(TraceError { line: i + 1 })?;
+        trace.push(TraceRecord { access, offset, len });
+    }
+    Ok(trace)
+}
+
+/// Rejoue une trace sur `device`.
+///
+/// La trace ne contient pas les données : une écriture réécrit les octets
+/// déjà présents à cet endroit, ce qui reproduit les accès sans modifier le
+/// contenu.
+pub fn replay<D: BlockDevice>(device: &D, trace: &[TraceRecord]) -> BlockDeviceResult<()> {
+    let mut buf = Vec::new();
+    for record in trace {
+        buf.resize(record.len, 0);
+        device.read_at(record.offset, &mut buf)?;
+        if record.access == Access::Write {
+            device.write_at(record.offset, &buf)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,261 @@
+//! Lecture d’un volume FAT32 sur un périphérique asynchrone.
+//!
+//! `AsyncFileSystem` reprend les opérations de lecture de `FileSystem`
+//! (Boot Sector, chaînes de clusters, répertoires, fichiers) pour un
+//! `AsyncBlockDevice`. Seuls les accès au périphérique diffèrent : le
+//! décodage du Boot Sector, des entrées FAT et des slots de répertoire, ainsi
+//! que la résolution des chemins, sont ceux du chemin synchrone.
+
+use alloc::vec::Vec;
+
+use crate::device::async_device::AsyncBlockDevice;
+use crate::fs::backup::{backup_at, backup_locations, checked_primary, BootSource};
+use crate::fs::boot_sector::{BootSector, BootSectorError};
+use crate::fs::cat::CatError;
+use crate::fs::clusters::{cluster_offset, extents, max_chain_len, ClusterError, Extent, Visited, MAX_BATCH};
+use crate::fs::directory::{parse_entries, push_slots, DirectoryEntry, DirectoryError, EntryType};
+use crate::fs::error::{FsError, FsResult};
+use crate::fs::filesystem::MountOptions;
+use crate::fs::fat::{entry_offset, next_in_chain, FatError};
+use crate::fs::path::{components, find_entry, start_cluster, PathError};
+
+/// Lit et valide le Boot Sector depuis un périphérique asynchrone.
+pub async fn read_boot_sector<D: AsyncBlockDevice>(device: &D) -> Result<BootSector, BootSectorError> {
+    let mut sector = [0u8; 512];
+    device.read_at(0, &mut sector).await?;
+    BootSector::parse(&sector)
+}
+
+/// Lit le Boot Sector, ou sa copie si l’original est invalide, comme
+/// `backup::read_boot`.
+pub async fn read_boot<D: AsyncBlockDevice>(device: &D) -> Result<(BootSector, BootSource), BootSectorError> {
+    let mut primary = [0u8; 512];
+    let read = device.read_at(0, &mut primary).await;
+    let checked = read.map_err(BootSectorError::from).and_then(|_| checked_primary(&primary));
+    let error = match checked {
+        Ok(boot) => return Ok((boot, BootSource::Primary)),
+        Err(e) => e,
+    };
+
+    for (offset, expected) in backup_locations(&primary) {
+        let mut sector = [0u8; 512];
+        if device.read_at(offset, &mut sector).await.is_err() {
+            continue;
+        }
+        if let Some(backup) = backup_at(&sector, expected) {
+            return backup.map(|boot| (boot, BootSource::Backup)).map_err(|_| error);
+        }
+    }
+    Err(error)
+}
+
+/// Volume FAT32 monté sur un périphérique asynchrone, en lecture seule.
+pub struct AsyncFileSystem<'a, D: AsyncBlockDevice> {
+    device: &'a D,
+    boot: BootSector,
+    boot_source: BootSource,
+}
+
+impl<'a, D: AsyncBlockDevice> AsyncFileSystem<'a, D> {
+    /// Lit le Boot Sector et monte le volume, vérifié comme par
+    /// `FileSystem::mount`.
+    pub async fn mount(device: &'a D) -> FsResult<Self> {
+        Self::mount_with(device, &MountOptions::default()).await
+    }
+
+    /// Monte le volume selon `options` (voir `FileSystem::mount_with`).
+    pub async fn mount_with(device: &'a D, options: &MountOptions) -> FsResult<Self> {
+        let (boot, boot_source) = read_boot(device).await?;
+        match boot.validate(device.size()) {
+            Err(BootSectorError::Truncated(_)) if options.salvage => {}
+            result => result?,
+        }
+        Ok(Self { device, boot, boot_source })
+    }
+
+    pub fn device(&self) -> &'a D {
+        self.device
+    }
+
+    pub fn boot(&self) -> &BootSector {
+        &self.boot
+    }
+
+    /// Copie du Boot Sector d’où le volume a été monté.
+    pub fn boot_source(&self) -> BootSource {
+        self.boot_source
+    }
+
+    /// Cluster de la racine.
+    pub fn root_cluster(&self) -> u32 {
+        self.boot.root_cluster
+    }
+
+    /// Cluster suivant dans la chaîne (`None` en fin de chaîne), d’après la
+    /// copie active de la FAT.
+    pub async fn next_cluster(&self, cluster: u32) -> Result<Option<u32>, FatError> {
+        if cluster < 2 {
+            return Err(FatError::InvalidCluster);
+        }
+
+        let mut entry = [0u8; 4];
+        let offset = entry_offset(&self.boot, self.boot.active_fat(), cluster)?;
+        self.device.read_at(offset, &mut entry).await?;
+        next_in_chain(u32::from_le_bytes(entry) & 0x0FFF_FFFF)
+    }
+
+    /// Liste les clusters d’une chaîne, dans l’ordre.
+    pub async fn chain(&self, start_cluster: u32) -> Result<Vec<u32>, ClusterError> {
+        let mut clusters = Vec::new();
+        self.walk(start_cluster, &mut clusters).await?;
+        Ok(clusters)
+    }
+
+    /// Lit toutes les entrées d’un répertoire à partir du cluster donné.
+    pub async fn read_dir(&self, start_cluster: u32) -> Result<Vec<DirectoryEntry>, DirectoryError> {
+        let mut buf = alloc::vec![0u8; self.boot.cluster_size()];
+        let mut slots = Vec::new();
+
+        for cluster in self.chain(start_cluster).await? {
+            let offset = cluster_offset(&self.boot, cluster)?;
+            self.device
+                .read_at(offset, &mut buf)
+                .await
+                .map_err(|e| DirectoryError::Cluster(e.into()))?;
+            push_slots(cluster, &buf, &mut slots);
+        }
+
+        parse_entries(slots)
+    }
+
+    /// Liste un répertoire.
+    pub async fn list(&self, path: &str, cwd: u32) -> FsResult<Vec<DirectoryEntry>> {
+        let list = async {
+            let (cluster, entry) = self.resolve(path, cwd).await?;
+            if let Some(e) = entry
+                && !e.entry_type.is_dir()
+            {
+                return Err(PathError::NotADirectory);
+            }
+            self.read_dir_at(cluster).await
+        };
+
+        list.await.map_err(|e| FsError::from(e).with_path(path))
+    }
+
+    /// Lit le contenu complet d’un fichier.
+    pub async fn read_file(&self, path: &str, cwd: u32) -> FsResult<Vec<u8>> {
+        let read = async {
+            let entry = match self.resolve(path, cwd).await {
+                Ok((_, Some(entry))) => entry,
+                Ok((_, None)) | Err(PathError::NotFound) => return Err(CatError::NotFound),
+                Err(e) => return Err(CatError::Path(e)),
+            };
+            if entry.entry_type != EntryType::File {
+                return Err(CatError::NotAFile);
+            }
+
+            let mut data = Vec::new();
+            // Un fichier vide n’a pas de cluster.
+            if entry.start_cluster == 0 && entry.size == 0 {
+                return Ok(data);
+            }
+
+            let cluster = entry.start_cluster;
+            self.read_chain(cluster, &mut data)
+                .await
+                .map_err(|source| CatError::Cluster { cluster, source })?;
+            data.truncate(entry.size as usize);
+            Ok(data)
+        };
+
+        read.await.map_err(|e| FsError::from(e).with_path(path))
+    }
+
+    /// Résout un chemin comme `PathResolver::resolve`.
+    pub async fn resolve(&self, path: &str, cwd: u32) -> Result<(u32, Option<DirectoryEntry>), PathError> {
+        let mut current_cluster = start_cluster(&self.boot, path, cwd)?;
+        let mut last_entry = None;
+
+        for part in components(path) {
+            let entry = find_entry(self.read_dir_at(current_cluster).await?, part)?;
+
+            match entry.entry_type {
+                EntryType::Directory => current_cluster = entry.start_cluster,
+                EntryType::File => {
+                    last_entry = Some(entry);
+                    break;
+                }
+            }
+
+            last_entry = Some(entry);
+        }
+
+        Ok((current_cluster, last_entry))
+    }
+
+    async fn read_dir_at(&self, cluster: u32) -> Result<Vec<DirectoryEntry>, PathError> {
+        self.read_dir(cluster)
+            .await
+            .map_err(|source| PathError::Directory { cluster, source })
+    }
+
+    /// Ajoute à `clusters` ceux de la chaîne, jusqu’à la fin ou à l’erreur.
+    async fn walk(&self, start_cluster: u32, clusters: &mut Vec<u32>) -> Result<(), ClusterError> {
+        let limit = max_chain_len(&self.boot);
+        let mut visited = Visited::default();
+        let mut current = start_cluster;
+
+        loop {
+            if clusters.len() >= limit || !visited.insert(current) {
+                return Err(ClusterError::ChainLoop);
+            }
+            clusters.push(current);
+
+            match self.next_cluster(current).await? {
+                Some(next) => current = next,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Lit une chaîne comme `ClusterReader::read_cluster_chain`, une requête
+    /// par suite de clusters consécutifs (16 Mio au plus) ; une chaîne qui
+    /// boucle est rejetée sans rien lire. En cas d’erreur de lecture, `out`
+    /// reste inchangé.
+    async fn read_chain(&self, start_cluster: u32, out: &mut Vec<u8>) -> Result<(), ClusterError> {
+        let mut chain = Vec::new();
+        let walked = self.walk(start_cluster, &mut chain).await;
+        if walked == Err(ClusterError::ChainLoop) {
+            return walked;
+        }
+
+        let first = out.len();
+        if let Err(e) = self.read_extents(&extents(&chain), out).await {
+            out.truncate(first);
+            return Err(e);
+        }
+
+        walked
+    }
+
+    /// Ajoute à `out` les données de suites de clusters.
+    async fn read_extents(&self, extents: &[Extent], out: &mut Vec<u8>) -> Result<(), ClusterError> {
+        let cluster_size = self.boot.cluster_size() as u64;
+        for extent in extents {
+            let mut offset = cluster_offset(&self.boot, extent.start)?;
+            let mut remaining = extent.length as u64 * cluster_size;
+
+            while remaining > 0 {
+                let n = remaining.min(MAX_BATCH as u64) as usize;
+                let first = out.len();
+                out.resize(first + n, 0);
+                self.device.read_at(offset, &mut out[first..]).await?;
+                offset += n as u64;
+                remaining -= n as u64;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+//! Boot Sector de secours.
+//!
+//! FAT32 garde une copie du Boot Sector dans la zone réservée, au secteur
+//! indiqué par le champ `BPB_BkBootSec` (6 en pratique). Ce module lit le
+//! volume depuis cette copie quand l’original est illisible, compare les deux
+//! champ par champ et restaure l’un à partir de l’autre.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::boot_sector::{BootSector, BootSectorError};
+
+/// Secteur habituel de la copie du Boot Sector.
+pub const BACKUP_BOOT_SECTOR: u16 = 6;
+
+/// Copie du Boot Sector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootSource {
+    /// Secteur 0.
+    Primary,
+    /// Copie de secours de la zone réservée.
+    Backup,
+}
+
+impl fmt::Display for BootSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BootSource::Primary => write!(f, "primary boot sector"),
+            BootSource::Backup => write!(f, "backup boot sector"),
+        }
+    }
+}
+
+/// Champs du Boot Sector : nom, offset et taille.
+const FIELDS: &[(&str, usize, usize)] = &[
+    ("jump", 0, 3),
+    ("oem_name", 3, 8),
+    ("bytes_per_sector", 11, 2),
+    ("sectors_per_cluster", 13, 1),
+    ("reserved_sectors", 14, 2),
+    ("fat_count", 16, 1),
+    ("root_entries", 17, 2),
+    ("total_sectors_16", 19, 2),
+    ("media", 21, 1),
+    ("sectors_per_fat_16", 22, 2),
+    ("sectors_per_track", 24, 2),
+    ("heads", 26, 2),
+    ("hidden_sectors", 28, 4),
+    ("total_sectors", 32, 4),
+    ("sectors_per_fat", 36, 4),
+    ("ext_flags", 40, 2),
+    ("fs_version", 42, 2),
+    ("root_cluster", 44, 4),
+    ("fs_info_sector", 48, 2),
+    ("backup_boot_sector", 50, 2),
+    ("reserved", 52, 12),
+    ("drive_number", 64, 1),
+    ("reserved1", 65, 1),
+    ("boot_signature", 66, 1),
+    ("volume_id", 67, 4),
+    ("volume_label", 71, 11),
+    ("fs_type", 82, 8),
+    ("boot_code", 90, 420),
+    ("signature", 510, 2),
+];
+
+/// Champ qui diffère entre le Boot Sector et sa copie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootFieldDiff {
+    pub field: &'static str,
+    pub offset: usize,
+    pub primary: Vec<u8>,
+    pub backup: Vec<u8>,
+}
+
+impl fmt::Display for BootFieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Les champs numériques (4 octets au plus) sont affichés en valeur.
+        if self.primary.len() <= 4 {
+            let value = |bytes: &[u8]| bytes.iter().rev().fold(0u32, |v, &b| (v << 8) | b as u32);
+            write!(
+                f,
+                "{}: primary {:#x}, backup {:#x}",
+                self.field,
+                value(&self.primary),
+                value(&self.backup)
+            )
+        } else {
+            write!(f, "{}: differs", self.field)
+        }
+    }
+}
+
+/// Lit le Boot Sector, ou sa copie si l’original est invalide.
+///
+/// Une copie n’est retenue que si elle se décode et décrit une géométrie
+/// cohérente ; sinon l’erreur de l’original est rendue.
+pub fn read_boot<D: BlockDevice>(device: &D) -> Result<(BootSector, BootSource), BootSectorError> {
+    let mut primary = [0u8; 512];
+    let checked = device
+        .read_at(0, &mut primary)
+        .map_err(BootSectorError::from)
+        .and_then(|_| checked_primary(&primary));
+    let error = match checked {
+        Ok(boot) => return Ok((boot, BootSource::Primary)),
+        Err(e) => e,
+    };
+
+    for (offset, expected) in backup_locations(&primary) {
+        let mut sector = [0u8; 512];
+        if device.read_at(offset, &mut sector).is_err() {
+            continue;
+        }
+        if let Some(backup) = backup_at(&sector, expected) {
+            return backup.map(|boot| (boot, BootSource::Backup)).map_err(|_| error);
+        }
+    }
+    Err(error)
+}
+
+/// Original retenu par `read_boot` : décodé et de géométrie cohérente.
+pub(crate) fn checked_primary(sector: &[u8; 512]) -> Result<BootSector, BootSectorError> {
+    let boot = BootSector::parse(sector)?;
+    boot.validate(None)?;
+    Ok(boot)
+}
+
+/// Copie lue à un emplacement de `backup_locations`.
+///
+/// `None` si elle ne se décode pas ou n’annonce pas la taille de secteur
+/// attendue : l’emplacement suivant est essayé. Sinon, la première copie
+/// décodable est la seule candidate, retenue si elle est cohérente.
+pub(crate) fn backup_at(
+    sector: &[u8; 512],
+    expected: Option<u16>,
+) -> Option<Result<BootSector, BootSectorError>> {
+    match BootSector::parse(sector) {
+        Ok(boot) if matches_location(&boot, expected) => Some(boot.validate(None).map(|()| boot)),
+        _ => None,
+    }
+}
+
+/// Lit la copie de secours ; rend aussi son offset disque.
+///
+/// Le secteur qu’indique l’original, s’il est lisible, est essayé en
+/// premier ; à défaut, le secteur 6 (la taille de secteur est alors celle de
+/// la copie).
+pub fn read_backup<D: BlockDevice>(device: &D) -> Result<(BootSector, u64), BootSectorError> {
+    // Un original illisible laisse seulement les emplacements habituels.
+    let mut primary = [0u8; 512];
+    let _ = device.read_at(0, &mut primary);
+
+    let mut error = BootSectorError::NoBackup;
+    for (offset, expected) in backup_locations(&primary) {
+        match read_at(device, offset) {
+            Ok(boot) if matches_location(&boot, expected) => return Ok((boot, offset)),
+            Ok(_) => {}
+            Err(e) => error = e,
+        }
+    }
+    Err(error)
+}
+
+/// Emplacements où chercher la copie, dans l’ordre : celui qu’indique
+/// l’original `primary` s’il se décode, puis le secteur 6 pour chaque taille
+/// de secteur, avec la taille que la copie doit alors annoncer.
+pub(crate) fn backup_locations(primary: &[u8; 512]) -> impl Iterator<Item = (u64, Option<u16>)> {
+    let field = field_offset(primary).map(|offset| (offset, None));
+    let probes = [512u16, 1024, 2048, 4096]
+        .into_iter()
+        .map(|bps| (BACKUP_BOOT_SECTOR as u64 * bps as u64, Some(bps)));
+    field.into_iter().chain(probes)
+}
+
+/// Vrai si la copie lue à un emplacement de `backup_locations` annonce la
+/// taille de secteur attendue.
+fn matches_location(boot: &BootSector, expected: Option<u16>) -> bool {
+    expected.is_none_or(|bps| boot.bytes_per_sector == bps)
+}
+
+/// Compare le Boot Sector et sa copie, champ par champ.
+///
+/// Les deux secteurs sont comparés bruts : l’original peut être invalide.
+pub fn compare<D: BlockDevice>(device: &D) -> Result<Vec<BootFieldDiff>, BootSectorError> {
+    let offset = match read_backup(device) {
+        Ok((_, offset)) => offset,
+        Err(_) => backup_offset_from_primary(device).ok_or(BootSectorError::NoBackup)?,
+    };
+
+    let mut primary = [0u8; 512];
+    let mut backup = [0u8; 512];
+    device.read_at(0, &mut primary)?;
+    device.read_at(offset, &mut backup)?;
+
+    Ok(FIELDS
+        .iter()
+        .filter(|&&(_, at, len)| primary[at..at + len] != backup[at..at + len])
+        .map(|&(field, at, len)| BootFieldDiff {
+            field,
+            offset: at,
+            primary: primary[at..at + len].to_vec(),
+            backup: backup[at..at + len].to_vec(),
+        })
+        .collect())
+}
+
+/// Recopie le secteur `from` (qui doit être valide) sur l’autre copie.
+///
+/// La copie de secours n’est écrite qu’à l’emplacement indiqué par
+/// `BPB_BkBootSec`, dans la zone réservée ; sinon `NoBackup`.
+pub fn restore<D: BlockDevice>(device: &D, from: BootSource) -> Result<(), BootSectorError> {
+    let (boot, source, target) = match from {
+        BootSource::Backup => {
+            let (boot, offset) = read_backup(device)?;
+            (boot, offset, 0)
+        }
+        BootSource::Primary => {
+            let boot = BootSector::read(device)?;
+            // Sans emplacement valide dans la zone réservée, écrire au
+            // secteur 6 pourrait écraser la FAT.
+            let target = backup_offset_from_primary(device).ok_or(BootSectorError::NoBackup)?;
+            (boot, 0, target)
+        }
+    };
+    boot.validate(None)?;
+
+    let mut sector = vec![0u8; boot.bytes_per_sector as usize];
+    device.read_at(source, &mut sector)?;
+    device.write_at(target, &sector)?;
+    Ok(())
+}
+
+/// Offset de la copie d’après le champ `BPB_BkBootSec` d’un original
+/// décodable ; `None` si le champ est absent ou hors de la zone réservée.
+fn backup_offset_from_primary<D: BlockDevice>(device: &D) -> Option<u64> {
+    let mut primary = [0u8; 512];
+    device.read_at(0, &mut primary).ok()?;
+    field_offset(&primary)
+}
+
+fn field_offset(primary: &[u8; 512]) -> Option<u64> {
+    let boot = BootSector::parse(primary).ok()?;
+    let sector = u16::from_le_bytes([primary[50], primary[51]]);
+    (sector != 0 && sector < boot.reserved_sectors)
+        .then(|| sector as u64 * boot.bytes_per_sector as u64)
+}
+
+fn read_at<D: BlockDevice>(device: &D, offset: u64) -> Result<BootSector, BootSectorError> {
+    let mut sector = [0u8; 512];
+    device.read_at(offset, &mut sector)?;
+    BootSector::parse(&sector)
+}
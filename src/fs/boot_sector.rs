@@ -1,5 +1,7 @@
 //! Lecture et validation du Boot Sector FAT32.
 
+use core::fmt;
+
 use crate::device::block_device::{BlockDevice, BlockDeviceError};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -9,7 +11,13 @@ pub struct BootSector {
     pub reserved_sectors: u16,
     pub fat_count: u8,
     pub sectors_per_fat: u32,
+    /// Drapeaux étendus (`BPB_ExtFlags`) : bits 0-3, FAT active ; bit 7,
+    /// miroir désactivé.
+    pub ext_flags: u16,
     pub root_cluster: u32,
+    pub total_sectors: u32,
+    /// Secteur du FSInfo (0 ou 0xFFFF si absent).
+    pub fs_info_sector: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -19,6 +27,22 @@ pub enum BootSectorError {
     InvalidBytesPerSector,
     InvalidSectorsPerCluster,
     NotFAT32,
+    /// Régions réservée, FAT et données incohérentes (FAT vide, aucune
+    /// donnée, ...).
+    InvalidGeometry,
+    /// Le volume déborde du périphérique.
+    Truncated(Truncation),
+    /// Aucune copie de secours lisible.
+    NoBackup,
+}
+
+/// Volume plus grand que le périphérique qui le porte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Truncation {
+    /// Taille du volume d’après le Boot Sector, en octets.
+    pub volume: u64,
+    /// Taille du périphérique, en octets.
+    pub device: u64,
 }
 
 impl From<BlockDeviceError> for BootSectorError {
@@ -27,12 +51,44 @@ impl From<BlockDeviceError> for BootSectorError {
     }
 }
 
+impl fmt::Display for BootSectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BootSectorError::Io(_) => write!(f, "cannot read boot sector"),
+            BootSectorError::InvalidSignature => write!(f, "invalid boot sector signature"),
+            BootSectorError::InvalidBytesPerSector => write!(f, "invalid bytes per sector"),
+            BootSectorError::InvalidSectorsPerCluster => write!(f, "invalid sectors per cluster"),
+            BootSectorError::NotFAT32 => write!(f, "not a FAT32 volume"),
+            BootSectorError::InvalidGeometry => write!(f, "invalid volume geometry"),
+            BootSectorError::Truncated(t) => write!(
+                f,
+                "image is truncated: volume needs {} bytes, device has {}",
+                t.volume, t.device
+            ),
+            BootSectorError::NoBackup => write!(f, "no backup boot sector"),
+        }
+    }
+}
+
+impl core::error::Error for BootSectorError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            BootSectorError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 impl BootSector {
     /// Lit et valide le Boot Sector FAT32 depuis le périphérique.
     pub fn read<D: BlockDevice>(device: &D) -> Result<Self, BootSectorError> {
         let mut sector = [0u8; 512];
         device.read_at(0, &mut sector)?;
+        Self::parse(&sector)
+    }
 
+    /// Valide et décode un Boot Sector déjà lu.
+    pub fn parse(sector: &[u8; 512]) -> Result<Self, BootSectorError> {
         // Signature de fin (0x55AA)
         if sector[510] != 0x55 || sector[511] != 0xAA {
             return Err(BootSectorError::InvalidSignature);
@@ -51,6 +107,13 @@ impl BootSector {
         let reserved_sectors = u16::from_le_bytes([sector[14], sector[15]]);
         let fat_count = sector[16];
 
+        let total_sectors_16 = u16::from_le_bytes([sector[19], sector[20]]);
+        let total_sectors = if total_sectors_16 != 0 {
+            total_sectors_16 as u32
+        } else {
+            u32::from_le_bytes([sector[32], sector[33], sector[34], sector[35]])
+        };
+
         let sectors_per_fat_16 = u16::from_le_bytes([sector[22], sector[23]]);
         if sectors_per_fat_16 != 0 {
             return Err(BootSectorError::NotFAT32);
@@ -58,8 +121,10 @@ impl BootSector {
 
         let sectors_per_fat =
             u32::from_le_bytes([sector[36], sector[37], sector[38], sector[39]]);
+        let ext_flags = u16::from_le_bytes([sector[40], sector[41]]);
         let root_cluster =
             u32::from_le_bytes([sector[44], sector[45], sector[46], sector[47]]);
+        let fs_info_sector = u16::from_le_bytes([sector[48], sector[49]]);
 
         Ok(Self {
             bytes_per_sector,
@@ -67,7 +132,83 @@ impl BootSector {
             reserved_sectors,
             fat_count,
             sectors_per_fat,
+            ext_flags,
             root_cluster,
+            total_sectors,
+            fs_info_sector,
         })
     }
+
+    /// Vérifie que les régions décrites tiennent dans le volume, et le
+    /// volume dans le périphérique de `device_size` octets (s’il est connu).
+    ///
+    /// Un volume tronqué est signalé en dernier : les autres erreurs rendent
+    /// la géométrie inutilisable, pas celle-ci.
+    pub fn validate(&self, device_size: Option<u64>) -> Result<(), BootSectorError> {
+        if self.reserved_sectors == 0
+            || self.fat_count == 0
+            || self.sectors_per_fat == 0
+            || self.data_start_sector() >= self.total_sectors as u64
+            || self.active_fat() >= self.fat_count
+        {
+            return Err(BootSectorError::InvalidGeometry);
+        }
+
+        let volume = self.total_sectors as u64 * self.bytes_per_sector as u64;
+        match device_size {
+            Some(device) if device < volume => {
+                Err(BootSectorError::Truncated(Truncation { volume, device }))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Vrai si les écritures vont dans toutes les copies de la FAT (bit 7
+    /// des drapeaux étendus à 0).
+    pub fn mirrored(&self) -> bool {
+        self.ext_flags & 0x0080 == 0
+    }
+
+    /// Copie de la FAT qui fait foi : la première tant que le miroir est
+    /// actif, celle des bits 0-3 sinon.
+    pub fn active_fat(&self) -> u8 {
+        if self.mirrored() {
+            0
+        } else {
+            (self.ext_flags & 0x000F) as u8
+        }
+    }
+
+    /// Taille d’un cluster en octets.
+    pub fn cluster_size(&self) -> usize {
+        self.bytes_per_sector as usize * self.sectors_per_cluster as usize
+    }
+
+    /// Offset disque (en octets) de la copie `copy` de la FAT.
+    pub fn fat_offset(&self, copy: u8) -> u64 {
+        (self.reserved_sectors as u64 + copy as u64 * self.sectors_per_fat as u64)
+            * self.bytes_per_sector as u64
+    }
+
+    /// Premier secteur de la région de données (cluster 2).
+    pub fn data_start_sector(&self) -> u64 {
+        self.reserved_sectors as u64 + self.fat_count as u64 * self.sectors_per_fat as u64
+    }
+
+    /// Nombre de clusters de données adressables.
+    ///
+    /// Borné à la fois par la taille du volume et par la taille de la FAT.
+    pub fn cluster_count(&self) -> u32 {
+        let data_sectors = (self.total_sectors as u64).saturating_sub(self.data_start_sector());
+        let by_volume = data_sectors / self.sectors_per_cluster.max(1) as u64;
+        let by_fat = (self.sectors_per_fat as u64 * self.bytes_per_sector as u64 / 4)
+            .saturating_sub(2);
+
+        by_volume.min(by_fat).min(0x0FFF_FFF5) as u32
+    }
+
+    /// Plus grand numéro de cluster valide.
+    pub fn max_cluster(&self) -> u32 {
+        self.cluster_count() + 1
+    }
 }
@@ -0,0 +1,212 @@
+//! Construction déclarative d’une image : paramètres du volume et liste
+//! d’entrées (répertoires et fichiers avec leur contenu).
+//!
+//! Le résultat ne dépend que de la description : l’identifiant du volume et
+//! les dates sont fixés par l’appelant, les entrées sont créées dans l’ordre
+//! donné et les fichiers alloués de façon contiguë à partir du début du
+//! volume. Sur un périphérique rempli de zéros, deux constructions donnent
+//! donc la même image, octet pour octet.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::boot_sector::BootSector;
+use crate::fs::clusters::ClusterReader;
+use crate::fs::create::{Create, CreateError, EntryTimes};
+use crate::fs::directory::{DirectoryReader, ATTR_ARCHIVE};
+use crate::fs::error::FsResult;
+use crate::fs::fat::Fat;
+use crate::fs::format::{format, FormatOptions};
+use crate::fs::path::PathError;
+use crate::fs::timestamp::DateTime;
+
+/// Nature d’une entrée à créer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeKind {
+    Directory,
+    File(Vec<u8>),
+}
+
+/// Entrée à créer dans l’image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// Chemin absolu (`/a/b`), composantes en noms longs.
+    pub path: String,
+    pub kind: NodeKind,
+    /// Attributs lecture seule, caché, système et archive (`ATTR_*`).
+    pub attributes: u8,
+    /// Date de l’entrée ; `None` : date commune de l’image.
+    pub time: Option<DateTime>,
+}
+
+impl Node {
+    pub fn directory(path: &str) -> Self {
+        Self {
+            path: String::from(path),
+            kind: NodeKind::Directory,
+            attributes: 0,
+            time: None,
+        }
+    }
+
+    pub fn file(path: &str, contents: impl Into<Vec<u8>>) -> Self {
+        Self {
+            path: String::from(path),
+            kind: NodeKind::File(contents.into()),
+            attributes: ATTR_ARCHIVE,
+            time: None,
+        }
+    }
+}
+
+/// Description d’une image à construire.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageBuilder {
+    size: u64,
+    format: FormatOptions,
+    time: Option<DateTime>,
+    nodes: Vec<Node>,
+}
+
+impl ImageBuilder {
+    /// Image de `size` octets, formatée avec les options par défaut.
+    pub fn new(size: u64) -> Self {
+        Self {
+            size,
+            format: FormatOptions::default(),
+            time: None,
+            nodes: Vec::new(),
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn format_options(mut self, options: FormatOptions) -> Self {
+        self.format = options;
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.format.label = Some(String::from(label));
+        self
+    }
+
+    pub fn volume_id(mut self, volume_id: u32) -> Self {
+        self.format.volume_id = volume_id;
+        self
+    }
+
+    /// Date des entrées qui n’en précisent pas ; sans elle, leurs dates
+    /// restent nulles.
+    pub fn time(mut self, time: DateTime) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    pub fn directory(self, path: &str) -> Self {
+        self.node(Node::directory(path))
+    }
+
+    pub fn file(self, path: &str, contents: impl Into<Vec<u8>>) -> Self {
+        self.node(Node::file(path, contents))
+    }
+
+    /// Ajoute une entrée. Les répertoires parents absents sont créés à la
+    /// volée avec les attributs et la date par défaut : pour les préciser,
+    /// déclarer le répertoire avant son contenu.
+    pub fn node(mut self, node: Node) -> Self {
+        self.nodes.push(node);
+        self
+    }
+
+    /// Formate `device` et y crée les entrées décrites.
+    ///
+    /// Seules les zones écrites le sont : pour une image reproductible, le
+    /// périphérique doit être rempli de zéros au départ.
+    pub fn build<D: BlockDevice>(&self, device: &D) -> FsResult<BootSector> {
+        let boot = format(device, self.size, &self.format)?;
+        let fat = Fat::new(device, &boot);
+        let clusters = ClusterReader::new(device, &boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let create = Create::new(&boot, &fat, &clusters, &dirs);
+
+        // Entrées créées, par chemin en majuscules (FAT ignore la casse) :
+        // cluster des répertoires, `None` pour les fichiers.
+        let mut created = BTreeMap::new();
+
+        for node in &self.nodes {
+            self.add(&create, boot.root_cluster, &mut created, node)
+                .map_err(|e| e.with_path(&node.path))?;
+        }
+
+        Ok(boot)
+    }
+
+    fn add<D: BlockDevice>(
+        &self,
+        create: &Create<'_, D>,
+        root: u32,
+        created: &mut BTreeMap<String, Option<u32>>,
+        node: &Node,
+    ) -> FsResult<()> {
+        let parts: Vec<&str> = node.path.split('/').filter(|p| !p.is_empty()).collect();
+        let Some((name, parents)) = parts.split_last() else {
+            return Err(CreateError::InvalidName.into());
+        };
+
+        let mut parent = root;
+        let mut key = String::new();
+        for part in parents {
+            key.push('/');
+            key.push_str(&part.to_uppercase());
+            parent = match created.get(&key) {
+                Some(Some(cluster)) => *cluster,
+                Some(None) => return Err(PathError::NotADirectory.into()),
+                None => {
+                    let dir = create.mkdir(parent, part, &self.times(None))?;
+                    created.insert(key.clone(), Some(dir.cluster));
+                    dir.cluster
+                }
+            };
+        }
+
+        key.push('/');
+        key.push_str(&name.to_uppercase());
+        let times = self.times(node.time);
+        match &node.kind {
+            NodeKind::Directory => {
+                let dir = create.mkdir(parent, name, &times)?;
+                if node.attributes != 0 {
+                    create.set_attributes(dir.location, node.attributes)?;
+                }
+                created.insert(key, Some(dir.cluster));
+            }
+            NodeKind::File(contents) => {
+                let file = create.create_file(parent, name, contents.len() as u64, &times)?;
+                if let Err(e) = create.write_at(&file, 0, contents) {
+                    create.discard(&file);
+                    return Err(e.into());
+                }
+                let location = create.finish(&file)?;
+                if node.attributes != ATTR_ARCHIVE {
+                    create.set_attributes(location, node.attributes)?;
+                }
+                created.insert(key, None);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn times(&self, time: Option<DateTime>) -> EntryTimes {
+        time.or(self.time).map(EntryTimes::all).unwrap_or_default()
+    }
+}
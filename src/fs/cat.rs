@@ -1,15 +1,50 @@
 use alloc::vec::Vec;
+use core::fmt;
 
 use crate::device::block_device::BlockDevice;
-use crate::fs::clusters::ClusterReader;
+use crate::fs::clusters::{ClusterError, ClusterReader};
 use crate::fs::path::{PathResolver, PathError};
-use crate::fs::directory::EntryType;
+use crate::fs::directory::{DirectoryEntry, EntryType};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CatError {
     NotFound,
     NotAFile,
-    Io,
+    /// Échec de la résolution du chemin (autre que « introuvable »).
+    Path(PathError),
+    /// Échec de lecture de la chaîne de clusters du fichier.
+    Cluster {
+        cluster: u32,
+        source: ClusterError,
+    },
+    /// La chaîne de clusters s’arrête avant la taille déclarée du fichier.
+    ShortChain { size: u64, readable: u64 },
+}
+
+impl fmt::Display for CatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatError::NotFound => write!(f, "no such file"),
+            CatError::NotAFile => write!(f, "not a regular file"),
+            CatError::Path(_) => write!(f, "cannot resolve path"),
+            CatError::Cluster { cluster, .. } => {
+                write!(f, "cannot read file data starting at cluster {}", cluster)
+            }
+            CatError::ShortChain { size, readable } => {
+                write!(f, "cluster chain ends after {} of {} bytes", readable, size)
+            }
+        }
+    }
+}
+
+impl core::error::Error for CatError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            CatError::Path(e) => Some(e),
+            CatError::Cluster { source, .. } => Some(source),
+            _ => None,
+        }
+    }
 }
 
 pub struct Cat<'a, D: BlockDevice> {
@@ -29,10 +64,100 @@ impl<'a, D: BlockDevice> Cat<'a, D> {
     }
 
     pub fn cat(&self, path: &str, cwd: u32) -> Result<Vec<u8>, CatError> {
+        let entry = self.file_entry(path, cwd)?;
+
+        let mut data = Vec::new();
+        // Un fichier vide n’a pas de cluster.
+        if entry.start_cluster == 0 && entry.size == 0 {
+            return Ok(data);
+        }
+
+        self.cluster_reader
+            .read_cluster_chain(entry.start_cluster, &mut data)
+            .map_err(|source| CatError::Cluster {
+                cluster: entry.start_cluster,
+                source,
+            })?;
+
+        // Respect strict de la taille FAT
+        data.truncate(entry.size as usize);
+
+        Ok(data)
+    }
+
+    /// Ouvre un fichier pour des lectures partielles : seule la FAT est
+    /// parcourue, aucune donnée n’est lue.
+    pub fn open(&self, path: &str, cwd: u32) -> Result<OpenFile, CatError> {
+        self.open_entry(self.file_entry(path, cwd)?)
+    }
+
+    /// Ouvre le fichier décrit par une entrée déjà lue.
+    pub fn open_entry(&self, entry: DirectoryEntry) -> Result<OpenFile, CatError> {
+        if entry.entry_type != EntryType::File {
+            return Err(CatError::NotAFile);
+        }
+
+        let chain = if entry.start_cluster == 0 {
+            Vec::new()
+        } else {
+            self.cluster_reader
+                .chain(entry.start_cluster)
+                .map_err(|source| CatError::Cluster {
+                    cluster: entry.start_cluster,
+                    source,
+                })?
+        };
+
+        Ok(OpenFile { entry, chain })
+    }
+
+    /// Lit à partir de `offset` dans un fichier ouvert ; retourne le nombre
+    /// d’octets lus, plus petit que `buf` en fin de fichier (ou de chaîne).
+    pub fn read_at(&self, file: &OpenFile, offset: u64, buf: &mut [u8]) -> Result<usize, CatError> {
+        let cluster_size = self.cluster_reader.cluster_size() as u64;
+        let end = file.size().min(offset.saturating_add(buf.len() as u64));
+
+        let mut pos = offset;
+        while pos < end {
+            let Some(&cluster) = file.chain.get((pos / cluster_size) as usize) else {
+                break; // chaîne plus courte que la taille déclarée
+            };
+            let index = (pos / cluster_size) as usize;
+            let within = pos % cluster_size;
+            // Les clusters consécutifs sur le disque sont lus d’un bloc ; la
+            // suite n’est examinée que sur les clusters utiles à cet appel.
+            let needed = (end - pos + within).div_ceil(cluster_size) as usize;
+            let run = file.chain[index..]
+                .iter()
+                .take(needed)
+                .zip(cluster..)
+                .take_while(|&(&c, expected)| c == expected)
+                .count() as u64;
+            let n = (run * cluster_size - within).min(end - pos) as usize;
+            let at = (pos - offset) as usize;
+
+            self.cluster_reader
+                .read_in_run(cluster, within, &mut buf[at..at + n])
+                .map_err(|source| CatError::Cluster { cluster, source })?;
+            pos += n as u64;
+        }
+
+        Ok(pos.saturating_sub(offset) as usize)
+    }
+
+    /// Octets lisibles d’un fichier ouvert : sa taille, bornée par ce que
+    /// contient sa chaîne.
+    pub fn readable(&self, file: &OpenFile) -> u64 {
+        let allocated = file.chain.len() as u64 * self.cluster_reader.cluster_size() as u64;
+        file.size().min(allocated)
+    }
+
+    /// Entrée du fichier désigné par `path`.
+    fn file_entry(&self, path: &str, cwd: u32) -> Result<DirectoryEntry, CatError> {
         let (_parent, entry_opt) =
             self.resolver.resolve(path, cwd).map_err(|e| match e {
                 PathError::NotFound => CatError::NotFound,
-                _ => CatError::Io,
+                e => CatError::Path(e),
             })?;
 
         let entry = entry_opt.ok_or(CatError::NotFound)?;
@@ -41,15 +166,19 @@ impl<'a, D: BlockDevice> Cat<'a, D> {
             return Err(CatError::NotAFile);
         }
 
-        let mut data = Vec::new();
-
-        self.cluster_reader
-            .read_cluster_chain(entry.start_cluster, &mut data)
-            .map_err(|_| CatError::Io)?;
+        Ok(entry)
+    }
+}
 
-        // Respect strict de la taille FAT
-        data.truncate(entry.size as usize);
+/// Fichier ouvert : son entrée et sa chaîne de clusters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenFile {
+    pub entry: DirectoryEntry,
+    chain: Vec<u32>,
+}
 
-        Ok(data)
+impl OpenFile {
+    pub fn size(&self) -> u64 {
+        self.entry.size as u64
     }
 }
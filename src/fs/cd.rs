@@ -1,12 +1,34 @@
+use core::fmt;
+
 use crate::device::block_device::BlockDevice;
 use crate::fs::path::{PathResolver, PathError};
 use crate::fs::directory::EntryType;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CdError {
     NotFound,
     NotADirectory,
-    Io,
+    /// Échec de la résolution du chemin (autre que « introuvable »).
+    Path(PathError),
+}
+
+impl fmt::Display for CdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CdError::NotFound => write!(f, "no such directory"),
+            CdError::NotADirectory => write!(f, "not a directory"),
+            CdError::Path(_) => write!(f, "cannot resolve path"),
+        }
+    }
+}
+
+impl core::error::Error for CdError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            CdError::Path(e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
 pub struct Cd<'a, D: BlockDevice> {
@@ -22,7 +44,7 @@ impl<'a, D: BlockDevice> Cd<'a, D> {
         let (cluster, entry) =
             self.resolver.resolve(path, cwd).map_err(|e| match e {
                 PathError::NotFound => CdError::NotFound,
-                _ => CdError::Io,
+                e => CdError::Path(e),
             })?;
 
         match entry {
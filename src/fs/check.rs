@@ -0,0 +1,906 @@
+//! Vérification de cohérence d’un volume FAT32 (équivalent de `fsck`).
+//!
+//! Le vérificateur parcourt tous les répertoires et toutes les chaînes de
+//! clusters depuis la racine, puis confronte le résultat à la FAT et au
+//! FSInfo. En mode réparation (périphérique inscriptible), il corrige ce que
+//! corrige `dosfsck` : chaînes perdues libérées ou sauvées en `FSCKnnnn.REC`,
+//! chaînes tronquées, copies de FAT resynchronisées, FSInfo recalculé,
+//! volume noté comme démonté proprement (FAT[1]).
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::boot_sector::BootSector;
+use crate::fs::clusters::ClusterReader;
+use crate::fs::directory::{
+    is_valid_short_name, DirectoryReader, DirectorySlot, SlotLocation, ATTR_ARCHIVE,
+    ATTR_DIRECTORY,
+};
+use crate::fs::error::FsResult;
+use crate::fs::fat::{follow_chain, ChainEnd, Fat, FatError, VolumeFlags, FAT_BAD, FAT_EOC, FAT_FREE};
+use crate::fs::fsinfo::{FsInfo, FsInfoError, FSINFO_UNKNOWN};
+
+/// Traitement des chaînes perdues en mode réparation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LostChainAction {
+    /// Marque les clusters comme libres.
+    Free,
+    /// Sauve chaque chaîne dans un fichier `FSCKnnnn.REC` à la racine.
+    Save,
+}
+
+/// Options de vérification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckOptions {
+    /// Corrige les problèmes trouvés (le périphérique doit être inscriptible).
+    pub repair: bool,
+    pub lost_chains: LostChainAction,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            repair: false,
+            lost_chains: LostChainAction::Save,
+        }
+    }
+}
+
+/// Problème détecté sur le volume.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// Une copie de la FAT diffère de la copie active.
+    FatCopyMismatch {
+        copy: u8,
+        entries: u32,
+        first_cluster: u32,
+    },
+    /// Le premier cluster d’une entrée est hors du volume.
+    InvalidStartCluster { path: String, cluster: u32 },
+    /// La chaîne mène à une entrée FAT libre, défectueuse ou hors limites.
+    BrokenChain { path: String, cluster: u32, value: u32 },
+    /// La chaîne revient sur l’un de ses propres clusters.
+    ChainLoop { path: String, cluster: u32 },
+    /// Le cluster appartient déjà à une autre entrée.
+    CrossLinked {
+        path: String,
+        cluster: u32,
+        other: String,
+    },
+    /// La chaîne est plus courte que la taille du fichier.
+    ChainTooShort {
+        path: String,
+        size: u32,
+        allocated: u64,
+    },
+    /// La chaîne est plus longue que nécessaire pour la taille du fichier.
+    ChainTooLong {
+        path: String,
+        size: u32,
+        allocated: u64,
+    },
+    /// Entrée `.` ou `..` absente en tête de sous-répertoire.
+    MissingDotEntry { path: String, name: &'static str },
+    /// Entrée `.` ou `..` pointant vers le mauvais cluster.
+    InvalidDotEntry {
+        path: String,
+        name: &'static str,
+        cluster: u32,
+        expected: u32,
+    },
+    /// Nom 8.3 contenant des caractères interdits.
+    BadShortName { path: String, raw: [u8; 11] },
+    /// Chaîne allouée dans la FAT mais référencée par aucune entrée.
+    LostChain { start: u32, clusters: u32 },
+    /// Le secteur FSInfo est illisible ou sans signature.
+    FsInfoInvalid,
+    /// Nombre de clusters libres du FSInfo erroné.
+    FsInfoFreeCount { recorded: u32, actual: u32 },
+    /// Indication de prochain cluster libre hors du volume.
+    FsInfoNextFree { recorded: u32 },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::FatCopyMismatch {
+                copy,
+                entries,
+                first_cluster,
+            } => write!(
+                f,
+                "FAT copy {} differs from the active FAT in {} entries (first at cluster {})",
+                copy, entries, first_cluster
+            ),
+            Issue::InvalidStartCluster { path, cluster } => {
+                write!(f, "{}: invalid start cluster {}", path, cluster)
+            }
+            Issue::BrokenChain {
+                path,
+                cluster,
+                value,
+            } => write!(
+                f,
+                "{}: chain broken after cluster {} (FAT value {:#010x})",
+                path, cluster, value
+            ),
+            Issue::ChainLoop { path, cluster } => {
+                write!(f, "{}: chain loops back to cluster {}", path, cluster)
+            }
+            Issue::CrossLinked {
+                path,
+                cluster,
+                other,
+            } => write!(f, "{}: cluster {} cross-linked with {}", path, cluster, other),
+            Issue::ChainTooShort {
+                path,
+                size,
+                allocated,
+            } => write!(
+                f,
+                "{}: size is {} bytes but chain holds only {} bytes",
+                path, size, allocated
+            ),
+            Issue::ChainTooLong {
+                path,
+                size,
+                allocated,
+            } => write!(
+                f,
+                "{}: size is {} bytes but chain holds {} bytes",
+                path, size, allocated
+            ),
+            Issue::MissingDotEntry { path, name } => {
+                write!(f, "{}: missing \"{}\" entry", path, name)
+            }
+            Issue::InvalidDotEntry {
+                path,
+                name,
+                cluster,
+                expected,
+            } => write!(
+                f,
+                "{}: \"{}\" points to cluster {} instead of {}",
+                path, name, cluster, expected
+            ),
+            Issue::BadShortName { path, raw } => {
+                write!(f, "{}: bad short name {:?}", path, String::from_utf8_lossy(&raw[..]))
+            }
+            Issue::LostChain { start, clusters } => {
+                write!(f, "lost chain of {} clusters at cluster {}", clusters, start)
+            }
+            Issue::FsInfoInvalid => write!(f, "FSInfo sector is invalid"),
+            Issue::FsInfoFreeCount { recorded, actual } => write!(
+                f,
+                "FSInfo free count is {} but {} clusters are free",
+                recorded, actual
+            ),
+            Issue::FsInfoNextFree { recorded } => {
+                write!(f, "FSInfo next free cluster {} is out of range", recorded)
+            }
+        }
+    }
+}
+
+/// Problème détecté, et s’il a été corrigé.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub issue: Issue,
+    pub repaired: bool,
+}
+
+/// Rapport de vérification.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CheckReport {
+    pub findings: Vec<Finding>,
+    pub files: u32,
+    pub directories: u32,
+    pub used_clusters: u32,
+    pub free_clusters: u32,
+    /// État de FAT[1] avant la vérification.
+    pub volume: VolumeFlags,
+}
+
+impl CheckReport {
+    /// Aucun problème détecté.
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Le volume n’a pas été démonté proprement ou a connu une erreur
+    /// disque : ses incohérences éventuelles peuvent être récentes.
+    pub fn scan_recommended(&self) -> bool {
+        self.volume.needs_check()
+    }
+
+    /// Problèmes détectés mais non corrigés.
+    pub fn unrepaired(&self) -> impl Iterator<Item = &Issue> {
+        self.findings.iter().filter(|f| !f.repaired).map(|f| &f.issue)
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for finding in &self.findings {
+            let status = if finding.repaired { "fixed" } else { "found" };
+            writeln!(f, "[{}] {}", status, finding.issue)?;
+        }
+        if self.scan_recommended() {
+            writeln!(f, "volume: {}", self.volume)?;
+        }
+        write!(
+            f,
+            "{} files, {} directories, {} clusters used, {} free",
+            self.files, self.directories, self.used_clusters, self.free_clusters
+        )
+    }
+}
+
+/// État partagé pendant la vérification.
+struct State {
+    repair: bool,
+    /// Copie mémoire de la FAT active, tenue à jour lors des réparations.
+    table: Vec<u32>,
+    /// Propriétaire de chaque cluster (0 = aucun, sinon index + 1 dans `owners`).
+    owner: Vec<u32>,
+    owners: Vec<String>,
+    report: CheckReport,
+}
+
+impl State {
+    fn found(&mut self, issue: Issue) {
+        let repaired = self.repair;
+        self.report.findings.push(Finding { issue, repaired });
+    }
+
+    fn report_only(&mut self, issue: Issue) {
+        self.report.findings.push(Finding {
+            issue,
+            repaired: false,
+        });
+    }
+}
+
+/// Vérificateur de cohérence.
+pub struct Checker<'a, D: BlockDevice> {
+    device: &'a D,
+    boot: &'a BootSector,
+}
+
+impl<'a, D: BlockDevice> Checker<'a, D> {
+    pub fn new(device: &'a D, boot: &'a BootSector) -> Self {
+        Self { device, boot }
+    }
+
+    /// Vrai si FAT[1] indique que le volume n’a pas été démonté proprement
+    /// (ou a connu une erreur disque) : une vérification complète est
+    /// conseillée. Seule l’entrée FAT[1] est lue.
+    pub fn scan_recommended(&self) -> FsResult<bool> {
+        Ok(Fat::new(self.device, self.boot).volume_flags()?.needs_check())
+    }
+
+    /// Vérifie le volume et, si demandé, le répare.
+    ///
+    /// En mode réparation, un volume sans problème restant est ensuite noté
+    /// comme démonté proprement, sans erreur disque.
+    pub fn check(&self, options: &CheckOptions) -> FsResult<CheckReport> {
+        let fat = Fat::new(self.device, self.boot);
+        let clusters = ClusterReader::new(self.device, self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+
+        let table = fat.read_table(fat.copy())?;
+        let mut st = State {
+            repair: options.repair,
+            owner: alloc::vec![0; table.len()],
+            table,
+            owners: Vec::new(),
+            report: CheckReport::default(),
+        };
+
+        st.report.volume = fat.volume_flags()?;
+        self.check_fat_copies(&fat, &mut st)?;
+        let root_clusters = self.check_tree(&fat, &clusters, &dirs, &mut st)?;
+        self.check_lost_chains(&fat, &clusters, &dirs, &mut st, options, root_clusters)?;
+        self.check_fs_info(&mut st)?;
+
+        let max = self.boot.max_cluster() as usize;
+        st.report.used_clusters = (2..=max).filter(|&c| st.owner[c] != 0).count() as u32;
+        st.report.free_clusters = (2..=max).filter(|&c| st.table[c] == FAT_FREE).count() as u32;
+
+        if options.repair && st.report.volume.needs_check() && st.report.unrepaired().next().is_none() {
+            fat.set_volume_flags(VolumeFlags::default())?;
+        }
+
+        Ok(st.report)
+    }
+
+    /// Compare chaque copie de la FAT à la copie active. Sans miroir, les
+    /// autres copies ne sont pas tenues à jour et ne sont pas comparées.
+    fn check_fat_copies(&self, fat: &Fat<'a, D>, st: &mut State) -> FsResult<()> {
+        if !self.boot.mirrored() {
+            return Ok(());
+        }
+
+        for copy in (0..self.boot.fat_count).filter(|&c| c != fat.copy()) {
+            let other = fat.read_table(copy)?;
+
+            let mut differing = other
+                .iter()
+                .zip(st.table.iter())
+                .enumerate()
+                .filter(|(_, (a, b))| a != b)
+                .map(|(i, _)| i as u32);
+
+            if let Some(first_cluster) = differing.next() {
+                let entries = 1 + differing.count() as u32;
+                if st.repair {
+                    fat.sync_copy(fat.copy(), copy)?;
+                }
+                st.found(Issue::FatCopyMismatch {
+                    copy,
+                    entries,
+                    first_cluster,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parcourt l’arborescence depuis la racine ; retourne les clusters de la racine.
+    fn check_tree(
+        &self,
+        fat: &Fat<'a, D>,
+        clusters: &ClusterReader<'a, D>,
+        dirs: &DirectoryReader<'a, D>,
+        st: &mut State,
+    ) -> FsResult<Vec<u32>> {
+        let root = self.boot.root_cluster;
+        let root_id = new_owner(st, String::from("/"));
+        let (root_clusters, end) = self.claim_chain(st, root, root_id);
+
+        if invalid_start(end) {
+            st.report_only(Issue::InvalidStartCluster {
+                path: String::from("/"),
+                cluster: root,
+            });
+            return Ok(root_clusters);
+        }
+        self.handle_chain_end(fat, st, "/", &root_clusters, end)?;
+        st.report.directories += 1;
+
+        // (clusters du répertoire, premier cluster du parent, chemin) ; 0 pour la racine.
+        let mut stack: Vec<(Vec<u32>, u32, String)> =
+            alloc::vec![(root_clusters.clone(), 0, String::new())];
+
+        while let Some((dir_clusters, parent, path)) = stack.pop() {
+            let mut slots = self.read_slots(clusters, &dir_clusters)?;
+
+            if parent != 0 {
+                self.check_dot_entries(dirs, st, &mut slots, &dir_clusters, parent, &path)?;
+            }
+
+            for mut slot in slots {
+                if slot.is_end() {
+                    break;
+                }
+                if slot.is_deleted() || slot.is_lfn() || slot.raw[0] == b'.' {
+                    continue;
+                }
+                if slot.is_volume_label() {
+                    continue;
+                }
+
+                let child = format!("{}/{}", path, display_name(slot.short_name()));
+                if !is_valid_short_name(slot.short_name()) {
+                    let mut raw = [0u8; 11];
+                    raw.copy_from_slice(slot.short_name());
+                    st.report_only(Issue::BadShortName {
+                        path: child.clone(),
+                        raw,
+                    });
+                }
+
+                if slot.attributes() & ATTR_DIRECTORY != 0 {
+                    if let Some(sub) = self.check_subdirectory(fat, st, &slot, &child)? {
+                        stack.push((sub, dir_clusters[0], child));
+                    }
+                } else {
+                    self.check_file(fat, dirs, st, &mut slot, &child)?;
+                }
+            }
+        }
+
+        Ok(root_clusters)
+    }
+
+    /// Vérifie la chaîne d’un sous-répertoire ; retourne ses clusters s’il
+    /// doit être parcouru.
+    fn check_subdirectory(
+        &self,
+        fat: &Fat<'a, D>,
+        st: &mut State,
+        slot: &DirectorySlot,
+        path: &str,
+    ) -> FsResult<Option<Vec<u32>>> {
+        st.report.directories += 1;
+
+        let start = slot.start_cluster();
+        let id = new_owner(st, String::from(path));
+        let (chain, end) = self.claim_chain(st, start, id);
+
+        match end {
+            end if invalid_start(end) => {
+                st.report_only(Issue::InvalidStartCluster {
+                    path: String::from(path),
+                    cluster: start,
+                });
+                Ok(None)
+            }
+            ChainEnd::Loop { cluster } if chain.is_empty() => {
+                // Répertoire déjà rencontré (lien croisé ou cycle) : ne pas redescendre.
+                st.report_only(Issue::CrossLinked {
+                    path: String::from(path),
+                    cluster,
+                    other: st.owners[st.owner[cluster as usize] as usize - 1].clone(),
+                });
+                Ok(None)
+            }
+            end => {
+                self.handle_chain_end(fat, st, path, &chain, end)?;
+                Ok(Some(chain))
+            }
+        }
+    }
+
+    /// Vérifie la chaîne d’un fichier et sa cohérence avec la taille.
+    fn check_file(
+        &self,
+        fat: &Fat<'a, D>,
+        dirs: &DirectoryReader<'a, D>,
+        st: &mut State,
+        slot: &mut DirectorySlot,
+        path: &str,
+    ) -> FsResult<()> {
+        st.report.files += 1;
+
+        let cluster_size = self.boot.cluster_size() as u64;
+        let size = slot.size();
+        let start = slot.start_cluster();
+        let expected = (size as u64).div_ceil(cluster_size) as usize;
+
+        let mut chain = Vec::new();
+        if start != 0 {
+            let id = new_owner(st, String::from(path));
+            let (claimed, end) = self.claim_chain(st, start, id);
+            chain = claimed;
+
+            if invalid_start(end) {
+                st.found(Issue::InvalidStartCluster {
+                    path: String::from(path),
+                    cluster: start,
+                });
+                if st.repair {
+                    slot.set_start_cluster(0);
+                    slot.set_size(0);
+                    dirs.write_slot(slot)?;
+                }
+                return Ok(());
+            }
+            self.handle_chain_end(fat, st, path, &chain, end)?;
+        }
+
+        let allocated = chain.len() as u64 * cluster_size;
+
+        if chain.len() > expected {
+            st.found(Issue::ChainTooLong {
+                path: String::from(path),
+                size,
+                allocated,
+            });
+            if st.repair {
+                for &c in &chain[expected..] {
+                    self.set_entry(fat, st, c, FAT_FREE)?;
+                    st.owner[c as usize] = 0;
+                }
+                if expected == 0 {
+                    slot.set_start_cluster(0);
+                    dirs.write_slot(slot)?;
+                } else {
+                    self.set_entry(fat, st, chain[expected - 1], FAT_EOC)?;
+                }
+            }
+        } else if chain.len() < expected {
+            st.found(Issue::ChainTooShort {
+                path: String::from(path),
+                size,
+                allocated,
+            });
+            if st.repair {
+                if chain.is_empty() {
+                    slot.set_start_cluster(0);
+                }
+                slot.set_size(allocated as u32);
+                dirs.write_slot(slot)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Vérifie les entrées `.` et `..` d’un sous-répertoire.
+    fn check_dot_entries(
+        &self,
+        dirs: &DirectoryReader<'a, D>,
+        st: &mut State,
+        slots: &mut [DirectorySlot],
+        dir_clusters: &[u32],
+        parent: u32,
+        path: &str,
+    ) -> FsResult<()> {
+        // `..` vaut 0 lorsque le parent est la racine.
+        let parent = if parent == self.boot.root_cluster { 0 } else { parent };
+        let expected = [(".", dir_clusters[0]), ("..", parent)];
+
+        for (index, (name, cluster)) in expected.into_iter().enumerate() {
+            let mut raw = [b' '; 11];
+            raw[..name.len()].copy_from_slice(name.as_bytes());
+
+            let slot = match slots.get_mut(index) {
+                Some(s) if s.short_name() == raw && s.attributes() & ATTR_DIRECTORY != 0 => s,
+                _ => {
+                    st.report_only(Issue::MissingDotEntry {
+                        path: String::from(path),
+                        name,
+                    });
+                    continue;
+                }
+            };
+
+            if slot.start_cluster() != cluster {
+                st.found(Issue::InvalidDotEntry {
+                    path: String::from(path),
+                    name,
+                    cluster: slot.start_cluster(),
+                    expected: cluster,
+                });
+                if st.repair {
+                    slot.set_start_cluster(cluster);
+                    dirs.write_slot(slot)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Recherche les clusters alloués sans propriétaire et les regroupe en chaînes.
+    fn check_lost_chains(
+        &self,
+        fat: &Fat<'a, D>,
+        clusters: &ClusterReader<'a, D>,
+        dirs: &DirectoryReader<'a, D>,
+        st: &mut State,
+        options: &CheckOptions,
+        mut root_clusters: Vec<u32>,
+    ) -> FsResult<()> {
+        let max = self.boot.max_cluster();
+        let is_lost = |st: &State, c: u32| {
+            let value = st.table[c as usize];
+            st.owner[c as usize] == 0 && value != FAT_FREE && value != FAT_BAD
+        };
+
+        // Un cluster perdu pointé par un autre cluster perdu n’est pas une tête de chaîne.
+        let mut pointed = alloc::vec![false; st.table.len()];
+        for c in 2..=max {
+            let next = st.table[c as usize];
+            if is_lost(st, c) && (2..=max).contains(&next) && is_lost(st, next) {
+                pointed[next as usize] = true;
+            }
+        }
+
+        let mut heads: Vec<u32> = (2..=max).filter(|&c| is_lost(st, c) && !pointed[c as usize]).collect();
+        // Les cycles entièrement perdus n’ont pas de tête : on en prend une arbitraire.
+        heads.extend((2..=max).filter(|&c| is_lost(st, c) && pointed[c as usize]));
+
+        let mut saved = 0u32;
+        for head in heads {
+            if !is_lost(st, head) {
+                continue; // déjà rattaché à une chaîne précédente
+            }
+
+            let id = new_owner(st, format!("lost chain at {}", head));
+            let mut chain = Vec::new();
+            let mut c = head;
+            while (2..=max).contains(&c) && is_lost(st, c) {
+                st.owner[c as usize] = id;
+                chain.push(c);
+                c = st.table[c as usize];
+            }
+
+            let issue = Issue::LostChain {
+                start: head,
+                clusters: chain.len() as u32,
+            };
+            if !st.repair {
+                st.found(issue);
+                continue;
+            }
+
+            match options.lost_chains {
+                LostChainAction::Free => {
+                    for &c in &chain {
+                        self.set_entry(fat, st, c, FAT_FREE)?;
+                        st.owner[c as usize] = 0;
+                    }
+                    st.found(issue);
+                }
+                LostChainAction::Save => {
+                    // Au-delà de 4 Gio, la taille enregistrée est plafonnée.
+                    let size = chain.len() as u64 * self.boot.cluster_size() as u64;
+                    let size = size.min(u32::MAX as u64) as u32;
+                    if self.save_lost_chain(fat, clusters, dirs, st, &mut root_clusters, &mut saved, head, size)? {
+                        self.set_entry(fat, st, chain[chain.len() - 1], FAT_EOC)?;
+                        st.found(issue);
+                    } else {
+                        st.report_only(issue);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Crée une entrée `FSCKnnnn.REC` à la racine pointant vers la chaîne.
+    ///
+    /// Comme `dosfsck`, les noms s’arrêtent à `FSCK9999.REC` : une fois
+    /// tous pris, rien n’est écrit et la fonction renvoie `false`.
+    #[allow(clippy::too_many_arguments)]
+    fn save_lost_chain(
+        &self,
+        fat: &Fat<'a, D>,
+        clusters: &ClusterReader<'a, D>,
+        dirs: &DirectoryReader<'a, D>,
+        st: &mut State,
+        root_clusters: &mut Vec<u32>,
+        saved: &mut u32,
+        start: u32,
+        size: u32,
+    ) -> FsResult<bool> {
+        let slots = self.read_slots(clusters, root_clusters)?;
+
+        // Choix d’un nom libre.
+        let mut name = [0u8; 11];
+        loop {
+            if *saved > 9999 {
+                return Ok(false);
+            }
+            name.copy_from_slice(format!("FSCK{:04}REC", *saved).as_bytes());
+            *saved += 1;
+            let taken = slots
+                .iter()
+                .take_while(|s| !s.is_end())
+                .any(|s| !s.is_deleted() && s.short_name() == name);
+            if !taken {
+                break;
+            }
+        }
+
+        let free = slots.iter().position(|s| s.is_end() || s.is_deleted());
+        let (location, was_end, next) = match free {
+            Some(i) => (slots[i].location, slots[i].is_end(), slots.get(i + 1).cloned()),
+            None => {
+                // Racine pleine : on l’agrandit d’un cluster.
+                let new = (2..=self.boot.max_cluster())
+                    .find(|&c| st.table[c as usize] == FAT_FREE)
+                    .ok_or(FatError::NoFreeCluster)?;
+                let last = root_clusters[root_clusters.len() - 1];
+                clusters.write_cluster(new, &alloc::vec![0u8; clusters.cluster_size()])?;
+                self.set_entry(fat, st, new, FAT_EOC)?;
+                self.set_entry(fat, st, last, new)?;
+                st.owner[new as usize] = st.owner[last as usize];
+                root_clusters.push(new);
+                (SlotLocation { cluster: new, index: 0 }, false, None)
+            }
+        };
+
+        let mut slot = DirectorySlot {
+            location,
+            raw: [0u8; 32],
+        };
+        slot.raw[0..11].copy_from_slice(&name);
+        slot.raw[11] = ATTR_ARCHIVE;
+        slot.set_start_cluster(start);
+        slot.set_size(size);
+        dirs.write_slot(&slot)?;
+
+        // Le marqueur de fin doit suivre la nouvelle entrée.
+        if was_end && let Some(mut next) = next {
+            next.raw[0] = 0x00;
+            dirs.write_slot(&next)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Compare le FSInfo au contenu réel de la FAT.
+    fn check_fs_info(&self, st: &mut State) -> FsResult<()> {
+        if FsInfo::offset(self.boot).is_none() {
+            return Ok(());
+        }
+
+        let max = self.boot.max_cluster();
+        let actual = (2..=max).filter(|&c| st.table[c as usize] == FAT_FREE).count() as u32;
+        let first_free = (2..=max)
+            .find(|&c| st.table[c as usize] == FAT_FREE)
+            .unwrap_or(FSINFO_UNKNOWN);
+
+        let mut stale = false;
+        match FsInfo::read(self.device, self.boot) {
+            Ok(info) => {
+                if info.free_count != FSINFO_UNKNOWN && info.free_count != actual {
+                    st.found(Issue::FsInfoFreeCount {
+                        recorded: info.free_count,
+                        actual,
+                    });
+                    stale = true;
+                }
+                if info.next_free != FSINFO_UNKNOWN && !(2..=max).contains(&info.next_free) {
+                    st.found(Issue::FsInfoNextFree {
+                        recorded: info.next_free,
+                    });
+                    stale = true;
+                }
+            }
+            Err(FsInfoError::InvalidSignature) => {
+                st.found(Issue::FsInfoInvalid);
+                stale = true;
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        if stale && st.repair {
+            let info = FsInfo {
+                free_count: actual,
+                next_free: first_free,
+            };
+            info.write(self.device, self.boot)?;
+        }
+
+        Ok(())
+    }
+
+    /// Parcourt une chaîne en mémoire en revendiquant ses clusters pour `id` ;
+    /// un cluster déjà revendiqué arrête le parcours sur `ChainEnd::Loop`.
+    fn claim_chain(&self, st: &mut State, start: u32, id: u32) -> (Vec<u32>, ChainEnd) {
+        let table = &st.table;
+        let owner = &mut st.owner;
+        let entry = |c: u32| Ok::<_, Infallible>(table[c as usize]);
+        let claim = |c: u32| {
+            let free = owner[c as usize] == 0;
+            if free {
+                owner[c as usize] = id;
+            }
+            free
+        };
+
+        let Ok(walked) = follow_chain(start, self.boot.max_cluster(), entry, claim);
+        walked
+    }
+
+    /// Signale une fin de chaîne anormale et, en réparation, la termine
+    /// proprement au dernier cluster valide.
+    fn handle_chain_end(
+        &self,
+        fat: &Fat<'a, D>,
+        st: &mut State,
+        path: &str,
+        chain: &[u32],
+        end: ChainEnd,
+    ) -> FsResult<()> {
+        let last = chain.last().copied().unwrap_or(0);
+        let broken = |value| Issue::BrokenChain {
+            path: String::from(path),
+            cluster: last,
+            value,
+        };
+        let issue = match end {
+            ChainEnd::Empty | ChainEnd::EndOfChain => return Ok(()),
+            ChainEnd::Free { .. } => broken(FAT_FREE),
+            ChainEnd::Bad { .. } => broken(FAT_BAD),
+            ChainEnd::InvalidCluster { value, .. } => broken(value),
+            // Un cluster déjà revendiqué par la chaîne elle-même est une
+            // boucle ; par une autre entrée, un lien croisé.
+            ChainEnd::Loop { cluster } => {
+                let other = st.owner[cluster as usize];
+                if chain.first().is_some_and(|&c| st.owner[c as usize] == other) {
+                    Issue::ChainLoop {
+                        path: String::from(path),
+                        cluster,
+                    }
+                } else {
+                    Issue::CrossLinked {
+                        path: String::from(path),
+                        cluster,
+                        other: st.owners[other as usize - 1].clone(),
+                    }
+                }
+            }
+        };
+
+        // Sans cluster conservé, il n’y a rien à couper : l’entrée garde son
+        // premier cluster et le défaut reste non réparé.
+        if last == 0 {
+            st.report_only(issue);
+            return Ok(());
+        }
+        st.found(issue);
+        if st.repair {
+            self.set_entry(fat, st, last, FAT_EOC)?;
+        }
+
+        Ok(())
+    }
+
+    /// Lit les slots de répertoire d’une liste de clusters déjà validée.
+    fn read_slots(
+        &self,
+        clusters: &ClusterReader<'a, D>,
+        dir_clusters: &[u32],
+    ) -> FsResult<Vec<DirectorySlot>> {
+        let mut buf = alloc::vec![0u8; clusters.cluster_size()];
+        let mut slots = Vec::new();
+
+        for &cluster in dir_clusters {
+            clusters.read_cluster(cluster, &mut buf)?;
+            for (index, chunk) in buf.chunks_exact(32).enumerate() {
+                let mut raw = [0u8; 32];
+                raw.copy_from_slice(chunk);
+                slots.push(DirectorySlot {
+                    location: SlotLocation {
+                        cluster,
+                        index: index as u32,
+                    },
+                    raw,
+                });
+            }
+        }
+
+        Ok(slots)
+    }
+
+    /// Écrit une entrée FAT (toutes copies) et met à jour la copie mémoire.
+    fn set_entry(&self, fat: &Fat<'a, D>, st: &mut State, cluster: u32, value: u32) -> FsResult<()> {
+        fat.set_entry(cluster, value)?;
+        st.table[cluster as usize] = value;
+        Ok(())
+    }
+}
+
+fn new_owner(st: &mut State, path: String) -> u32 {
+    st.owners.push(path);
+    st.owners.len() as u32
+}
+
+/// Vrai si le premier cluster de l’entrée n’est pas un cluster du volume.
+fn invalid_start(end: ChainEnd) -> bool {
+    matches!(end, ChainEnd::Empty | ChainEnd::InvalidCluster { from: 0, .. })
+}
+
+/// Nom 8.3 lisible, même s’il contient des octets invalides.
+fn display_name(raw: &[u8]) -> String {
+    let name = String::from_utf8_lossy(&raw[0..8]);
+    let ext = String::from_utf8_lossy(&raw[8..11]);
+    let (name, ext) = (name.trim_end(), ext.trim_end());
+
+    if ext.is_empty() {
+        String::from(name)
+    } else {
+        format!("{}.{}", name, ext)
+    }
+}
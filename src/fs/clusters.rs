@@ -1,14 +1,25 @@
 //! Lecture des clusters FAT32 (cluster -> données)
 
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+
 use crate::device::block_device::{BlockDevice, BlockDeviceError};
 use crate::fs::boot_sector::BootSector;
 use crate::fs::fat::{Fat, FatError};
 
+/// Taille maximale d’une requête de lecture groupée : au-delà, la lecture est
+/// découpée, ce qui borne la mémoire réservée avant de savoir si les clusters
+/// sont lisibles.
+pub(crate) const MAX_BATCH: usize = 16 << 20;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ClusterError {
     Io(BlockDeviceError),
     Fat(FatError),
     InvalidCluster,
+    /// La chaîne de clusters boucle sur elle-même.
+    ChainLoop,
 }
 
 impl From<BlockDeviceError> for ClusterError {
@@ -23,6 +34,27 @@ impl From<FatError> for ClusterError {
     }
 }
 
+impl fmt::Display for ClusterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClusterError::Io(_) => write!(f, "cannot read cluster"),
+            ClusterError::Fat(_) => write!(f, "cannot follow cluster chain"),
+            ClusterError::InvalidCluster => write!(f, "invalid cluster number"),
+            ClusterError::ChainLoop => write!(f, "cluster chain loops"),
+        }
+    }
+}
+
+impl core::error::Error for ClusterError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ClusterError::Io(e) => Some(e),
+            ClusterError::Fat(e) => Some(e),
+            ClusterError::InvalidCluster | ClusterError::ChainLoop => None,
+        }
+    }
+}
+
 /// Fournit des opérations de lecture de clusters.
 pub struct ClusterReader<'a, D: BlockDevice> {
     device: &'a D,
@@ -37,63 +69,276 @@ impl<'a, D: BlockDevice> ClusterReader<'a, D> {
 
     /// Calcule l’offset disque du début d’un cluster.
     pub fn cluster_offset(&self, cluster: u32) -> Result<u64, ClusterError> {
-        if cluster < 2 {
+        cluster_offset(self.boot, cluster)
+    }
+
+    /// Taille d’un cluster en octets.
+    pub fn cluster_size(&self) -> usize {
+        self.boot.cluster_size()
+    }
+
+    /// Lit un cluster complet dans `buf`.
+    pub fn read_cluster(&self, cluster: u32, buf: &mut [u8]) -> Result<(), ClusterError> {
+        if buf.len() != self.cluster_size() {
             return Err(ClusterError::InvalidCluster);
         }
 
-        let bytes_per_sector = self.boot.bytes_per_sector as u64;
-        let sectors_per_cluster = self.boot.sectors_per_cluster as u64;
+        self.read_in_cluster(cluster, 0, buf)
+    }
 
-        let fat_region_size =
-            self.boot.fat_count as u64 * self.boot.sectors_per_fat as u64;
+    /// Écrit un cluster complet depuis `buf`.
+    pub fn write_cluster(&self, cluster: u32, buf: &[u8]) -> Result<(), ClusterError> {
+        if buf.len() != self.cluster_size() {
+            return Err(ClusterError::InvalidCluster);
+        }
 
-        let data_region_sector =
-            self.boot.reserved_sectors as u64 + fat_region_size;
+        self.write_in_cluster(cluster, 0, buf)
+    }
 
-        let cluster_index = cluster as u64 - 2;
+    /// Lit `buf.len()` octets à partir de `offset` dans le cluster.
+    pub fn read_in_cluster(
+        &self,
+        cluster: u32,
+        offset: usize,
+        buf: &mut [u8],
+    ) -> Result<(), ClusterError> {
+        if offset + buf.len() > self.cluster_size() {
+            return Err(ClusterError::InvalidCluster);
+        }
 
-        Ok((data_region_sector + cluster_index * sectors_per_cluster)
-            * bytes_per_sector)
+        let start = self.cluster_offset(cluster)?;
+        self.device.read_at(start + offset as u64, buf)?;
+        Ok(())
     }
 
-    /// Lit un cluster complet dans `buf`.
-    pub fn read_cluster(&self, cluster: u32, buf: &mut [u8]) -> Result<(), ClusterError> {
-        let cluster_size =
-            self.boot.bytes_per_sector as usize * self.boot.sectors_per_cluster as usize;
-
-        if buf.len() != cluster_size {
+    /// Lit `buf.len()` octets à partir de `offset` dans une suite de clusters
+    /// consécutifs sur le disque, qui commence à `cluster`. La lecture doit
+    /// rester dans la région de données.
+    pub fn read_in_run(&self, cluster: u32, offset: u64, buf: &mut [u8]) -> Result<(), ClusterError> {
+        let start = self.cluster_offset(cluster)?;
+        let data_end = self.cluster_offset(self.boot.max_cluster())? + self.cluster_size() as u64;
+        let at = start.checked_add(offset).ok_or(ClusterError::InvalidCluster)?;
+        if at.checked_add(buf.len() as u64).is_none_or(|end| end > data_end) {
             return Err(ClusterError::InvalidCluster);
         }
 
-        let offset = self.cluster_offset(cluster)?;
-        self.device.read_at(offset, buf)?;
+        self.device.read_at(at, buf)?;
         Ok(())
     }
 
-    /// Lit une chaîne de clusters complète et concatène les données.
-    ///
-    /// S’arrête automatiquement à la fin de chaîne (EOC).
-    pub fn read_cluster_chain(
+    /// Écrit `buf` à partir de `offset` dans le cluster.
+    pub fn write_in_cluster(
         &self,
-        start_cluster: u32,
-        out: &mut alloc::vec::Vec<u8>,
+        cluster: u32,
+        offset: usize,
+        buf: &[u8],
     ) -> Result<(), ClusterError> {
-        let cluster_size =
-            self.boot.bytes_per_sector as usize * self.boot.sectors_per_cluster as usize;
+        if offset + buf.len() > self.cluster_size() {
+            return Err(ClusterError::InvalidCluster);
+        }
+
+        let start = self.cluster_offset(cluster)?;
+        self.device.write_at(start + offset as u64, buf)?;
+        Ok(())
+    }
+
+    /// Alloue un cluster remis à zéro et l’accroche après `last` s’il est donné.
+    ///
+    /// En cas d’échec, le cluster est libéré.
+    pub fn allocate_after(&self, last: Option<u32>) -> Result<u32, ClusterError> {
+        let cluster = self.fat.allocate()?;
 
+        let linked = self
+            .write_cluster(cluster, &alloc::vec![0u8; self.cluster_size()])
+            .and_then(|_| match last {
+                Some(last) => Ok(self.fat.set_entry(last, cluster)?),
+                None => Ok(()),
+            });
+        if let Err(e) = linked {
+            let _ = self.fat.free_chain(cluster);
+            return Err(e);
+        }
+
+        Ok(cluster)
+    }
+
+    /// Liste les clusters d’une chaîne, dans l’ordre.
+    ///
+    /// Une chaîne qui repasse par un cluster déjà parcouru, ou plus longue
+    /// que la FAT elle-même, boucle : elle est rejetée avec
+    /// `ClusterError::ChainLoop` dès le premier cluster revisité.
+    pub fn chain(&self, start_cluster: u32) -> Result<Vec<u32>, ClusterError> {
+        let mut clusters = Vec::new();
+        self.walk(start_cluster, &mut clusters)?;
+        Ok(clusters)
+    }
+
+    /// Ajoute à `clusters` ceux de la chaîne, jusqu’à la fin ou à l’erreur.
+    fn walk(&self, start_cluster: u32, clusters: &mut Vec<u32>) -> Result<(), ClusterError> {
+        let limit = self.max_chain_len();
+        let mut visited = Visited::default();
         let mut current = start_cluster;
 
         loop {
-            let mut buf = alloc::vec![0u8; cluster_size];
-            self.read_cluster(current, &mut buf)?;
-            out.extend_from_slice(&buf);
+            if clusters.len() >= limit || !visited.insert(current) {
+                return Err(ClusterError::ChainLoop);
+            }
+            clusters.push(current);
 
             match self.fat.next_cluster(current)? {
                 Some(next) => current = next,
-                None => break,
+                None => return Ok(()),
             }
         }
+    }
+
+    /// Lit une chaîne de clusters complète et concatène les données.
+    ///
+    /// La chaîne est suivie jusqu’à la fin (EOC), puis lue par suites de
+    /// clusters consécutifs (`read_extents`). Une chaîne qui boucle est
+    /// rejetée sans rien lire. Si elle est rompue, les clusters atteints sont
+    /// lus d’abord : une erreur de lecture l’emporte, comme si chaque cluster
+    /// était lu avant de suivre la FAT.
+    pub fn read_cluster_chain(&self, start_cluster: u32, out: &mut Vec<u8>) -> Result<(), ClusterError> {
+        let mut chain = Vec::new();
+        let walked = self.walk(start_cluster, &mut chain);
+        if walked == Err(ClusterError::ChainLoop) {
+            return walked;
+        }
+        self.read_extents(&extents(&chain), out)?;
+        walked
+    }
+
+    /// Ajoute à `out` les données de suites de clusters.
+    ///
+    /// Chaque suite est une seule plage d’une requête `read_vectored_at` ;
+    /// les requêtes sont découpées par tranches de 16 Mio au plus. En cas
+    /// d’erreur, `out` reste inchangé.
+    pub fn read_extents(&self, extents: &[Extent], out: &mut Vec<u8>) -> Result<(), ClusterError> {
+        let first = out.len();
+        let result = self.read_batches(extents, out);
+        if result.is_err() {
+            out.truncate(first);
+        }
+        result
+    }
+
+    fn read_batches(&self, extents: &[Extent], out: &mut Vec<u8>) -> Result<(), ClusterError> {
+        let cluster_size = self.cluster_size() as u64;
+        // Plages (offset disque, longueur) de la tranche en cours.
+        let mut batch: Vec<(u64, usize)> = Vec::new();
+        let mut batch_len = 0;
+
+        for extent in extents {
+            let mut offset = self.cluster_offset(extent.start)?;
+            let mut remaining = extent.length as u64 * cluster_size;
 
+            while remaining > 0 {
+                if batch_len == MAX_BATCH {
+                    self.read_batch(&batch, out)?;
+                    batch.clear();
+                    batch_len = 0;
+                }
+                let n = remaining.min((MAX_BATCH - batch_len) as u64) as usize;
+                batch.push((offset, n));
+                batch_len += n;
+                offset += n as u64;
+                remaining -= n as u64;
+            }
+        }
+
+        self.read_batch(&batch, out)
+    }
+
+    /// Lit une tranche de plages à la suite de `out`, en une requête.
+    fn read_batch(&self, ranges: &[(u64, usize)], out: &mut Vec<u8>) -> Result<(), ClusterError> {
+        let first = out.len();
+        out.resize(first + ranges.iter().map(|&(_, len)| len).sum::<usize>(), 0);
+
+        let mut requests = Vec::with_capacity(ranges.len());
+        let mut rest = &mut out[first..];
+        for &(offset, len) in ranges {
+            let (buf, tail) = rest.split_at_mut(len);
+            requests.push((offset, buf));
+            rest = tail;
+        }
+
+        self.device.read_vectored_at(&mut requests)?;
         Ok(())
     }
+
+    fn max_chain_len(&self) -> usize {
+        max_chain_len(self.boot)
+    }
+}
+
+/// Clusters déjà parcourus d’une chaîne, rangés par suites consécutives
+/// (début → fin) : une chaîne peu fragmentée reste peu coûteuse à suivre.
+#[derive(Default)]
+pub(crate) struct Visited {
+    runs: BTreeMap<u32, u32>,
+    /// Début de la suite en cours et dernier cluster noté.
+    current: Option<(u32, u32)>,
+}
+
+impl Visited {
+    /// Note `cluster` ; faux s’il avait déjà été parcouru.
+    pub(crate) fn insert(&mut self, cluster: u32) -> bool {
+        let seen = self
+            .runs
+            .range(..=cluster)
+            .next_back()
+            .is_some_and(|(_, &end)| cluster <= end);
+        if seen {
+            return false;
+        }
+
+        let start = match self.current {
+            Some((start, last)) if last.checked_add(1) == Some(cluster) => start,
+            _ => cluster,
+        };
+        self.runs.insert(start, cluster);
+        self.current = Some((start, cluster));
+        true
+    }
+}
+
+/// Suite de clusters consécutifs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extent {
+    pub start: u32,
+    pub length: u32,
+}
+
+/// Regroupe une chaîne en suites de clusters consécutifs.
+pub fn extents(chain: &[u32]) -> Vec<Extent> {
+    let mut extents: Vec<Extent> = Vec::new();
+
+    for &cluster in chain {
+        match extents.last_mut() {
+            Some(last) if last.start + last.length == cluster => last.length += 1,
+            _ => extents.push(Extent { start: cluster, length: 1 }),
+        }
+    }
+
+    extents
+}
+
+/// Offset disque du début d’un cluster.
+pub(crate) fn cluster_offset(boot: &BootSector, cluster: u32) -> Result<u64, ClusterError> {
+    if cluster < 2 {
+        return Err(ClusterError::InvalidCluster);
+    }
+
+    let bytes_per_sector = boot.bytes_per_sector as u64;
+    let sectors_per_cluster = boot.sectors_per_cluster as u64;
+
+    let cluster_index = cluster as u64 - 2;
+
+    Ok((boot.data_start_sector() + cluster_index * sectors_per_cluster) * bytes_per_sector)
+}
+
+/// Nombre maximal de clusters d’une chaîne sans boucle.
+pub(crate) fn max_chain_len(boot: &BootSector) -> usize {
+    boot.sectors_per_fat as usize * boot.bytes_per_sector as usize / 4
 }
@@ -0,0 +1,317 @@
+//! Création de fichiers et de répertoires.
+//!
+//! Un nom qui n’est pas déjà un nom 8.3 reçoit un nom long (LFN) et un alias
+//! `NOM~N.EXT` unique dans le répertoire. Les clusters d’un fichier sont
+//! alloués en une fois, contigus si le volume le permet ; son entrée n’est
+//! ajoutée qu’une fois le contenu écrit.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::boot_sector::BootSector;
+use crate::fs::clusters::{ClusterError, ClusterReader};
+use crate::fs::directory::{
+    DirectoryError, DirectoryReader, DirectorySlot, SlotLocation, ATTR_ARCHIVE, ATTR_DIRECTORY,
+};
+use crate::fs::fat::{Fat, FatError};
+use crate::fs::lfn;
+use crate::fs::timestamp::DateTime;
+
+/// Attributs modifiables par `set_attributes` (R, H, S, A).
+pub const ATTR_CHANGEABLE: u8 = 0x27;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CreateError {
+    Directory(DirectoryError),
+    Cluster(ClusterError),
+    Fat(FatError),
+    /// Nom vide, trop long ou contenant des caractères interdits.
+    InvalidName,
+    /// Une entrée de même nom (sans tenir compte de la casse) existe déjà.
+    AlreadyExists,
+    /// Taille supérieure à 4 Gio - 1, la limite de FAT32.
+    FileTooLarge,
+}
+
+impl From<DirectoryError> for CreateError {
+    fn from(e: DirectoryError) -> Self {
+        CreateError::Directory(e)
+    }
+}
+
+impl From<ClusterError> for CreateError {
+    fn from(e: ClusterError) -> Self {
+        CreateError::Cluster(e)
+    }
+}
+
+impl From<FatError> for CreateError {
+    fn from(e: FatError) -> Self {
+        CreateError::Fat(e)
+    }
+}
+
+impl fmt::Display for CreateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreateError::Directory(_) => write!(f, "cannot update directory"),
+            CreateError::Cluster(_) => write!(f, "cannot write file data"),
+            CreateError::Fat(_) => write!(f, "cannot allocate clusters"),
+            CreateError::InvalidName => write!(f, "invalid file name"),
+            CreateError::AlreadyExists => write!(f, "entry already exists"),
+            CreateError::FileTooLarge => write!(f, "file too large for FAT32"),
+        }
+    }
+}
+
+impl core::error::Error for CreateError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            CreateError::Directory(e) => Some(e),
+            CreateError::Cluster(e) => Some(e),
+            CreateError::Fat(e) => Some(e),
+            CreateError::InvalidName | CreateError::AlreadyExists | CreateError::FileTooLarge => None,
+        }
+    }
+}
+
+/// Dates d’une nouvelle entrée ; une date absente reste nulle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EntryTimes {
+    pub created: Option<DateTime>,
+    pub modified: Option<DateTime>,
+    pub accessed: Option<DateTime>,
+}
+
+impl EntryTimes {
+    /// Même date pour la création, la modification et l’accès.
+    pub fn all(time: DateTime) -> Self {
+        Self {
+            created: Some(time),
+            modified: Some(time),
+            accessed: Some(time),
+        }
+    }
+}
+
+/// Fichier en cours de création : ses clusters sont alloués, mais il n’a
+/// pas encore d’entrée dans `parent`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewFile {
+    pub parent: u32,
+    pub name: String,
+    pub chain: Vec<u32>,
+    pub size: u64,
+    pub times: EntryTimes,
+}
+
+/// Répertoire créé.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewDirectory {
+    /// Emplacement du slot 8.3 dans le parent.
+    pub location: SlotLocation,
+    pub cluster: u32,
+}
+
+pub struct Create<'a, D: BlockDevice> {
+    boot: &'a BootSector,
+    fat: &'a Fat<'a, D>,
+    clusters: &'a ClusterReader<'a, D>,
+    dirs: &'a DirectoryReader<'a, D>,
+}
+
+impl<'a, D: BlockDevice> Create<'a, D> {
+    pub fn new(
+        boot: &'a BootSector,
+        fat: &'a Fat<'a, D>,
+        clusters: &'a ClusterReader<'a, D>,
+        dirs: &'a DirectoryReader<'a, D>,
+    ) -> Self {
+        Self { boot, fat, clusters, dirs }
+    }
+
+    /// Crée le répertoire `name` dans `parent`, avec ses entrées `.` et `..`.
+    pub fn mkdir(&self, parent: u32, name: &str, times: &EntryTimes) -> Result<NewDirectory, CreateError> {
+        let cluster = self.clusters.allocate_after(None)?;
+
+        // `..` vaut 0 lorsque le parent est la racine.
+        let parent_ref = if parent == self.boot.root_cluster { 0 } else { parent };
+        let dot = entry(*b".          ", ATTR_DIRECTORY, cluster, 0, times);
+        let dot_dot = entry(*b"..         ", ATTR_DIRECTORY, parent_ref, 0, times);
+        let written = self
+            .clusters
+            .write_in_cluster(cluster, 0, &dot)
+            .and_then(|_| self.clusters.write_in_cluster(cluster, 32, &dot_dot))
+            .map_err(CreateError::from)
+            .and_then(|_| self.add_entry(parent, name, ATTR_DIRECTORY, cluster, 0, times));
+
+        match written {
+            Ok(location) => Ok(NewDirectory { location, cluster }),
+            Err(e) => {
+                let _ = self.fat.free_chain(cluster);
+                Err(e)
+            }
+        }
+    }
+
+    /// Prépare le fichier `name` de `size` octets dans `parent` et lui
+    /// alloue ses clusters. Le contenu est ensuite écrit avec `write_at`,
+    /// puis l’entrée ajoutée par `finish` ; en cas d’échec, `discard` rend
+    /// les clusters. Un contenu inachevé n’est ainsi jamais visible.
+    pub fn create_file(
+        &self,
+        parent: u32,
+        name: &str,
+        size: u64,
+        times: &EntryTimes,
+    ) -> Result<NewFile, CreateError> {
+        if u32::try_from(size).is_err() {
+            return Err(CreateError::FileTooLarge);
+        }
+        self.check_name(parent, name)?;
+
+        let cluster_size = self.clusters.cluster_size() as u64;
+        let count = size.div_ceil(cluster_size) as u32;
+        let chain = self.fat.allocate_chain(count)?;
+
+        Ok(NewFile {
+            parent,
+            name: String::from(name),
+            chain,
+            size,
+            times: *times,
+        })
+    }
+
+    /// Ajoute l’entrée d’un fichier dont le contenu est écrit ; rend
+    /// l’emplacement de son slot 8.3. Les clusters sont rendus si l’ajout
+    /// échoue.
+    pub fn finish(&self, file: &NewFile) -> Result<SlotLocation, CreateError> {
+        let start = file.chain.first().copied().unwrap_or(0);
+        let added = self.add_entry(file.parent, &file.name, ATTR_ARCHIVE, start, file.size as u32, &file.times);
+        if added.is_err() {
+            self.discard(file);
+        }
+        added
+    }
+
+    /// Abandonne un fichier sans entrée et rend ses clusters.
+    pub fn discard(&self, file: &NewFile) {
+        if let Some(&start) = file.chain.first() {
+            let _ = self.fat.free_chain(start);
+        }
+    }
+
+    /// Écrit `data` à partir de `offset` dans un fichier créé par
+    /// `create_file`, sans dépasser sa taille.
+    pub fn write_at(&self, file: &NewFile, offset: u64, data: &[u8]) -> Result<(), CreateError> {
+        if offset + data.len() as u64 > file.size {
+            return Err(CreateError::FileTooLarge);
+        }
+
+        let cluster_size = self.clusters.cluster_size() as u64;
+        let mut pos = offset;
+        let mut data = data;
+        while !data.is_empty() {
+            let cluster = file.chain[(pos / cluster_size) as usize];
+            let within = (pos % cluster_size) as usize;
+            let n = data.len().min(cluster_size as usize - within);
+
+            self.clusters.write_in_cluster(cluster, within, &data[..n])?;
+            pos += n as u64;
+            data = &data[n..];
+        }
+
+        Ok(())
+    }
+
+    /// Remplace les attributs lecture seule, caché, système et archive de
+    /// l’entrée 8.3 située en `location` ; le bit répertoire est conservé.
+    pub fn set_attributes(&self, location: SlotLocation, attributes: u8) -> Result<(), CreateError> {
+        let mut slot = DirectorySlot { location, raw: [0u8; 32] };
+        self.clusters
+            .read_in_cluster(location.cluster, location.index as usize * 32, &mut slot.raw)?;
+
+        slot.raw[11] = (slot.raw[11] & ATTR_DIRECTORY) | (attributes & ATTR_CHANGEABLE);
+        Ok(self.dirs.write_slot(&slot)?)
+    }
+
+    /// Ajoute l’entrée `name` (nom long et alias 8.3 si nécessaire) dans le
+    /// répertoire `parent`.
+    pub fn add_entry(
+        &self,
+        parent: u32,
+        name: &str,
+        attributes: u8,
+        start_cluster: u32,
+        size: u32,
+        times: &EntryTimes,
+    ) -> Result<SlotLocation, CreateError> {
+        self.check_name(parent, name)?;
+
+        let (short, mut slots) = match lfn::exact_short_name(name) {
+            Some(short) => (short, Vec::new()),
+            None => {
+                let taken: Vec<[u8; 11]> = self
+                    .dirs
+                    .read_slots(parent)?
+                    .iter()
+                    .take_while(|s| !s.is_end())
+                    .filter(|s| !s.is_deleted() && !s.is_lfn())
+                    .map(|s| {
+                        let mut raw = [0u8; 11];
+                        raw.copy_from_slice(s.short_name());
+                        raw
+                    })
+                    .collect();
+                let short = lfn::short_alias(name, |raw| taken.contains(raw));
+                (short, lfn::encode(name, &short).ok_or(CreateError::InvalidName)?)
+            }
+        };
+        slots.push(entry(short, attributes, start_cluster, size, times));
+
+        Ok(self.dirs.add_slots(parent, &slots)?)
+    }
+
+    /// Vérifie que `name` est valide et libre dans `parent`.
+    fn check_name(&self, parent: u32, name: &str) -> Result<(), CreateError> {
+        if !lfn::is_valid_long_name(name) {
+            return Err(CreateError::InvalidName);
+        }
+
+        let upper = name.to_uppercase();
+        let exists = self.dirs.read_dir(parent)?.iter().any(|e| {
+            e.name.to_uppercase() == upper
+                || e.long_name.as_ref().is_some_and(|l| l.to_uppercase() == upper)
+        });
+        if exists {
+            return Err(CreateError::AlreadyExists);
+        }
+        Ok(())
+    }
+}
+
+/// Slot 8.3 brut.
+fn entry(name: [u8; 11], attributes: u8, start_cluster: u32, size: u32, times: &EntryTimes) -> [u8; 32] {
+    let mut slot = DirectorySlot {
+        location: SlotLocation { cluster: 0, index: 0 },
+        raw: [0u8; 32],
+    };
+    slot.raw[..11].copy_from_slice(&name);
+    slot.raw[11] = attributes;
+    slot.set_start_cluster(start_cluster);
+    slot.set_size(size);
+
+    if let Some(time) = times.created {
+        slot.set_created(time);
+    }
+    if let Some(time) = times.modified {
+        slot.set_modified(time);
+    }
+    if let Some(time) = times.accessed {
+        slot.set_accessed(time);
+    }
+    slot.raw
+}
@@ -1,6 +1,10 @@
-//! Parsing des entrées de répertoire FAT32 (8.3 uniquement)
+//! Parsing des entrées de répertoire FAT32 (8.3, noms longs et dates)
+
+use core::fmt;
 
 use crate::fs::clusters::{ClusterError, ClusterReader};
+use crate::fs::lfn::LongNameCollector;
+use crate::fs::timestamp::DateTime;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DirectoryError {
@@ -20,6 +24,24 @@ impl From<ClusterError> for DirectoryError {
     }
 }
 
+impl fmt::Display for DirectoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DirectoryError::Cluster(_) => write!(f, "cannot read directory data"),
+            DirectoryError::InvalidEntry => write!(f, "invalid directory entry"),
+        }
+    }
+}
+
+impl core::error::Error for DirectoryError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            DirectoryError::Cluster(e) => Some(e),
+            DirectoryError::InvalidEntry => None,
+        }
+    }
+}
+
 /// Type d’entrée de répertoire
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EntryType {
@@ -30,12 +52,142 @@ pub enum EntryType {
 /// Entrée de répertoire FAT32 (simplifiée)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DirectoryEntry {
+    /// Nom 8.3 (`NOM.EXT`).
     pub name: alloc::string::String,
+    /// Nom long VFAT, s’il existe et est cohérent.
+    pub long_name: Option<alloc::string::String>,
     pub entry_type: EntryType,
+    pub attributes: u8,
     pub start_cluster: u32,
     pub size: u32,
+    pub created: Option<DateTime>,
+    pub modified: Option<DateTime>,
+    /// Date de dernier accès (sans heure).
+    pub accessed: Option<DateTime>,
+    /// Emplacement du slot 8.3 de l’entrée.
+    pub location: SlotLocation,
 }
 
+impl DirectoryEntry {
+    /// Nom long s’il existe, nom 8.3 sinon.
+    pub fn display_name(&self) -> &str {
+        self.long_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Vrai si `name` désigne l’entrée (nom 8.3 ou nom long).
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name == name || self.long_name.as_deref() == Some(name)
+    }
+}
+
+/// Position d’un slot de 32 octets : cluster du répertoire et index dans ce cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotLocation {
+    pub cluster: u32,
+    pub index: u32,
+}
+
+/// Slot brut de 32 octets d’un répertoire.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectorySlot {
+    pub location: SlotLocation,
+    pub raw: [u8; 32],
+}
+
+impl DirectorySlot {
+    /// Marqueur de fin de répertoire (0x00).
+    pub fn is_end(&self) -> bool {
+        self.raw[0] == 0x00
+    }
+
+    /// Entrée supprimée (0xE5).
+    pub fn is_deleted(&self) -> bool {
+        self.raw[0] == 0xE5
+    }
+
+    /// Fragment de nom long (LFN).
+    pub fn is_lfn(&self) -> bool {
+        self.attributes() == ATTR_LONG_NAME
+    }
+
+    /// Entrée d’étiquette de volume (et non fragment LFN).
+    pub fn is_volume_label(&self) -> bool {
+        !self.is_lfn() && self.attributes() & (ATTR_VOLUME_ID | ATTR_DIRECTORY) == ATTR_VOLUME_ID
+    }
+
+    pub fn attributes(&self) -> u8 {
+        self.raw[11]
+    }
+
+    /// Nom 8.3 brut (11 octets, complétés par des espaces).
+    pub fn short_name(&self) -> &[u8] {
+        &self.raw[0..11]
+    }
+
+    pub fn start_cluster(&self) -> u32 {
+        let high = u16::from_le_bytes([self.raw[20], self.raw[21]]) as u32;
+        let low = u16::from_le_bytes([self.raw[26], self.raw[27]]) as u32;
+        (high << 16) | low
+    }
+
+    pub fn set_start_cluster(&mut self, cluster: u32) {
+        self.raw[20..22].copy_from_slice(&((cluster >> 16) as u16).to_le_bytes());
+        self.raw[26..28].copy_from_slice(&(cluster as u16).to_le_bytes());
+    }
+
+    pub fn size(&self) -> u32 {
+        u32::from_le_bytes([self.raw[28], self.raw[29], self.raw[30], self.raw[31]])
+    }
+
+    pub fn set_size(&mut self, size: u32) {
+        self.raw[28..32].copy_from_slice(&size.to_le_bytes());
+    }
+
+    /// Date de création (les centièmes de l’octet 13 sont ignorés).
+    pub fn created(&self) -> Option<DateTime> {
+        DateTime::from_fat(self.u16_at(16), self.u16_at(14))
+    }
+
+    pub fn modified(&self) -> Option<DateTime> {
+        DateTime::from_fat(self.u16_at(24), self.u16_at(22))
+    }
+
+    pub fn accessed(&self) -> Option<DateTime> {
+        DateTime::from_fat(self.u16_at(18), 0)
+    }
+
+    pub fn set_modified(&mut self, value: DateTime) {
+        let (date, time) = value.to_fat();
+        self.raw[22..24].copy_from_slice(&time.to_le_bytes());
+        self.raw[24..26].copy_from_slice(&date.to_le_bytes());
+    }
+
+    pub fn set_created(&mut self, value: DateTime) {
+        let (date, time) = value.to_fat();
+        self.raw[13] = 0;
+        self.raw[14..16].copy_from_slice(&time.to_le_bytes());
+        self.raw[16..18].copy_from_slice(&date.to_le_bytes());
+    }
+
+    /// Seule la date est conservée.
+    pub fn set_accessed(&mut self, value: DateTime) {
+        self.raw[18..20].copy_from_slice(&value.to_fat().0.to_le_bytes());
+    }
+
+    fn u16_at(&self, at: usize) -> u16 {
+        u16::from_le_bytes([self.raw[at], self.raw[at + 1]])
+    }
+}
+
+/// Attribut « répertoire ».
+pub const ATTR_DIRECTORY: u8 = 0x10;
+/// Attribut « archive », posé sur les fichiers ordinaires.
+pub const ATTR_ARCHIVE: u8 = 0x20;
+/// Attribut « étiquette de volume ».
+pub const ATTR_VOLUME_ID: u8 = 0x08;
+/// Combinaison d’attributs identifiant un fragment LFN.
+pub const ATTR_LONG_NAME: u8 = 0x0F;
+
 /// Lecteur de répertoire
 pub struct DirectoryReader<'a, D: crate::device::block_device::BlockDevice> {
     cluster_reader: &'a ClusterReader<'a, D>,
@@ -51,59 +203,195 @@ impl<'a, D: crate::device::block_device::BlockDevice> DirectoryReader<'a, D> {
         &self,
         start_cluster: u32,
     ) -> Result<alloc::vec::Vec<DirectoryEntry>, DirectoryError> {
-        let mut data = alloc::vec::Vec::new();
-        self.cluster_reader
-            .read_cluster_chain(start_cluster, &mut data)?;
+        parse_entries(self.read_slots(start_cluster)?)
+    }
 
-        let mut entries = alloc::vec::Vec::new();
+    /// Lit tous les slots bruts d’un répertoire, y compris ceux situés
+    /// après le marqueur de fin.
+    pub fn read_slots(
+        &self,
+        start_cluster: u32,
+    ) -> Result<alloc::vec::Vec<DirectorySlot>, DirectoryError> {
+        let cluster_size = self.cluster_reader.cluster_size();
+        let mut buf = alloc::vec![0u8; cluster_size];
+        let mut slots = alloc::vec::Vec::new();
 
-        for chunk in data.chunks_exact(32) {
-            let first = chunk[0];
+        for cluster in self.cluster_reader.chain(start_cluster)? {
+            self.cluster_reader.read_cluster(cluster, &mut buf)?;
+            push_slots(cluster, &buf, &mut slots);
+        }
 
-            if first == 0x00 {
-                break; // fin du répertoire
-            }
+        Ok(slots)
+    }
 
-            if first == 0xE5 {
-                continue; // supprimé
-            }
+    /// Ajoute un slot dans le premier emplacement libre du répertoire,
+    /// en agrandissant sa chaîne si nécessaire.
+    pub fn add_slot(
+        &self,
+        start_cluster: u32,
+        raw: [u8; 32],
+    ) -> Result<SlotLocation, DirectoryError> {
+        self.add_slots(start_cluster, &[raw])
+    }
+
+    /// Ajoute des slots consécutifs (fragments LFN puis entrée 8.3) dans le
+    /// premier emplacement libre assez grand, en agrandissant la chaîne si
+    /// nécessaire. Retourne l’emplacement du dernier slot.
+    pub fn add_slots(
+        &self,
+        start_cluster: u32,
+        raws: &[[u8; 32]],
+    ) -> Result<SlotLocation, DirectoryError> {
+        let count = raws.len();
+        if count == 0 {
+            return Err(DirectoryError::InvalidEntry);
+        }
+
+        let mut slots = self.read_slots(start_cluster)?;
+        let end = slots.iter().position(|s| s.is_end()).unwrap_or(slots.len());
+        let free = |i: usize| i >= end || slots[i].is_deleted();
+
+        // Premier emplacement libre assez grand, sinon la fin du répertoire.
+        let first = (0..slots.len())
+            .find(|&i| i + count <= slots.len() && (i..i + count).all(free))
+            .unwrap_or_else(|| (0..slots.len()).rev().take_while(|&i| free(i)).last().unwrap_or(slots.len()));
+
+        let per_cluster = self.cluster_reader.cluster_size() / 32;
+        let mut last = slots.last().map(|s| s.location.cluster);
+        while slots.len() < first + count {
+            let cluster = self.cluster_reader.allocate_after(last)?;
+            slots.extend((0..per_cluster).map(|index| DirectorySlot {
+                location: SlotLocation { cluster, index: index as u32 },
+                raw: [0u8; 32],
+            }));
+            last = Some(cluster);
+        }
 
-            let attr = chunk[11];
-            if attr == 0x0F {
-                continue; // LFN ignoré
+        // Le marqueur de fin doit suivre les nouvelles entrées.
+        if first + count > end
+            && let Some(next) = slots.get(first + count)
+            && !next.is_end()
+        {
+            let mut next = next.clone();
+            next.raw[0] = 0x00;
+            self.write_slot(&next)?;
+        }
+
+        for (i, (slot, raw)) in slots[first..first + count].iter().zip(raws).enumerate() {
+            if let Err(e) = self.write_slot(&DirectorySlot { location: slot.location, raw: *raw }) {
+                // Les slots déjà écrits, et celui en échec, reprennent leur
+                // contenu : pas d’entrée à moitié créée.
+                for old in &slots[first..=first + i] {
+                    let _ = self.write_slot(old);
+                }
+                return Err(e);
             }
+        }
+        Ok(slots[first + count - 1].location)
+    }
+
+    /// Réécrit un slot à sa position.
+    pub fn write_slot(&self, slot: &DirectorySlot) -> Result<(), DirectoryError> {
+        self.cluster_reader.write_in_cluster(
+            slot.location.cluster,
+            slot.location.index as usize * 32,
+            &slot.raw,
+        )?;
+        Ok(())
+    }
+}
+
+/// Entrées d’une suite de slots bruts, lus dans l’ordre du répertoire.
+///
+/// Les fragments LFN sont rattachés à l’entrée 8.3 qui les suit ; la lecture
+/// s’arrête au marqueur de fin.
+pub(crate) fn parse_entries(
+    slots: alloc::vec::Vec<DirectorySlot>,
+) -> Result<alloc::vec::Vec<DirectoryEntry>, DirectoryError> {
+    let mut entries = alloc::vec::Vec::new();
+    let mut long_name = LongNameCollector::new();
+
+    for slot in slots {
+        if slot.is_end() {
+            break; // fin du répertoire
+        }
+
+        if slot.is_deleted() {
+            long_name.reset();
+            continue; // supprimé
+        }
 
-            let name = parse_short_name(&chunk[0..11])?;
-
-            let is_dir = attr & 0x10 != 0;
-
-            let high =
-                u16::from_le_bytes([chunk[20], chunk[21]]) as u32;
-            let low =
-                u16::from_le_bytes([chunk[26], chunk[27]]) as u32;
-            let start_cluster = (high << 16) | low;
-
-            let size =
-                u32::from_le_bytes([chunk[28], chunk[29], chunk[30], chunk[31]]);
-
-            entries.push(DirectoryEntry {
-                name,
-                entry_type: if is_dir {
-                    EntryType::Directory
-                } else {
-                    EntryType::File
-                },
-                start_cluster,
-                size,
-            });
+        if slot.is_lfn() {
+            long_name.push(&slot.raw);
+            continue;
         }
 
-        Ok(entries)
+        if slot.is_volume_label() {
+            long_name.reset();
+            continue; // étiquette de volume
+        }
+
+        let name = parse_short_name(slot.short_name())?;
+
+        let is_dir = slot.attributes() & ATTR_DIRECTORY != 0;
+
+        entries.push(DirectoryEntry {
+            name,
+            long_name: long_name.finish(slot.short_name()),
+            entry_type: if is_dir {
+                EntryType::Directory
+            } else {
+                EntryType::File
+            },
+            attributes: slot.attributes(),
+            start_cluster: slot.start_cluster(),
+            size: slot.size(),
+            created: slot.created(),
+            modified: slot.modified(),
+            accessed: slot.accessed(),
+            location: slot.location,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Découpe un cluster de répertoire lu en slots de 32 octets.
+pub(crate) fn push_slots(cluster: u32, buf: &[u8], slots: &mut alloc::vec::Vec<DirectorySlot>) {
+    for (index, chunk) in buf.chunks_exact(32).enumerate() {
+        let mut raw = [0u8; 32];
+        raw.copy_from_slice(chunk);
+        slots.push(DirectorySlot {
+            location: SlotLocation {
+                cluster,
+                index: index as u32,
+            },
+            raw,
+        });
+    }
+}
+
+/// Vérifie qu’un nom 8.3 brut ne contient que des caractères autorisés.
+///
+/// Les entrées `.` et `..` ne sont pas des noms valides au sens de cette
+/// fonction et doivent être traitées à part.
+pub fn is_valid_short_name(raw: &[u8]) -> bool {
+    if raw.len() != 11 || raw[0] == b' ' {
+        return false;
     }
+
+    raw.iter().enumerate().all(|(i, &c)| match c {
+        0x05 => i == 0, // 0xE5 échappé en tête de nom
+        0x00..=0x1F => false,
+        b'"' | b'*' | b'+' | b',' | b'.' | b'/' | b':' | b';' | b'<' | b'=' | b'>' | b'?'
+        | b'[' | b'\\' | b']' | b'|' => false,
+        b'a'..=b'z' => false,
+        _ => true,
+    })
 }
 
 /// Parse un nom 8.3
-fn parse_short_name(raw: &[u8]) -> Result<alloc::string::String, DirectoryError> {
+pub(crate) fn parse_short_name(raw: &[u8]) -> Result<alloc::string::String, DirectoryError> {
     let name = core::str::from_utf8(&raw[0..8]).map_err(|_| DirectoryError::InvalidEntry)?;
     let ext = core::str::from_utf8(&raw[8..11]).map_err(|_| DirectoryError::InvalidEntry)?;
 
@@ -111,7 +399,7 @@ fn parse_short_name(raw: &[u8]) -> Result<alloc::string::String, DirectoryError>
     let ext = ext.trim_end();
 
     let full = if ext.is_empty() {
-        alloc::format!("{}", name)
+        alloc::string::String::from(name)
     } else {
         alloc::format!("{}.{}", name, ext)
     };
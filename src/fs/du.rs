@@ -0,0 +1,145 @@
+//! Occupation disque par répertoire (équivalent de `du`).
+//!
+//! Pour chaque répertoire, on additionne la taille logique des fichiers
+//! (taille déclarée dans les entrées) et la place réellement allouée
+//! (clusters des fichiers et des répertoires eux-mêmes).
+
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::clusters::ClusterReader;
+use crate::fs::directory::DirectoryReader;
+use crate::fs::error::FsError;
+use crate::fs::walk::{WalkOptions, Walker};
+
+/// Totaux d’un répertoire, sous-répertoires compris.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuEntry {
+    pub path: String,
+    /// Profondeur par rapport au point de départ (0 pour lui-même).
+    pub depth: usize,
+    /// Somme des tailles de fichiers.
+    pub logical: u64,
+    /// Octets des clusters alloués.
+    pub allocated: u64,
+    pub files: u32,
+    pub directories: u32,
+}
+
+/// Résultat de `du` : totaux par répertoire (ordre suffixe, le point de
+/// départ en dernier) et erreurs rencontrées en chemin.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DuReport {
+    pub entries: Vec<DuEntry>,
+    pub errors: Vec<FsError>,
+}
+
+pub struct Du<'a, D: BlockDevice> {
+    dirs: &'a DirectoryReader<'a, D>,
+    clusters: &'a ClusterReader<'a, D>,
+}
+
+impl<'a, D: BlockDevice> Du<'a, D> {
+    pub fn new(dirs: &'a DirectoryReader<'a, D>, clusters: &'a ClusterReader<'a, D>) -> Self {
+        Self { dirs, clusters }
+    }
+
+    /// Calcule les totaux du répertoire `start_cluster` ; seuls les
+    /// répertoires de profondeur au plus `max_depth` sont rapportés, mais
+    /// tous sont comptés.
+    pub fn du(&self, start_cluster: u32, path: &str, max_depth: Option<usize>) -> DuReport {
+        let mut report = DuReport::default();
+
+        let mut root = self.frame(path, 0);
+        root.allocated = self.allocated(start_cluster, path, &mut report.errors);
+        let mut stack = alloc::vec![root];
+        let mut seen = BTreeSet::from([start_cluster]);
+
+        for item in Walker::new(self.dirs).walk(start_cluster, path, WalkOptions::default()) {
+            let item = match item {
+                Ok(item) => item,
+                Err(e) => {
+                    report.errors.push(e);
+                    continue;
+                }
+            };
+
+            // Les répertoires plus profonds que l’entrée courante sont terminés.
+            while stack.len() > item.depth {
+                self.close(&mut stack, &mut report, max_depth);
+            }
+
+            // Un répertoire déjà compté (cycle, lien croisé) n’est pas parcouru.
+            if item.entry.entry_type.is_dir() && !seen.insert(item.entry.start_cluster) {
+                let parent = stack.last_mut().expect("start directory stays on the stack");
+                parent.directories += 1;
+                continue;
+            }
+
+            let allocated = if item.entry.start_cluster == 0 {
+                0
+            } else {
+                self.allocated(item.entry.start_cluster, &item.path, &mut report.errors)
+            };
+
+            if item.entry.entry_type.is_dir() {
+                let mut frame = self.frame(&item.path, item.depth);
+                frame.allocated = allocated;
+                stack.push(frame);
+            } else {
+                let parent = stack.last_mut().expect("start directory stays on the stack");
+                parent.logical += item.entry.size as u64;
+                parent.allocated += allocated;
+                parent.files += 1;
+            }
+        }
+
+        while !stack.is_empty() {
+            self.close(&mut stack, &mut report, max_depth);
+        }
+
+        report
+    }
+
+    fn frame(&self, path: &str, depth: usize) -> DuEntry {
+        DuEntry {
+            path: String::from(path),
+            depth,
+            logical: 0,
+            allocated: 0,
+            files: 0,
+            directories: 0,
+        }
+    }
+
+    /// Termine le répertoire au sommet de la pile et reporte ses totaux sur son parent.
+    fn close(&self, stack: &mut Vec<DuEntry>, report: &mut DuReport, max_depth: Option<usize>) {
+        let Some(done) = stack.pop() else {
+            return;
+        };
+
+        if let Some(parent) = stack.last_mut() {
+            parent.logical += done.logical;
+            parent.allocated += done.allocated;
+            parent.files += done.files;
+            parent.directories += done.directories + 1;
+        }
+
+        if max_depth.is_none_or(|max| done.depth <= max) {
+            report.entries.push(done);
+        }
+    }
+
+    /// Octets alloués à la chaîne commençant à `start`.
+    fn allocated(&self, start: u32, path: &str, errors: &mut Vec<FsError>) -> u64 {
+        match self.clusters.chain(start) {
+            Ok(chain) => chain.len() as u64 * self.clusters.cluster_size() as u64,
+            Err(e) => {
+                errors.push(FsError::from(e).with_cluster(start).with_path(path));
+                0
+            }
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Type d’erreur unifié du système de fichiers.
+//!
+//! Chaque module garde son propre type d’erreur ; `FsError` les regroupe
+//! sans perte d’information et permet d’y attacher le chemin ou le cluster
+//! concerné. La cause d’origine reste accessible via `Error::source`.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt;
+
+use crate::device::block_device::BlockDeviceError;
+use crate::fs::boot_sector::BootSectorError;
+use crate::fs::cat::CatError;
+use crate::fs::cd::CdError;
+use crate::fs::clusters::ClusterError;
+use crate::fs::create::CreateError;
+use crate::fs::directory::DirectoryError;
+use crate::fs::fat::FatError;
+use crate::fs::format::FormatError;
+use crate::fs::label::LabelError;
+use crate::fs::fsinfo::FsInfoError;
+use crate::fs::path::PathError;
+
+/// Erreur unifiée de toutes les opérations FAT32.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsError {
+    Device(BlockDeviceError),
+    BootSector(BootSectorError),
+    Fat(FatError),
+    FsInfo(FsInfoError),
+    Cluster(ClusterError),
+    Directory(DirectoryError),
+    Path(PathError),
+    Cat(CatError),
+    Cd(CdError),
+    Format(FormatError),
+    Label(LabelError),
+    Create(CreateError),
+    /// Erreur survenue lors du traitement du chemin donné.
+    InPath {
+        path: String,
+        source: Box<FsError>,
+    },
+    /// Erreur survenue lors du traitement du cluster donné.
+    InCluster {
+        cluster: u32,
+        source: Box<FsError>,
+    },
+}
+
+/// Résultat d’une opération du système de fichiers.
+pub type FsResult<T> = Result<T, FsError>;
+
+impl FsError {
+    /// Attache le chemin concerné à l’erreur.
+    pub fn with_path(self, path: &str) -> Self {
+        FsError::InPath {
+            path: String::from(path),
+            source: Box::new(self),
+        }
+    }
+
+    /// Attache le cluster concerné à l’erreur.
+    pub fn with_cluster(self, cluster: u32) -> Self {
+        FsError::InCluster {
+            cluster,
+            source: Box::new(self),
+        }
+    }
+
+    /// Retourne l’erreur sans son contexte (chemin, cluster).
+    pub fn kind(&self) -> &FsError {
+        match self {
+            FsError::InPath { source, .. } | FsError::InCluster { source, .. } => source.kind(),
+            e => e,
+        }
+    }
+
+    /// Chemin attaché à l’erreur, s’il y en a un.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            FsError::InPath { path, .. } => Some(path),
+            FsError::InCluster { source, .. } => source.path(),
+            _ => None,
+        }
+    }
+
+    /// Cluster attaché à l’erreur, s’il y en a un.
+    pub fn cluster(&self) -> Option<u32> {
+        match self {
+            FsError::InCluster { cluster, .. } => Some(*cluster),
+            FsError::InPath { source, .. } => source.cluster(),
+            _ => None,
+        }
+    }
+
+    /// Erreur du module d’origine, vue comme `core::error::Error`.
+    fn inner(&self) -> &(dyn core::error::Error + 'static) {
+        match self {
+            FsError::Device(e) => e,
+            FsError::BootSector(e) => e,
+            FsError::Fat(e) => e,
+            FsError::FsInfo(e) => e,
+            FsError::Cluster(e) => e,
+            FsError::Directory(e) => e,
+            FsError::Path(e) => e,
+            FsError::Cat(e) => e,
+            FsError::Cd(e) => e,
+            FsError::Format(e) => e,
+            FsError::Label(e) => e,
+            FsError::Create(e) => e,
+            FsError::InPath { source, .. } | FsError::InCluster { source, .. } => source.inner(),
+        }
+    }
+}
+
+impl fmt::Display for FsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsError::InPath { path, source } => write!(f, "{}: {}", path, source),
+            FsError::InCluster { cluster, source } => write!(f, "cluster {}: {}", cluster, source),
+            e => write!(f, "{}", e.inner()),
+        }
+    }
+}
+
+impl core::error::Error for FsError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        self.inner().source()
+    }
+}
+
+impl From<BlockDeviceError> for FsError {
+    fn from(e: BlockDeviceError) -> Self {
+        FsError::Device(e)
+    }
+}
+
+impl From<BootSectorError> for FsError {
+    fn from(e: BootSectorError) -> Self {
+        FsError::BootSector(e)
+    }
+}
+
+impl From<FatError> for FsError {
+    fn from(e: FatError) -> Self {
+        FsError::Fat(e)
+    }
+}
+
+impl From<FsInfoError> for FsError {
+    fn from(e: FsInfoError) -> Self {
+        FsError::FsInfo(e)
+    }
+}
+
+impl From<ClusterError> for FsError {
+    fn from(e: ClusterError) -> Self {
+        FsError::Cluster(e)
+    }
+}
+
+impl From<DirectoryError> for FsError {
+    fn from(e: DirectoryError) -> Self {
+        FsError::Directory(e)
+    }
+}
+
+impl From<PathError> for FsError {
+    fn from(e: PathError) -> Self {
+        FsError::Path(e)
+    }
+}
+
+impl From<CatError> for FsError {
+    fn from(e: CatError) -> Self {
+        FsError::Cat(e)
+    }
+}
+
+impl From<CdError> for FsError {
+    fn from(e: CdError) -> Self {
+        FsError::Cd(e)
+    }
+}
+
+impl From<FormatError> for FsError {
+    fn from(e: FormatError) -> Self {
+        FsError::Format(e)
+    }
+}
+
+impl From<LabelError> for FsError {
+    fn from(e: LabelError) -> Self {
+        FsError::Label(e)
+    }
+}
+
+impl From<CreateError> for FsError {
+    fn from(e: CreateError) -> Self {
+        FsError::Create(e)
+    }
+}
@@ -0,0 +1,261 @@
+//! Extraction de fichiers et d’arborescences vers le système de fichiers hôte.
+//!
+//! Les noms longs sont préférés aux noms 8.3 ; les caractères interdits sur
+//! l’hôte sont remplacés par `_`. Le contenu est copié cluster par cluster,
+//! sans charger les fichiers en mémoire. Une erreur sur un fichier est notée
+//! dans le rapport et l’extraction continue avec les suivants.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::string::String;
+use std::time::{Duration, SystemTime};
+use std::vec;
+use std::vec::Vec;
+
+use core::fmt;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::cat::{Cat, CatError};
+use crate::fs::directory::{DirectoryEntry, DirectoryReader};
+use crate::fs::error::{FsError, FsResult};
+use crate::fs::path::PathResolver;
+use crate::fs::timestamp::DateTime;
+use crate::fs::walk::{WalkOptions, Walker};
+
+/// Erreur d’extraction d’une entrée.
+#[derive(Debug)]
+pub enum ExtractError {
+    /// Lecture impossible dans l’image (le chemin est attaché à l’erreur).
+    Image(FsError),
+    /// Écriture impossible sur l’hôte.
+    Host { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractError::Image(e) => write!(f, "{}", e),
+            ExtractError::Host { path, .. } => write!(f, "{}: cannot write", path.display()),
+        }
+    }
+}
+
+impl core::error::Error for ExtractError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ExtractError::Image(e) => e.source(),
+            ExtractError::Host { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Options d’extraction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractOptions {
+    /// Restaure les dates de modification (interprétées comme UTC).
+    pub preserve_times: bool,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self { preserve_times: true }
+    }
+}
+
+/// Bilan d’une extraction.
+#[derive(Debug, Default)]
+pub struct ExtractReport {
+    pub files: u32,
+    pub directories: u32,
+    pub bytes: u64,
+    pub errors: Vec<ExtractError>,
+}
+
+pub struct Extractor<'a, D: BlockDevice> {
+    resolver: &'a PathResolver<'a, D>,
+    dirs: &'a DirectoryReader<'a, D>,
+    cat: &'a Cat<'a, D>,
+    chunk: usize,
+    options: ExtractOptions,
+}
+
+impl<'a, D: BlockDevice> Extractor<'a, D> {
+    pub fn new(
+        resolver: &'a PathResolver<'a, D>,
+        dirs: &'a DirectoryReader<'a, D>,
+        cat: &'a Cat<'a, D>,
+        chunk: usize,
+        options: ExtractOptions,
+    ) -> Self {
+        Self {
+            resolver,
+            dirs,
+            cat,
+            chunk: chunk.max(1),
+            options,
+        }
+    }
+
+    /// Copie `path` (fichier ou répertoire) dans le répertoire hôte `dest`.
+    ///
+    /// Un répertoire est recréé sous son nom dans `dest`, sauf la racine dont
+    /// le contenu est copié directement dans `dest`. Seule une erreur de
+    /// résolution de `path` fait échouer l’appel.
+    pub fn extract(&self, path: &str, cwd: u32, dest: &Path) -> FsResult<ExtractReport> {
+        let (cluster, entry) = self
+            .resolver
+            .resolve(path, cwd)
+            .map_err(|e| FsError::from(e).with_path(path))?;
+
+        let mut report = ExtractReport::default();
+        match entry {
+            Some(entry) if !entry.entry_type.is_dir() => {
+                let target = dest.join(host_name(entry.display_name()));
+                self.file(path, entry, &target, &mut report);
+            }
+            Some(entry) => {
+                let target = dest.join(host_name(entry.display_name()));
+                if self.directory(&target, &mut report) {
+                    self.tree(cluster, path, &target, &mut report);
+                    self.set_time(&target, entry.modified, &mut report);
+                }
+            }
+            None => self.tree(cluster, path, dest, &mut report),
+        }
+
+        Ok(report)
+    }
+
+    /// Copie le contenu du répertoire `cluster` dans `dest`.
+    fn tree(&self, cluster: u32, path: &str, dest: &Path, report: &mut ExtractReport) {
+        // hosts[d] : répertoire hôte des entrées de profondeur d + 1.
+        let mut hosts = vec![dest.to_path_buf()];
+        let mut dir_times = Vec::new();
+
+        for item in Walker::new(self.dirs).walk(cluster, path, WalkOptions::default()) {
+            let item = match item {
+                Ok(item) => item,
+                Err(e) => {
+                    report.errors.push(ExtractError::Image(e));
+                    continue;
+                }
+            };
+
+            hosts.truncate(item.depth);
+            let target = hosts[item.depth - 1].join(host_name(item.entry.display_name()));
+
+            if item.entry.entry_type.is_dir() {
+                // Le contenu d’un répertoire non créé échouera entrée par entrée.
+                self.directory(&target, report);
+                dir_times.push((target.clone(), item.entry.modified));
+                hosts.push(target);
+            } else {
+                self.file(&item.path, item.entry, &target, report);
+            }
+        }
+
+        // Après le contenu, qui modifie la date des répertoires.
+        for (target, modified) in dir_times.into_iter().rev() {
+            self.set_time(&target, modified, report);
+        }
+    }
+
+    fn directory(&self, target: &Path, report: &mut ExtractReport) -> bool {
+        match fs::create_dir_all(target) {
+            Ok(()) => {
+                report.directories += 1;
+                true
+            }
+            Err(source) => {
+                report.errors.push(ExtractError::Host {
+                    path: target.to_path_buf(),
+                    source,
+                });
+                false
+            }
+        }
+    }
+
+    fn file(&self, path: &str, entry: DirectoryEntry, target: &Path, report: &mut ExtractReport) {
+        let modified = entry.modified;
+
+        let file = match self.cat.open_entry(entry) {
+            Ok(file) => file,
+            Err(e) => {
+                report.errors.push(ExtractError::Image(FsError::from(e).with_path(path)));
+                return;
+            }
+        };
+
+        let host_error = |source| ExtractError::Host {
+            path: target.to_path_buf(),
+            source,
+        };
+        let mut out = match File::create(target) {
+            Ok(out) => out,
+            Err(e) => {
+                report.errors.push(host_error(e));
+                return;
+            }
+        };
+
+        let mut buf = vec![0u8; self.chunk];
+        let mut pos = 0;
+        while pos < file.size() {
+            let n = match self.cat.read_at(&file, pos, &mut buf) {
+                Ok(0) => {
+                    let e = CatError::ShortChain { size: file.size(), readable: pos };
+                    report.errors.push(ExtractError::Image(FsError::from(e).with_path(path)));
+                    return;
+                }
+                Ok(n) => n,
+                Err(e) => {
+                    report.errors.push(ExtractError::Image(FsError::from(e).with_path(path)));
+                    return;
+                }
+            };
+            if let Err(e) = out.write_all(&buf[..n]) {
+                report.errors.push(host_error(e));
+                return;
+            }
+            pos += n as u64;
+        }
+
+        report.files += 1;
+        report.bytes += pos;
+        drop(out);
+        self.set_time(target, modified, report);
+    }
+
+    fn set_time(&self, target: &Path, modified: Option<DateTime>, report: &mut ExtractReport) {
+        let Some(time) = modified.filter(|_| self.options.preserve_times) else {
+            return;
+        };
+
+        let result = File::options()
+            .write(!target.is_dir())
+            .read(target.is_dir())
+            .open(target)
+            .and_then(|f| f.set_modified(system_time(time)));
+        if let Err(source) = result {
+            report.errors.push(ExtractError::Host {
+                path: target.to_path_buf(),
+                source,
+            });
+        }
+    }
+}
+
+/// Nom utilisable sur l’hôte : `/` et NUL remplacés, `.` et `..` évités.
+pub fn host_name(name: &str) -> String {
+    match name {
+        "" | "." | ".." => String::from("_"),
+        name => name.replace(['/', '\0'], "_"),
+    }
+}
+
+/// Date FAT (sans fuseau) vue comme une date UTC.
+fn system_time(time: DateTime) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(time.to_unix().max(0) as u64)
+}
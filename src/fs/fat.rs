@@ -1,12 +1,23 @@
 //! Lecture et interprétation de la FAT32 (File Allocation Table)
 
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::fmt;
+
 use crate::device::block_device::{BlockDevice, BlockDeviceError};
 use crate::fs::boot_sector::BootSector;
+use crate::fs::fsinfo::{FsInfo, FsInfoError, FSINFO_UNKNOWN};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FatError {
     Io(BlockDeviceError),
     InvalidCluster,
+    /// Aucun cluster libre sur le volume.
+    NoFreeCluster,
+    /// Copie de la FAT au-delà de `fat_count`.
+    InvalidCopy(u8),
+    /// Mise à jour du secteur FSInfo impossible.
+    FsInfo(FsInfoError),
 }
 
 impl From<BlockDeviceError> for FatError {
@@ -15,15 +26,170 @@ impl From<BlockDeviceError> for FatError {
     }
 }
 
+impl fmt::Display for FatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FatError::Io(_) => write!(f, "cannot read FAT entry"),
+            FatError::InvalidCluster => write!(f, "invalid cluster in FAT"),
+            FatError::NoFreeCluster => write!(f, "no free cluster left"),
+            FatError::InvalidCopy(copy) => write!(f, "no FAT copy {}", copy),
+            FatError::FsInfo(_) => write!(f, "cannot update FSInfo sector"),
+        }
+    }
+}
+
+impl core::error::Error for FatError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            FatError::Io(e) => Some(e),
+            FatError::FsInfo(e) => Some(e),
+            FatError::InvalidCluster | FatError::NoFreeCluster | FatError::InvalidCopy(_) => None,
+        }
+    }
+}
+
+/// Entrée FAT d’un cluster libre.
+pub const FAT_FREE: u32 = 0x0000_0000;
+/// Entrée FAT d’un cluster défectueux.
+pub const FAT_BAD: u32 = 0x0FFF_FFF7;
+/// Marqueur de fin de chaîne écrit par ce crate.
+pub const FAT_EOC: u32 = 0x0FFF_FFFF;
+
+/// Bit de FAT[1] à 1 quand le volume a été démonté proprement.
+pub const FAT1_CLEAN: u32 = 0x0800_0000;
+/// Bit de FAT[1] à 0 quand une erreur disque a été rencontrée.
+pub const FAT1_NO_HARD_ERROR: u32 = 0x0400_0000;
+
+/// État du volume enregistré dans les bits de poids fort de FAT[1].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VolumeFlags {
+    /// Démonté proprement.
+    pub clean: bool,
+    /// Une erreur disque a été rencontrée.
+    pub hard_error: bool,
+}
+
+impl Default for VolumeFlags {
+    fn default() -> Self {
+        Self {
+            clean: true,
+            hard_error: false,
+        }
+    }
+}
+
+impl VolumeFlags {
+    /// Décode une valeur brute de FAT[1].
+    pub fn from_entry(value: u32) -> Self {
+        Self {
+            clean: value & FAT1_CLEAN != 0,
+            hard_error: value & FAT1_NO_HARD_ERROR == 0,
+        }
+    }
+
+    /// Reporte les drapeaux sur une valeur brute de FAT[1].
+    pub fn to_entry(self, value: u32) -> u32 {
+        let mut value = value & !(FAT1_CLEAN | FAT1_NO_HARD_ERROR);
+        if self.clean {
+            value |= FAT1_CLEAN;
+        }
+        if !self.hard_error {
+            value |= FAT1_NO_HARD_ERROR;
+        }
+        value
+    }
+
+    /// Une vérification complète du volume est conseillée.
+    pub fn needs_check(&self) -> bool {
+        !self.clean || self.hard_error
+    }
+}
+
+impl fmt::Display for VolumeFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.clean, self.hard_error) {
+            (true, false) => write!(f, "clean"),
+            (true, true) => write!(f, "hard error recorded"),
+            (false, false) => write!(f, "not cleanly unmounted"),
+            (false, true) => write!(f, "not cleanly unmounted, hard error recorded"),
+        }
+    }
+}
+
+/// Entrées lues par bloc (64 Kio) lors des parcours de la FAT.
+const CHUNK_ENTRIES: u32 = 16 * 1024;
+
+/// Entrée dont la valeur diffère d’une copie de la FAT à l’autre.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FatDiff {
+    pub cluster: u32,
+    /// Valeur (28 bits) de l’entrée dans chaque copie, dans l’ordre.
+    pub values: Vec<u32>,
+}
+
+/// Raison de l’arrêt du suivi de la chaîne.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainEnd {
+    /// Pas de chaîne (premier cluster à 0).
+    Empty,
+    /// Fin de chaîne normale.
+    EndOfChain,
+    /// Le cluster donné est marqué libre dans la FAT.
+    Free { cluster: u32 },
+    /// Le cluster donné est marqué défectueux.
+    Bad { cluster: u32 },
+    /// `from` désigne `value`, qui n’est pas un cluster du volume
+    /// (`from` vaut 0 quand c’est le premier cluster de l’entrée).
+    InvalidCluster { from: u32, value: u32 },
+    /// La chaîne revient sur `cluster`.
+    Loop { cluster: u32 },
+}
+
+impl fmt::Display for ChainEnd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainEnd::Empty => write!(f, "no clusters"),
+            ChainEnd::EndOfChain => write!(f, "end of chain"),
+            ChainEnd::Free { cluster } => write!(f, "cluster {} is free", cluster),
+            ChainEnd::Bad { cluster } => write!(f, "cluster {} is marked bad", cluster),
+            ChainEnd::InvalidCluster { from, value } => {
+                write!(f, "cluster {} points to invalid cluster {:#x}", from, value)
+            }
+            ChainEnd::Loop { cluster } => write!(f, "chain loops back to cluster {}", cluster),
+        }
+    }
+}
+
 /// Représente une FAT32 lisible
 pub struct Fat<'a, D: BlockDevice> {
     device: &'a D,
     boot: &'a BootSector,
+    /// Copie lue par `entry` et les parcours.
+    copy: u8,
 }
 
 impl<'a, D: BlockDevice> Fat<'a, D> {
+    /// Lit la copie active (voir `BootSector::active_fat`).
     pub fn new(device: &'a D, boot: &'a BootSector) -> Self {
-        Self { device, boot }
+        Self {
+            device,
+            boot,
+            copy: boot.active_fat(),
+        }
+    }
+
+    /// Lit la copie `copy`, par exemple quand la copie active est abîmée.
+    ///
+    /// Les écritures ne changent pas : toutes les copies si le miroir est
+    /// actif, la copie active sinon.
+    pub fn with_copy(mut self, copy: u8) -> Self {
+        self.copy = copy;
+        self
+    }
+
+    /// Copie lue.
+    pub fn copy(&self) -> u8 {
+        self.copy
     }
 
     /// Retourne le cluster suivant dans la chaîne.
@@ -31,25 +197,418 @@ impl<'a, D: BlockDevice> Fat<'a, D> {
     /// - `None` → fin de chaîne (EOC)
     /// - `Some(cluster)` → cluster suivant
     pub fn next_cluster(&self, cluster: u32) -> Result<Option<u32>, FatError> {
+        next_in_chain(self.entry(cluster)?)
+    }
+
+    /// Lit la valeur brute (28 bits) de l’entrée FAT d’un cluster.
+    pub fn entry(&self, cluster: u32) -> Result<u32, FatError> {
+        self.entry_in_copy(self.copy, cluster)
+    }
+
+    /// Suit la chaîne commençant à `first` jusqu’à sa fin ou jusqu’à la
+    /// première anomalie, sans jamais échouer sur une chaîne corrompue.
+    pub fn follow_chain(&self, first: u32) -> Result<(Vec<u32>, ChainEnd), FatError> {
+        let mut seen = BTreeSet::new();
+        follow_chain(first, self.boot.max_cluster(), |c| self.entry(c), |c| seen.insert(c))
+    }
+
+    /// Lit la valeur brute d’une entrée dans la copie `copy` de la FAT.
+    pub fn entry_in_copy(&self, copy: u8, cluster: u32) -> Result<u32, FatError> {
         if cluster < 2 {
             return Err(FatError::InvalidCluster);
         }
 
-        let fat_offset = cluster as u64 * 4;
-        let fat_start = self.boot.reserved_sectors as u64
-            * self.boot.bytes_per_sector as u64;
+        let mut entry = [0u8; 4];
+        self.device.read_at(self.entry_offset(copy, cluster)?, &mut entry)?;
+
+        Ok(u32::from_le_bytes(entry) & 0x0FFF_FFFF)
+    }
+
+    /// Écrit une entrée dans toutes les copies de la FAT, ou dans la seule
+    /// copie active si le miroir est désactivé.
+    ///
+    /// Les 4 bits de poids fort, réservés, sont préservés. Si l’écriture
+    /// d’une copie échoue, les copies précédentes reprennent (autant que
+    /// possible) leur ancienne valeur, ainsi que la copie en échec, pour
+    /// qu’elles restent identiques.
+    pub fn set_entry(&self, cluster: u32, value: u32) -> Result<(), FatError> {
+        if cluster < 2 {
+            return Err(FatError::InvalidCluster);
+        }
 
-        let offset = fat_start + fat_offset;
+        self.write_entry(cluster, |raw| (raw & 0xF000_0000) | (value & 0x0FFF_FFFF))
+    }
 
+    /// État du volume d’après FAT[1], dans la copie lue.
+    pub fn volume_flags(&self) -> Result<VolumeFlags, FatError> {
         let mut entry = [0u8; 4];
-        self.device.read_at(offset, &mut entry)?;
+        self.device.read_at(self.entry_offset(self.copy, 1)?, &mut entry)?;
+        Ok(VolumeFlags::from_entry(u32::from_le_bytes(entry)))
+    }
+
+    /// Enregistre l’état du volume dans FAT[1], copies comprises comme pour
+    /// `set_entry` ; les autres bits sont préservés.
+    pub fn set_volume_flags(&self, flags: VolumeFlags) -> Result<(), FatError> {
+        self.write_entry(1, |raw| flags.to_entry(raw))
+    }
+
+    /// Remplace l’entrée brute d’un cluster par `update(ancienne)` dans les
+    /// copies écrites, en rétablissant les anciennes valeurs en cas d’échec.
+    fn write_entry(&self, cluster: u32, update: impl Fn(u32) -> u32) -> Result<(), FatError> {
+        let copies = if self.boot.mirrored() {
+            0..self.boot.fat_count
+        } else {
+            let active = self.boot.active_fat();
+            active..active + 1
+        };
+
+        let mut written: Vec<(u64, [u8; 4])> = Vec::new();
+        for copy in copies {
+            let result = self.entry_offset(copy, cluster).and_then(|offset| {
+                let mut entry = [0u8; 4];
+                self.device.read_at(offset, &mut entry)?;
+
+                // Noté avant l’écriture, qui peut échouer à moitié.
+                written.push((offset, entry));
+                let raw = update(u32::from_le_bytes(entry));
+                self.device.write_at(offset, &raw.to_le_bytes())?;
+                Ok(())
+            });
+
+            if let Err(e) = result {
+                for (offset, old) in written {
+                    let _ = self.device.write_at(offset, &old);
+                }
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Alloue un cluster libre et le marque comme fin de chaîne.
+    ///
+    /// La recherche part de l’indication du FSInfo, qui est ensuite mis à jour.
+    pub fn allocate(&self) -> Result<u32, FatError> {
+        let max = self.boot.max_cluster();
+        let info = FsInfo::read(self.device, self.boot).ok();
+        let hint = info
+            .map(|i| i.next_free)
+            .filter(|c| (2..=max).contains(c))
+            .unwrap_or(2);
+
+        let mut found = None;
+        for cluster in (hint..=max).chain(2..hint) {
+            if self.entry(cluster)? == FAT_FREE {
+                found = Some(cluster);
+                break;
+            }
+        }
+        let cluster = found.ok_or(FatError::NoFreeCluster)?;
+
+        self.set_entry(cluster, FAT_EOC)?;
+        if let Some(info) = info
+            && let Err(e) = self.update_fs_info(info, -1, cluster + 1)
+        {
+            let _ = self.set_entry(cluster, FAT_FREE);
+            return Err(e);
+        }
+
+        Ok(cluster)
+    }
+
+    /// Alloue et chaîne `count` clusters, contigus si un tel espace existe.
+    ///
+    /// Le premier emplacement libre assez grand est retenu (recherche depuis
+    /// le cluster 2, pour un résultat reproductible) ; à défaut, les premiers
+    /// clusters libres sont chaînés dans l’ordre.
+    pub fn allocate_chain(&self, count: u32) -> Result<Vec<u32>, FatError> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut run_start = 0;
+        let mut run_len = 0;
+        let mut contiguous = None;
+        let mut scattered = Vec::new();
+        self.scan(self.copy, |cluster, value| {
+            if contiguous.is_some() || value != FAT_FREE {
+                run_len = 0;
+                return;
+            }
+            if run_len == 0 {
+                run_start = cluster;
+            }
+            run_len += 1;
+            if run_len == count {
+                contiguous = Some(run_start);
+            }
+            if scattered.len() < count as usize {
+                scattered.push(cluster);
+            }
+        })?;
+
+        let chain = match contiguous {
+            Some(start) => (start..start + count).collect(),
+            None if scattered.len() == count as usize => scattered,
+            None => return Err(FatError::NoFreeCluster),
+        };
+
+        // En cas d’échec, les clusters déjà chaînés sont rendus libres.
+        let release = |linked: &[u32]| {
+            for &cluster in linked {
+                let _ = self.set_entry(cluster, FAT_FREE);
+            }
+        };
+
+        let last = chain[chain.len() - 1];
+        for (i, &cluster) in chain.iter().enumerate() {
+            let next = chain.get(i + 1).copied().unwrap_or(FAT_EOC);
+            if let Err(e) = self.set_entry(cluster, next) {
+                release(&chain[..i]);
+                return Err(e);
+            }
+        }
+
+        if let Ok(info) = FsInfo::read(self.device, self.boot) {
+            let next_free = if last < self.boot.max_cluster() { last + 1 } else { 2 };
+            if let Err(e) = self.update_fs_info(info, -(count as i64), next_free) {
+                release(&chain);
+                return Err(e);
+            }
+        }
+
+        Ok(chain)
+    }
+
+    /// Libère toute la chaîne commençant à `start`.
+    ///
+    /// Une entrée déjà libre arrête le parcours, ce qui protège des boucles.
+    pub fn free_chain(&self, start: u32) -> Result<(), FatError> {
+        let info = FsInfo::read(self.device, self.boot).ok();
+        let max = self.boot.max_cluster();
+
+        let mut current = start;
+        let mut freed = 0i64;
+        loop {
+            let value = self.entry(current)?;
+            if value == FAT_FREE {
+                break;
+            }
+
+            self.set_entry(current, FAT_FREE)?;
+            freed += 1;
+
+            if (2..=max).contains(&value) {
+                current = value;
+            } else {
+                break;
+            }
+        }
+
+        if let Some(info) = info {
+            self.update_fs_info(info, freed, info.next_free.min(start))?;
+        }
+
+        Ok(())
+    }
+
+    /// Ajuste le nombre de clusters libres du FSInfo et son indication.
+    fn update_fs_info(&self, info: FsInfo, delta: i64, next_free: u32) -> Result<(), FatError> {
+        let free_count = if info.free_count == FSINFO_UNKNOWN {
+            FSINFO_UNKNOWN
+        } else {
+            (info.free_count as i64 + delta).max(0) as u32
+        };
+
+        FsInfo { free_count, next_free }
+            .write(self.device, self.boot)
+            .map_err(|e| match e {
+                FsInfoError::Io(e) => FatError::Io(e),
+                e => FatError::FsInfo(e),
+            })
+    }
+
+    /// Recopie intégralement la copie `from` de la FAT sur la copie `to`,
+    /// par blocs de 64 Kio.
+    pub fn sync_copy(&self, from: u8, to: u8) -> Result<(), FatError> {
+        for copy in [from, to] {
+            if copy >= self.boot.fat_count {
+                return Err(FatError::InvalidCopy(copy));
+            }
+        }
+
+        let fat_size = self.boot.sectors_per_fat as u64 * self.boot.bytes_per_sector as u64;
+        let mut chunk = alloc::vec![0u8; CHUNK_ENTRIES as usize * 4];
+        let mut done = 0;
+        while done < fat_size {
+            let len = (chunk.len() as u64).min(fat_size - done) as usize;
+            self.device.read_at(self.boot.fat_offset(from) + done, &mut chunk[..len])?;
+            self.device.write_at(self.boot.fat_offset(to) + done, &chunk[..len])?;
+            done += len as u64;
+        }
+        Ok(())
+    }
+
+    /// Recopie la copie `from` sur toutes les autres.
+    pub fn resync(&self, from: u8) -> Result<(), FatError> {
+        if from >= self.boot.fat_count {
+            return Err(FatError::InvalidCopy(from));
+        }
+
+        for to in (0..self.boot.fat_count).filter(|&to| to != from) {
+            self.sync_copy(from, to)?;
+        }
+        Ok(())
+    }
+
+    /// Compare toutes les copies de la FAT, entrée par entrée (0 à
+    /// `max_cluster`) ; rend les entrées qui diffèrent.
+    ///
+    /// Les copies sont lues en parallèle par blocs de 64 Kio, sans être
+    /// chargées en entier.
+    pub fn diff(&self) -> Result<Vec<FatDiff>, FatError> {
+        let copies = self.boot.fat_count as usize;
+        let mut diffs = Vec::new();
+        if copies < 2 {
+            return Ok(diffs);
+        }
+
+        let max = self.boot.max_cluster();
+        let mut chunks = alloc::vec![alloc::vec![0u8; CHUNK_ENTRIES as usize * 4]; copies];
+        let mut cluster = 0;
+
+        while cluster <= max {
+            let count = CHUNK_ENTRIES.min(max - cluster + 1);
+            for (copy, chunk) in chunks.iter_mut().enumerate() {
+                let offset = entry_offset(self.boot, copy as u8, cluster)?;
+                self.device.read_at(offset, &mut chunk[..count as usize * 4])?;
+            }
+
+            for i in 0..count as usize {
+                let value = |chunk: &Vec<u8>| {
+                    let e = &chunk[i * 4..i * 4 + 4];
+                    u32::from_le_bytes([e[0], e[1], e[2], e[3]]) & 0x0FFF_FFFF
+                };
+                let first = value(&chunks[0]);
+                if chunks[1..].iter().any(|chunk| value(chunk) != first) {
+                    diffs.push(FatDiff {
+                        cluster: cluster + i as u32,
+                        values: chunks.iter().map(value).collect(),
+                    });
+                }
+            }
+            cluster += count;
+        }
+
+        Ok(diffs)
+    }
+
+    /// Lit les entrées 0..=`max_cluster` d’une copie de la FAT.
+    ///
+    /// La lecture se fait par blocs : une FAT annoncée plus grande que le
+    /// périphérique échoue en atteignant sa fin, avant d’être allouée en entier.
+    pub fn read_table(&self, copy: u8) -> Result<Vec<u32>, FatError> {
+        let mut head = [0u8; 8];
+        self.device.read_at(self.boot.fat_offset(copy), &mut head)?;
+
+        let mut table = Vec::new();
+        for e in head.chunks_exact(4) {
+            table.push(u32::from_le_bytes([e[0], e[1], e[2], e[3]]) & 0x0FFF_FFFF);
+        }
+        self.scan(copy, |_, value| table.push(value))?;
+
+        Ok(table)
+    }
+
+    /// Parcourt les entrées 2..=`max_cluster` d’une copie, par blocs de 64 Kio,
+    /// sans charger toute la FAT en mémoire.
+    pub fn scan<F: FnMut(u32, u32)>(&self, copy: u8, mut f: F) -> Result<(), FatError> {
+        let max = self.boot.max_cluster();
+        let mut raw = alloc::vec![0u8; CHUNK_ENTRIES as usize * 4];
+        let mut cluster = 2;
+
+        while cluster <= max {
+            let count = CHUNK_ENTRIES.min(max - cluster + 1);
+            let buf = &mut raw[..count as usize * 4];
+            self.device.read_at(self.entry_offset(copy, cluster)?, buf)?;
+
+            for (i, e) in buf.chunks_exact(4).enumerate() {
+                let value = u32::from_le_bytes([e[0], e[1], e[2], e[3]]) & 0x0FFF_FFFF;
+                f(cluster + i as u32, value);
+            }
+            cluster += count;
+        }
+
+        Ok(())
+    }
+
+    /// Offset disque d’une entrée dans la copie `copy` de la FAT.
+    fn entry_offset(&self, copy: u8, cluster: u32) -> Result<u64, FatError> {
+        entry_offset(self.boot, copy, cluster)
+    }
+}
+
+/// Offset disque de l’entrée d’un cluster dans la copie `copy` de la FAT.
+pub(crate) fn entry_offset(boot: &BootSector, copy: u8, cluster: u32) -> Result<u64, FatError> {
+    let fat_size = boot.sectors_per_fat as u64 * boot.bytes_per_sector as u64;
+    let fat_offset = cluster as u64 * 4;
+
+    if copy >= boot.fat_count.max(1) {
+        return Err(FatError::InvalidCopy(copy));
+    }
+    if fat_offset + 4 > fat_size {
+        return Err(FatError::InvalidCluster);
+    }
+
+    Ok(boot.fat_offset(copy) + fat_offset)
+}
+
+/// Cluster suivant d’après la valeur (28 bits) d’une entrée FAT.
+pub(crate) fn next_in_chain(value: u32) -> Result<Option<u32>, FatError> {
+    match value {
+        0x0000_0000 => Err(FatError::InvalidCluster),
+        0x0FFF_FFF8..=0x0FFF_FFFF => Ok(None), // End Of Chain
+        next => Ok(Some(next)),
+    }
+}
+
+/// Suit une chaîne dont les entrées sont lues par `entry`.
+///
+/// `claim` est appelé sur chaque cluster avant son ajout ; s’il le refuse
+/// (cluster déjà parcouru), le suivi s’arrête sur `ChainEnd::Loop`.
+pub fn follow_chain<E>(
+    first: u32,
+    max_cluster: u32,
+    mut entry: impl FnMut(u32) -> Result<u32, E>,
+    mut claim: impl FnMut(u32) -> bool,
+) -> Result<(Vec<u32>, ChainEnd), E> {
+    let mut chain = Vec::new();
+
+    if first == 0 {
+        return Ok((chain, ChainEnd::Empty));
+    }
+    if !(2..=max_cluster).contains(&first) {
+        return Ok((chain, ChainEnd::InvalidCluster { from: 0, value: first }));
+    }
+
+    let mut cluster = first;
+    loop {
+        if !claim(cluster) {
+            return Ok((chain, ChainEnd::Loop { cluster }));
+        }
+        chain.push(cluster);
 
-        let value = u32::from_le_bytes(entry) & 0x0FFF_FFFF;
+        let next = match entry(cluster)? {
+            FAT_FREE => return Ok((chain, ChainEnd::Free { cluster })),
+            FAT_BAD => return Ok((chain, ChainEnd::Bad { cluster })),
+            0x0FFF_FFF8..=0x0FFF_FFFF => return Ok((chain, ChainEnd::EndOfChain)),
+            next => next,
+        };
 
-        match value {
-            0x0000_0000 => Err(FatError::InvalidCluster),
-            0x0FFF_FFF8..=0x0FFF_FFFF => Ok(None), // End Of Chain
-            next => Ok(Some(next)),
+        if !(2..=max_cluster).contains(&next) {
+            return Ok((chain, ChainEnd::InvalidCluster { from: cluster, value: next }));
         }
+        cluster = next;
     }
 }
@@ -0,0 +1,484 @@
+//! Point d’entrée d’un volume FAT32 monté.
+//!
+//! `FileSystem` garde le périphérique et le Boot Sector, et construit à la
+//! demande les lecteurs (`Fat`, `ClusterReader`, ...) dont chaque opération
+//! a besoin. Toutes ses opérations renvoient `FsError`, avec le chemin
+//! concerné lorsqu’il y en a un.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::device::block_device::{BlockDevice, BlockDeviceError};
+use crate::fs::backup::{self, BootFieldDiff, BootSource};
+use crate::fs::boot_sector::{BootSector, BootSectorError, Truncation};
+use crate::fs::cat::{Cat, CatError, OpenFile};
+use crate::fs::cd::Cd;
+use crate::fs::clusters::ClusterReader;
+use crate::fs::create::{Create, EntryTimes};
+use crate::fs::directory::{DirectoryEntry, DirectoryReader};
+use crate::fs::du::{Du, DuReport};
+use crate::fs::error::{FsError, FsResult};
+#[cfg(feature = "std")]
+use crate::fs::extract::{ExtractOptions, ExtractReport, Extractor};
+use crate::fs::fat::{Fat, FatDiff, FatError, VolumeFlags};
+use crate::fs::find::{Find, FindOptions};
+#[cfg(feature = "std")]
+use crate::fs::import::{ImportOptions, ImportReport, Importer};
+use crate::fs::inspect::Inspect;
+use crate::fs::label::Label;
+use crate::fs::ls::Ls;
+use crate::fs::path::{PathError, PathResolver};
+use crate::fs::stat::{EntryStat, Stat};
+use crate::fs::statfs::{FreeCount, FsStats, StatFs};
+use crate::fs::tree::Tree;
+use crate::fs::view::{hexdump, Count, View};
+use crate::fs::walk::{WalkEntry, WalkOptions, Walker};
+
+/// Options de montage.
+#[derive(Debug, Clone, Default)]
+pub struct MountOptions {
+    /// Monte un volume qui déborde du périphérique : seuls les clusters
+    /// situés sur le périphérique restent lisibles, les autres accès
+    /// échouent avec `OutOfBounds`.
+    pub salvage: bool,
+    /// Autorise les écritures (sinon elles échouent avec
+    /// `BlockDeviceError::ReadOnly`) et marque le volume comme non démonté
+    /// (FAT[1]) jusqu’à `unmount`.
+    pub writable: bool,
+}
+
+/// Volume FAT32 monté sur un périphérique.
+pub struct FileSystem<'a, D: BlockDevice> {
+    device: &'a D,
+    boot: BootSector,
+    truncation: Option<Truncation>,
+    boot_source: BootSource,
+    /// Copie de la FAT lue (la copie active, sauf `with_fat_copy`).
+    fat_copy: u8,
+    /// État de FAT[1] lu par un montage en écriture, avant marquage.
+    mounted_flags: Option<VolumeFlags>,
+    /// Le montage a marqué le volume comme non démonté.
+    marked_dirty: bool,
+    writable: bool,
+}
+
+impl<'a, D: BlockDevice> FileSystem<'a, D> {
+    /// Lit le Boot Sector et monte le volume, après avoir vérifié sa
+    /// géométrie et qu’il tient sur le périphérique.
+    pub fn mount(device: &'a D) -> FsResult<Self> {
+        Self::mount_with(device, &MountOptions::default())
+    }
+
+    /// Monte le volume selon `options`.
+    ///
+    /// Si le Boot Sector est invalide, le volume est monté depuis sa copie de
+    /// secours (voir `boot_source`). Seul un montage en écriture accepte
+    /// les modifications ; il marque le volume comme non démonté, s’il était
+    /// propre, jusqu’à `unmount`.
+    pub fn mount_with(device: &'a D, options: &MountOptions) -> FsResult<Self> {
+        let (boot, boot_source) = backup::read_boot(device)?;
+        let truncation = match boot.validate(device.size()) {
+            Ok(()) => None,
+            Err(BootSectorError::Truncated(t)) if options.salvage => Some(t),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut mounted_flags = None;
+        let mut marked_dirty = false;
+        if options.writable {
+            let fat = Fat::new(device, &boot);
+            let flags = fat.volume_flags()?;
+            if flags.clean {
+                fat.set_volume_flags(VolumeFlags { clean: false, ..flags })?;
+                marked_dirty = true;
+            }
+            mounted_flags = Some(flags);
+        }
+
+        Ok(Self {
+            device,
+            fat_copy: boot.active_fat(),
+            boot,
+            truncation,
+            boot_source,
+            mounted_flags,
+            marked_dirty,
+            writable: options.writable,
+        })
+    }
+
+    /// Monte le volume avec un Boot Sector déjà connu, sans vérification,
+    /// en lecture seule.
+    pub fn new(device: &'a D, boot: BootSector) -> Self {
+        Self {
+            device,
+            fat_copy: boot.active_fat(),
+            boot,
+            truncation: None,
+            boot_source: BootSource::Primary,
+            mounted_flags: None,
+            marked_dirty: false,
+            writable: false,
+        }
+    }
+
+    /// Débordement du volume, s’il a été monté malgré tout (`salvage`).
+    pub fn truncation(&self) -> Option<Truncation> {
+        self.truncation
+    }
+
+    /// Copie du Boot Sector d’où vient la géométrie du volume.
+    pub fn boot_source(&self) -> BootSource {
+        self.boot_source
+    }
+
+    /// Champs qui diffèrent entre le Boot Sector et sa copie de secours.
+    pub fn compare_boot(&self) -> FsResult<Vec<BootFieldDiff>> {
+        Ok(backup::compare(self.device)?)
+    }
+
+    /// Restaure une copie du Boot Sector à partir de `from`.
+    pub fn restore_boot(&self, from: BootSource) -> FsResult<()> {
+        self.check_writable()?;
+        Ok(backup::restore(self.device, from)?)
+    }
+
+    /// État du volume d’après FAT[1], tel qu’il était au montage.
+    pub fn volume_flags(&self) -> FsResult<VolumeFlags> {
+        match self.mounted_flags {
+            Some(flags) => Ok(flags),
+            None => Ok(self.fat().volume_flags()?),
+        }
+    }
+
+    /// Démonte le volume : s’il a été marqué au montage, il est de nouveau
+    /// noté comme démonté proprement.
+    pub fn unmount(self) -> FsResult<()> {
+        if self.marked_dirty {
+            let fat = self.fat();
+            let flags = fat.volume_flags()?;
+            fat.set_volume_flags(VolumeFlags { clean: true, ..flags })?;
+        }
+        Ok(())
+    }
+
+    /// Lit la FAT dans la copie `copy`, quand la copie active est abîmée.
+    pub fn with_fat_copy(mut self, copy: u8) -> FsResult<Self> {
+        if copy >= self.boot.fat_count {
+            return Err(FatError::InvalidCopy(copy).into());
+        }
+        self.fat_copy = copy;
+        Ok(self)
+    }
+
+    /// Copie de la FAT lue.
+    pub fn fat_copy(&self) -> u8 {
+        self.fat_copy
+    }
+
+    /// Entrées qui diffèrent entre les copies de la FAT.
+    pub fn fat_diff(&self) -> FsResult<Vec<FatDiff>> {
+        Ok(self.fat().diff()?)
+    }
+
+    /// Recopie la copie `from` de la FAT sur toutes les autres.
+    pub fn resync_fat(&self, from: u8) -> FsResult<()> {
+        self.check_writable()?;
+        Ok(self.fat().resync(from)?)
+    }
+
+    pub fn device(&self) -> &'a D {
+        self.device
+    }
+
+    pub fn boot(&self) -> &BootSector {
+        &self.boot
+    }
+
+    /// Cluster de la racine, répertoire courant initial.
+    pub fn root_cluster(&self) -> u32 {
+        self.boot.root_cluster
+    }
+
+    /// Liste un répertoire (`None` : répertoire courant).
+    pub fn list(&self, path: Option<&str>, cwd: u32) -> FsResult<Vec<DirectoryEntry>> {
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let resolver = PathResolver::new(&self.boot, &dirs);
+
+        Ls::new(&resolver)
+            .list(path, cwd)
+            .map_err(|e| FsError::from(e).with_path(path.unwrap_or(".")))
+    }
+
+    /// Lit le contenu complet d’un fichier.
+    pub fn read_file(&self, path: &str, cwd: u32) -> FsResult<Vec<u8>> {
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let resolver = PathResolver::new(&self.boot, &dirs);
+
+        Cat::new(&resolver, &clusters)
+            .cat(path, cwd)
+            .map_err(|e| FsError::from(e).with_path(path))
+    }
+
+    /// Début d’un fichier (`head -c` / `head -n`).
+    pub fn head(&self, path: &str, cwd: u32, count: Count) -> FsResult<Vec<u8>> {
+        self.view(path, cwd, |view, file| view.head(file, count))
+    }
+
+    /// Fin d’un fichier (`tail -c` / `tail -n`) ; seuls les derniers clusters sont lus.
+    pub fn tail(&self, path: &str, cwd: u32, count: Count) -> FsResult<Vec<u8>> {
+        self.view(path, cwd, |view, file| view.tail(file, count))
+    }
+
+    /// `hexdump -C` de `len` octets (tout le reste si `None`) à partir de `offset`.
+    pub fn hexdump(&self, path: &str, cwd: u32, offset: u64, len: Option<u64>) -> FsResult<String> {
+        let data = self.view(path, cwd, |view, file| {
+            view.read(file, offset, len.unwrap_or(u64::MAX))
+        })?;
+
+        Ok(hexdump(&data, offset))
+    }
+
+    /// Ouvre `path` et lui applique une lecture partielle.
+    fn view<T>(
+        &self,
+        path: &str,
+        cwd: u32,
+        f: impl FnOnce(&View<'_, D>, &OpenFile) -> Result<T, CatError>,
+    ) -> FsResult<T> {
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let resolver = PathResolver::new(&self.boot, &dirs);
+        let cat = Cat::new(&resolver, &clusters);
+        let view = View::new(&cat, clusters.cluster_size());
+
+        cat.open(path, cwd)
+            .and_then(|file| f(&view, &file))
+            .map_err(|e| FsError::from(e).with_path(path))
+    }
+
+    /// Retourne le cluster du répertoire désigné par `path`.
+    pub fn change_dir(&self, path: &str, cwd: u32) -> FsResult<u32> {
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let resolver = PathResolver::new(&self.boot, &dirs);
+
+        Cd::new(&resolver)
+            .cd(path, cwd)
+            .map_err(|e| FsError::from(e).with_path(path))
+    }
+
+    /// Informations détaillées sur une entrée, chaîne de clusters comprise.
+    pub fn stat(&self, path: &str, cwd: u32) -> FsResult<EntryStat> {
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let resolver = PathResolver::new(&self.boot, &dirs);
+
+        Stat::new(&resolver, &fat, &self.boot)
+            .stat(path, cwd)
+            .map_err(|e| e.with_path(path))
+    }
+
+    /// Copie `path` (fichier ou répertoire) dans le répertoire hôte `dest`.
+    #[cfg(feature = "std")]
+    pub fn extract(
+        &self,
+        path: &str,
+        cwd: u32,
+        dest: &std::path::Path,
+        options: ExtractOptions,
+    ) -> FsResult<ExtractReport> {
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let resolver = PathResolver::new(&self.boot, &dirs);
+        let cat = Cat::new(&resolver, &clusters);
+
+        Extractor::new(&resolver, &dirs, &cat, clusters.cluster_size(), options)
+            .extract(path, cwd, dest)
+    }
+
+    /// Crée le répertoire `path` ; son parent doit exister.
+    pub fn mkdir(&self, path: &str, cwd: u32, times: &EntryTimes) -> FsResult<u32> {
+        self.check_writable()?;
+        let (parent, name) = split_parent(path);
+        let parent = self.change_dir(parent, cwd)?;
+
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+
+        Create::new(&self.boot, &fat, &clusters, &dirs)
+            .mkdir(parent, name, times)
+            .map(|dir| dir.cluster)
+            .map_err(|e| FsError::from(e).with_path(path))
+    }
+
+    /// Crée le fichier `path` avec le contenu `data` ; son parent doit exister.
+    pub fn write_file(&self, path: &str, cwd: u32, data: &[u8], times: &EntryTimes) -> FsResult<()> {
+        self.check_writable()?;
+        let (parent, name) = split_parent(path);
+        let parent = self.change_dir(parent, cwd)?;
+
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let create = Create::new(&self.boot, &fat, &clusters, &dirs);
+
+        create
+            .create_file(parent, name, data.len() as u64, times)
+            .and_then(|file| match create.write_at(&file, 0, data) {
+                Ok(()) => create.finish(&file),
+                Err(e) => {
+                    create.discard(&file);
+                    Err(e)
+                }
+            })
+            .map(|_| ())
+            .map_err(|e| FsError::from(e).with_path(path))
+    }
+
+    /// Copie le fichier ou le contenu du répertoire hôte `source` dans le
+    /// répertoire `dest` de l’image.
+    #[cfg(feature = "std")]
+    pub fn import(
+        &self,
+        source: &std::path::Path,
+        dest: &str,
+        cwd: u32,
+        options: ImportOptions,
+    ) -> FsResult<ImportReport> {
+        self.check_writable()?;
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let resolver = PathResolver::new(&self.boot, &dirs);
+        let cd = Cd::new(&resolver);
+        let create = Create::new(&self.boot, &fat, &clusters, &dirs);
+
+        Importer::new(&cd, &dirs, &create, clusters.cluster_size(), options)
+            .import(source, dest, cwd)
+    }
+
+    /// Accès brut aux secteurs, clusters, entrées FAT et slots.
+    pub fn inspect(&self) -> Inspect<'_, D> {
+        Inspect::new(self.device, &self.boot).with_fat_copy(self.fat_copy)
+    }
+
+    /// Étiquette du volume (`None` si le volume n’en a pas).
+    pub fn label(&self) -> FsResult<Option<String>> {
+        Ok(Label::new(self.device, &self.boot).with_fat_copy(self.fat_copy).get()?)
+    }
+
+    /// Change l’étiquette du volume ; une chaîne vide la supprime.
+    pub fn set_label(&self, label: &str) -> FsResult<()> {
+        self.check_writable()?;
+        Ok(Label::new(self.device, &self.boot).with_fat_copy(self.fat_copy).set(label)?)
+    }
+
+    /// Occupation du volume.
+    pub fn statfs(&self, mode: FreeCount) -> FsResult<FsStats> {
+        StatFs::new(self.device, &self.boot)
+            .with_fat_copy(self.fat_copy)
+            .stats(mode)
+    }
+
+    /// Parcourt récursivement le répertoire `path`, entrée par entrée.
+    pub fn walk<F: FnMut(FsResult<WalkEntry>)>(
+        &self,
+        path: &str,
+        cwd: u32,
+        options: WalkOptions,
+        mut f: F,
+    ) -> FsResult<()> {
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let start = self.resolve_dir(&dirs, path, cwd)?;
+
+        Walker::new(&dirs).walk(start, path, options).for_each(&mut f);
+        Ok(())
+    }
+
+    /// Cherche sous `path` les entrées satisfaisant `options`, au fil du parcours.
+    pub fn find<F: FnMut(FsResult<WalkEntry>)>(
+        &self,
+        path: &str,
+        cwd: u32,
+        options: FindOptions,
+        mut f: F,
+    ) -> FsResult<()> {
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let start = self.resolve_dir(&dirs, path, cwd)?;
+
+        Find::new(&dirs).find(start, path, options).for_each(&mut f);
+        Ok(())
+    }
+
+    /// Totaux logiques et alloués par répertoire sous `path`.
+    pub fn du(&self, path: &str, cwd: u32, max_depth: Option<usize>) -> FsResult<DuReport> {
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let start = self.resolve_dir(&dirs, path, cwd)?;
+
+        Ok(Du::new(&dirs, &clusters).du(start, path, max_depth))
+    }
+
+    /// Arborescence de `path` sous forme de texte.
+    pub fn tree(&self, path: &str, cwd: u32, max_depth: Option<usize>) -> FsResult<String> {
+        let fat = self.fat();
+        let clusters = ClusterReader::new(self.device, &self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+        let start = self.resolve_dir(&dirs, path, cwd)?;
+
+        Ok(Tree::new(&dirs).render(start, path, max_depth))
+    }
+
+    /// Refuse les écritures sur un montage qui n’est pas `writable`.
+    fn check_writable(&self) -> FsResult<()> {
+        if !self.writable {
+            return Err(BlockDeviceError::ReadOnly.into());
+        }
+        Ok(())
+    }
+
+    /// FAT lue dans la copie choisie.
+    fn fat(&self) -> Fat<'_, D> {
+        Fat::new(self.device, &self.boot).with_copy(self.fat_copy)
+    }
+
+    /// Cluster du répertoire désigné par `path`.
+    fn resolve_dir(&self, dirs: &DirectoryReader<'_, D>, path: &str, cwd: u32) -> FsResult<u32> {
+        let resolver = PathResolver::new(&self.boot, dirs);
+        let (cluster, entry) = resolver
+            .resolve(path, cwd)
+            .map_err(|e| FsError::from(e).with_path(path))?;
+
+        match entry {
+            Some(e) if !e.entry_type.is_dir() => {
+                Err(FsError::from(PathError::NotADirectory).with_path(path))
+            }
+            _ => Ok(cluster),
+        }
+    }
+}
+
+/// Sépare `path` en répertoire parent et nom de la dernière composante.
+fn split_parent(path: &str) -> (&str, &str) {
+    let path = path.trim_end_matches('/');
+    match path.rsplit_once('/') {
+        Some(("", name)) => ("/", name),
+        Some((parent, name)) => (parent, name),
+        None => (".", path),
+    }
+}
@@ -0,0 +1,232 @@
+//! Recherche d’entrées dans une arborescence (équivalent de `find`).
+//!
+//! Les critères sont évalués au fil du parcours : les résultats sont
+//! produits un par un, sans lire toute l’arborescence au préalable.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::directory::{DirectoryEntry, DirectoryReader, EntryType};
+use crate::fs::error::FsResult;
+use crate::fs::timestamp::DateTime;
+use crate::fs::walk::{Walk, WalkEntry, WalkOptions, WalkOrder, Walker};
+
+/// Critères de recherche ; une entrée doit tous les satisfaire.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FindOptions {
+    /// Motif glob (`*`, `?`, `[a-z]`, `[!x]`) testé sur le nom 8.3 et le nom long.
+    pub name: Option<String>,
+    /// Motif sensible à la casse (insensible par défaut, comme FAT).
+    pub case_sensitive: bool,
+    pub entry_type: Option<EntryType>,
+    /// Taille minimale, incluse.
+    pub min_size: Option<u64>,
+    /// Taille maximale, incluse.
+    pub max_size: Option<u64>,
+    /// Modifié à partir de cette date (incluse).
+    pub modified_after: Option<DateTime>,
+    /// Modifié avant cette date (exclue).
+    pub modified_before: Option<DateTime>,
+    /// Profondeur maximale (1 : contenu direct du point de départ).
+    pub max_depth: Option<usize>,
+}
+
+impl FindOptions {
+    /// Vrai si l’entrée satisfait tous les critères.
+    ///
+    /// Une entrée sans date de modification ne satisfait aucun critère de date.
+    pub fn matches(&self, entry: &DirectoryEntry) -> bool {
+        if let Some(pattern) = &self.name {
+            let short = glob_match(pattern, &entry.name, self.case_sensitive);
+            let long = entry
+                .long_name
+                .as_deref()
+                .is_some_and(|n| glob_match(pattern, n, self.case_sensitive));
+            if !short && !long {
+                return false;
+            }
+        }
+
+        if self.entry_type.as_ref().is_some_and(|t| *t != entry.entry_type) {
+            return false;
+        }
+
+        let size = entry.size as u64;
+        if self.min_size.is_some_and(|min| size < min) || self.max_size.is_some_and(|max| size > max) {
+            return false;
+        }
+
+        if self.modified_after.is_some() || self.modified_before.is_some() {
+            let Some(modified) = entry.modified else {
+                return false;
+            };
+            if self.modified_after.is_some_and(|after| modified < after)
+                || self.modified_before.is_some_and(|before| modified >= before)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+pub struct Find<'a, D: BlockDevice> {
+    dirs: &'a DirectoryReader<'a, D>,
+}
+
+impl<'a, D: BlockDevice> Find<'a, D> {
+    pub fn new(dirs: &'a DirectoryReader<'a, D>) -> Self {
+        Self { dirs }
+    }
+
+    /// Cherche sous le répertoire `start_cluster`, dont le chemin est `path`.
+    pub fn find(&self, start_cluster: u32, path: &str, options: FindOptions) -> FindIter<'a, D> {
+        let walk = Walker::new(self.dirs).walk(
+            start_cluster,
+            path,
+            WalkOptions {
+                order: WalkOrder::DepthFirst,
+                max_depth: options.max_depth,
+            },
+        );
+
+        FindIter { walk, options }
+    }
+}
+
+/// Itérateur des entrées trouvées ; les erreurs de lecture sont transmises
+/// telles quelles et la recherche continue.
+pub struct FindIter<'a, D: BlockDevice> {
+    walk: Walk<'a, D>,
+    options: FindOptions,
+}
+
+impl<'a, D: BlockDevice> Iterator for FindIter<'a, D> {
+    type Item = FsResult<WalkEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.walk.find(|item| match item {
+            Ok(found) => self.options.matches(&found.entry),
+            Err(_) => true,
+        })
+    }
+}
+
+/// Élément d’un motif glob.
+#[derive(Debug)]
+enum Token {
+    /// `*` : n’importe quelle suite de caractères.
+    Any,
+    /// `?` : un caractère.
+    One,
+    Char(char),
+    /// `[...]` : intervalles de caractères, éventuellement niés par `!` ou `^`.
+    Class { negated: bool, ranges: Vec<(char, char)> },
+}
+
+impl Token {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Token::Any | Token::One => true,
+            Token::Char(p) => *p == c,
+            Token::Class { negated, ranges } => {
+                ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != *negated
+            }
+        }
+    }
+}
+
+/// Teste un nom contre un motif glob.
+pub fn glob_match(pattern: &str, name: &str, case_sensitive: bool) -> bool {
+    let fold = |c: char| {
+        if case_sensitive {
+            c
+        } else {
+            c.to_lowercase().next().unwrap_or(c)
+        }
+    };
+
+    let tokens = parse_glob(&pattern.chars().map(fold).collect::<Vec<_>>());
+    let name: Vec<char> = name.chars().map(fold).collect();
+
+    // Retour arrière sur la dernière étoile rencontrée.
+    let (mut t, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match tokens.get(t) {
+            Some(Token::Any) => {
+                star = Some((t, n));
+                t += 1;
+                continue;
+            }
+            Some(token) if token.matches(name[n]) => {
+                t += 1;
+                n += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match star {
+            Some((star_t, star_n)) => {
+                t = star_t + 1;
+                n = star_n + 1;
+                star = Some((star_t, star_n + 1));
+            }
+            None => return false,
+        }
+    }
+
+    tokens[t..].iter().all(|token| matches!(token, Token::Any))
+}
+
+fn parse_glob(pattern: &[char]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < pattern.len() {
+        let token = match pattern[i] {
+            '*' => Token::Any,
+            '?' => Token::One,
+            '[' => match parse_class(&pattern[i + 1..]) {
+                Some((token, used)) => {
+                    i += used;
+                    token
+                }
+                // Crochet non fermé : caractère ordinaire.
+                None => Token::Char('['),
+            },
+            c => Token::Char(c),
+        };
+        tokens.push(token);
+        i += 1;
+    }
+
+    tokens
+}
+
+/// Analyse une classe après le `[` ; retourne le jeton et le nombre de
+/// caractères consommés, `]` compris.
+fn parse_class(rest: &[char]) -> Option<(Token, usize)> {
+    let negated = matches!(rest.first(), Some('!' | '^'));
+    let mut i = negated as usize;
+    let mut ranges = Vec::new();
+
+    loop {
+        let c = *rest.get(i)?;
+        // Un `]` en première position est un caractère de la classe.
+        if c == ']' && !ranges.is_empty() {
+            return Some((Token::Class { negated, ranges }, i + 1));
+        }
+
+        if rest.get(i + 1) == Some(&'-') && rest.get(i + 2).is_some_and(|&hi| hi != ']') {
+            ranges.push((c, rest[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((c, c));
+            i += 1;
+        }
+    }
+}
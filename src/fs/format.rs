@@ -0,0 +1,282 @@
+//! Formatage d’un périphérique en FAT32 (équivalent de `mkfs.fat -F 32`).
+//!
+//! Écrit le Boot Sector et sa copie de secours, le FSInfo (et sa copie), des
+//! FAT vierges avec les entrées réservées 0 et 1, et une racine vide.
+
+use alloc::string::String;
+use alloc::vec;
+use core::fmt;
+
+use crate::device::block_device::{BlockDevice, BlockDeviceError};
+use crate::fs::backup::BACKUP_BOOT_SECTOR;
+use crate::fs::boot_sector::{BootSector, BootSectorError};
+use crate::fs::directory::ATTR_VOLUME_ID;
+use crate::fs::fat::FAT_EOC;
+use crate::fs::fsinfo::FsInfo;
+use crate::fs::label::encode_label;
+
+/// Octet média des disques fixes.
+const MEDIA_FIXED: u8 = 0xF8;
+/// Secteur du FSInfo.
+const FS_INFO_SECTOR: u16 = 1;
+/// Plus petit nombre de clusters d’un volume FAT32 : en dessous, la
+/// spécification fait du volume un FAT16.
+pub const MIN_CLUSTERS: u64 = 65525;
+/// Plus grand nombre de clusters adressable en FAT32.
+const MAX_CLUSTERS: u64 = 0x0FFF_FFF5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    Io(BlockDeviceError),
+    /// Le volume écrit ne se relit pas (ne devrait jamais arriver).
+    Verify(BootSectorError),
+    InvalidSectorSize,
+    InvalidClusterSize,
+    InvalidFatCount,
+    /// Moins de secteurs réservés que nécessaire (Boot Sector, FSInfo et copies).
+    InvalidReservedSectors,
+    InvalidLabel,
+    /// Moins de `MIN_CLUSTERS` clusters.
+    VolumeTooSmall,
+    VolumeTooLarge,
+    /// Taille demandée au-delà de celle du périphérique.
+    ExceedsDevice { size: u64, device_size: u64 },
+}
+
+impl From<BlockDeviceError> for FormatError {
+    fn from(e: BlockDeviceError) -> Self {
+        FormatError::Io(e)
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Io(_) => write!(f, "cannot write filesystem structures"),
+            FormatError::Verify(_) => write!(f, "formatted volume cannot be read back"),
+            FormatError::InvalidSectorSize => write!(f, "invalid sector size"),
+            FormatError::InvalidClusterSize => write!(f, "invalid cluster size"),
+            FormatError::InvalidFatCount => write!(f, "invalid number of FATs"),
+            FormatError::InvalidReservedSectors => write!(f, "too few reserved sectors"),
+            FormatError::InvalidLabel => write!(f, "invalid volume label"),
+            FormatError::VolumeTooSmall => write!(f, "volume too small for FAT32"),
+            FormatError::VolumeTooLarge => write!(f, "volume too large for FAT32"),
+            FormatError::ExceedsDevice { size, device_size } => {
+                write!(f, "volume size {} exceeds device size {}", size, device_size)
+            }
+        }
+    }
+}
+
+impl core::error::Error for FormatError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            FormatError::Io(e) => Some(e),
+            FormatError::Verify(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Paramètres du formatage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    pub bytes_per_sector: u16,
+    /// `None` : choix automatique selon la table de Microsoft.
+    pub sectors_per_cluster: Option<u8>,
+    /// Étiquette de volume (11 caractères au plus).
+    pub label: Option<String>,
+    pub volume_id: u32,
+    pub fat_count: u8,
+    pub reserved_sectors: u16,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            bytes_per_sector: 512,
+            sectors_per_cluster: None,
+            label: None,
+            volume_id: 0,
+            fat_count: 2,
+            reserved_sectors: 32,
+        }
+    }
+}
+
+/// Taille de cluster recommandée par Microsoft pour un volume FAT32.
+///
+/// La table est exprimée en secteurs de 512 octets ; elle est ramenée à la
+/// taille de secteur réelle. Les volumes de moins de 260 Mo (y compris ceux
+/// que Microsoft refuse de formater en FAT32) reçoivent des clusters de 512 octets.
+pub fn default_sectors_per_cluster(size: u64, bytes_per_sector: u16) -> u8 {
+    let sectors_512 = size / 512;
+    let cluster_bytes: u64 = match sectors_512 {
+        0..=532_480 => 512,              // jusqu’à 260 Mo
+        532_481..=16_777_216 => 4096,    // jusqu’à 8 Go
+        16_777_217..=33_554_432 => 8192, // jusqu’à 16 Go
+        33_554_433..=67_108_864 => 16384, // jusqu’à 32 Go
+        _ => 32768,
+    };
+
+    (cluster_bytes / bytes_per_sector as u64).max(1) as u8
+}
+
+/// Formate `size` octets du périphérique en FAT32 et relit le Boot Sector écrit.
+///
+/// Le volume doit compter au moins `MIN_CLUSTERS` clusters (environ 33 Mo
+/// avec des secteurs de 512 octets) et tenir dans le périphérique, quand sa
+/// taille est connue.
+pub fn format<D: BlockDevice>(
+    device: &D,
+    size: u64,
+    options: &FormatOptions,
+) -> Result<BootSector, FormatError> {
+    if let Some(device_size) = device.size()
+        && size > device_size
+    {
+        return Err(FormatError::ExceedsDevice { size, device_size });
+    }
+
+    let bps = options.bytes_per_sector;
+    if !matches!(bps, 512 | 1024 | 2048 | 4096) {
+        return Err(FormatError::InvalidSectorSize);
+    }
+
+    let spc = options
+        .sectors_per_cluster
+        .unwrap_or_else(|| default_sectors_per_cluster(size, bps));
+    if !spc.is_power_of_two() || spc as u32 * bps as u32 > 65536 {
+        return Err(FormatError::InvalidClusterSize);
+    }
+
+    if options.fat_count == 0 {
+        return Err(FormatError::InvalidFatCount);
+    }
+    if options.reserved_sectors < BACKUP_BOOT_SECTOR + 2 {
+        return Err(FormatError::InvalidReservedSectors);
+    }
+
+    let label = match &options.label {
+        Some(label) => Some(encode_label(label).ok_or(FormatError::InvalidLabel)?),
+        None => None,
+    };
+
+    let total_sectors = (size / bps as u64).min(u32::MAX as u64);
+    let (sectors_per_fat, cluster_count) =
+        fat_geometry(total_sectors, bps, spc, options.reserved_sectors, options.fat_count);
+    if cluster_count < MIN_CLUSTERS {
+        return Err(FormatError::VolumeTooSmall);
+    }
+    if cluster_count > MAX_CLUSTERS {
+        return Err(FormatError::VolumeTooLarge);
+    }
+
+    let sector_size = bps as u64;
+    let data_start = options.reserved_sectors as u64 + options.fat_count as u64 * sectors_per_fat;
+
+    // Zones système et racine remises à zéro.
+    zero(device, 0, (data_start + spc as u64) * sector_size)?;
+
+    let boot = encode_boot_sector(options, spc, total_sectors as u32, sectors_per_fat as u32, label);
+    let fs_info = FsInfo {
+        free_count: cluster_count as u32 - 1,
+        next_free: 3,
+    }
+    .encode();
+
+    for base in [0, BACKUP_BOOT_SECTOR as u64] {
+        device.write_at(base * sector_size, &boot)?;
+        device.write_at((base + FS_INFO_SECTOR as u64) * sector_size, &fs_info)?;
+    }
+
+    // Entrées réservées : média en FAT[0], FAT[1] « volume propre », racine en FAT[2].
+    let mut head = [0u8; 12];
+    head[0..4].copy_from_slice(&(0x0FFF_FF00 | MEDIA_FIXED as u32).to_le_bytes());
+    head[4..8].copy_from_slice(&FAT_EOC.to_le_bytes());
+    head[8..12].copy_from_slice(&FAT_EOC.to_le_bytes());
+    for copy in 0..options.fat_count as u64 {
+        let fat_start = options.reserved_sectors as u64 + copy * sectors_per_fat;
+        device.write_at(fat_start * sector_size, &head)?;
+    }
+
+    if let Some(label) = label {
+        let mut entry = [0u8; 32];
+        entry[0..11].copy_from_slice(&label);
+        entry[11] = ATTR_VOLUME_ID;
+        device.write_at(data_start * sector_size, &entry)?;
+    }
+
+    BootSector::read(device).map_err(FormatError::Verify)
+}
+
+/// Taille d’une FAT (en secteurs) et nombre de clusters de données.
+///
+/// La FAT et la zone de données dépendant l’une de l’autre, on itère
+/// jusqu’à obtenir une FAT juste assez grande.
+fn fat_geometry(total_sectors: u64, bps: u16, spc: u8, reserved: u16, fats: u8) -> (u64, u64) {
+    let entries_per_sector = bps as u64 / 4;
+    let mut sectors_per_fat = 1u64;
+
+    loop {
+        let data = total_sectors
+            .saturating_sub(reserved as u64 + fats as u64 * sectors_per_fat);
+        let clusters = data / spc as u64;
+        let needed = (clusters + 2).div_ceil(entries_per_sector);
+
+        if needed <= sectors_per_fat {
+            return (sectors_per_fat, clusters);
+        }
+        sectors_per_fat = needed;
+    }
+}
+
+/// Construit le secteur de démarrage complet (BPB FAT32).
+fn encode_boot_sector(
+    options: &FormatOptions,
+    spc: u8,
+    total_sectors: u32,
+    sectors_per_fat: u32,
+    label: Option<[u8; 11]>,
+) -> vec::Vec<u8> {
+    let mut bs = vec![0u8; options.bytes_per_sector as usize];
+
+    bs[0..3].copy_from_slice(&[0xEB, 0x58, 0x90]);
+    bs[3..11].copy_from_slice(b"MSWIN4.1");
+    bs[11..13].copy_from_slice(&options.bytes_per_sector.to_le_bytes());
+    bs[13] = spc;
+    bs[14..16].copy_from_slice(&options.reserved_sectors.to_le_bytes());
+    bs[16] = options.fat_count;
+    bs[21] = MEDIA_FIXED;
+    bs[24..26].copy_from_slice(&63u16.to_le_bytes()); // secteurs par piste
+    bs[26..28].copy_from_slice(&255u16.to_le_bytes()); // têtes
+    bs[32..36].copy_from_slice(&total_sectors.to_le_bytes());
+    bs[36..40].copy_from_slice(&sectors_per_fat.to_le_bytes());
+    bs[44..48].copy_from_slice(&2u32.to_le_bytes()); // cluster racine
+    bs[48..50].copy_from_slice(&FS_INFO_SECTOR.to_le_bytes());
+    bs[50..52].copy_from_slice(&BACKUP_BOOT_SECTOR.to_le_bytes());
+    bs[64] = 0x80; // numéro de lecteur
+    bs[66] = 0x29; // signature étendue
+    bs[67..71].copy_from_slice(&options.volume_id.to_le_bytes());
+    bs[71..82].copy_from_slice(&label.unwrap_or(*b"NO NAME    "));
+    bs[82..90].copy_from_slice(b"FAT32   ");
+    bs[510] = 0x55;
+    bs[511] = 0xAA;
+
+    bs
+}
+
+/// Remplit de zéros `len` octets à partir de `offset`, par blocs.
+fn zero<D: BlockDevice>(device: &D, offset: u64, len: u64) -> Result<(), BlockDeviceError> {
+    const CHUNK: u64 = 64 * 1024;
+    let block = vec![0u8; CHUNK as usize];
+
+    let mut done = 0;
+    while done < len {
+        let n = CHUNK.min(len - done);
+        device.write_at(offset + done, &block[..n as usize])?;
+        done += n;
+    }
+
+    Ok(())
+}
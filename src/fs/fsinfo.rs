@@ -0,0 +1,114 @@
+//! Lecture et écriture du secteur FSInfo FAT32.
+//!
+//! Le FSInfo ne contient que des indications (nombre de clusters libres,
+//! prochain cluster libre) : elles peuvent être fausses et doivent être
+//! vérifiées avant d’être utilisées.
+
+use core::fmt;
+
+use crate::device::block_device::{BlockDevice, BlockDeviceError};
+use crate::fs::boot_sector::BootSector;
+
+const LEAD_SIGNATURE: u32 = 0x4161_5252;
+const STRUCT_SIGNATURE: u32 = 0x6141_7272;
+const TRAIL_SIGNATURE: u32 = 0xAA55_0000;
+
+/// Valeur signifiant « inconnu » pour les deux champs du FSInfo.
+pub const FSINFO_UNKNOWN: u32 = 0xFFFF_FFFF;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsInfoError {
+    Io(BlockDeviceError),
+    /// Le Boot Sector n’indique pas de secteur FSInfo.
+    Missing,
+    InvalidSignature,
+}
+
+impl From<BlockDeviceError> for FsInfoError {
+    fn from(e: BlockDeviceError) -> Self {
+        FsInfoError::Io(e)
+    }
+}
+
+impl fmt::Display for FsInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsInfoError::Io(_) => write!(f, "cannot access FSInfo sector"),
+            FsInfoError::Missing => write!(f, "volume has no FSInfo sector"),
+            FsInfoError::InvalidSignature => write!(f, "invalid FSInfo signature"),
+        }
+    }
+}
+
+impl core::error::Error for FsInfoError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            FsInfoError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Contenu utile du secteur FSInfo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsInfo {
+    /// Nombre de clusters libres (`FSINFO_UNKNOWN` si inconnu).
+    pub free_count: u32,
+    /// Indication du prochain cluster libre (`FSINFO_UNKNOWN` si inconnu).
+    pub next_free: u32,
+}
+
+impl FsInfo {
+    /// Offset disque du secteur FSInfo, s’il existe.
+    pub fn offset(boot: &BootSector) -> Option<u64> {
+        let sector = boot.fs_info_sector;
+        if sector == 0 || sector == 0xFFFF || sector >= boot.reserved_sectors {
+            return None;
+        }
+
+        Some(sector as u64 * boot.bytes_per_sector as u64)
+    }
+
+    /// Lit et valide le secteur FSInfo.
+    pub fn read<D: BlockDevice>(device: &D, boot: &BootSector) -> Result<Self, FsInfoError> {
+        let offset = Self::offset(boot).ok_or(FsInfoError::Missing)?;
+
+        let mut sector = [0u8; 512];
+        device.read_at(offset, &mut sector)?;
+
+        if read_u32(&sector, 0) != LEAD_SIGNATURE
+            || read_u32(&sector, 484) != STRUCT_SIGNATURE
+            || read_u32(&sector, 508) != TRAIL_SIGNATURE
+        {
+            return Err(FsInfoError::InvalidSignature);
+        }
+
+        Ok(Self {
+            free_count: read_u32(&sector, 488),
+            next_free: read_u32(&sector, 492),
+        })
+    }
+
+    /// Écrit un secteur FSInfo complet (signatures comprises).
+    pub fn write<D: BlockDevice>(&self, device: &D, boot: &BootSector) -> Result<(), FsInfoError> {
+        let offset = Self::offset(boot).ok_or(FsInfoError::Missing)?;
+
+        device.write_at(offset, &self.encode())?;
+        Ok(())
+    }
+
+    /// Encode le secteur FSInfo (512 premiers octets du secteur).
+    pub fn encode(&self) -> [u8; 512] {
+        let mut sector = [0u8; 512];
+        sector[0..4].copy_from_slice(&LEAD_SIGNATURE.to_le_bytes());
+        sector[484..488].copy_from_slice(&STRUCT_SIGNATURE.to_le_bytes());
+        sector[488..492].copy_from_slice(&self.free_count.to_le_bytes());
+        sector[492..496].copy_from_slice(&self.next_free.to_le_bytes());
+        sector[508..512].copy_from_slice(&TRAIL_SIGNATURE.to_le_bytes());
+        sector
+    }
+}
+
+fn read_u32(sector: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([sector[at], sector[at + 1], sector[at + 2], sector[at + 3]])
+}
@@ -0,0 +1,327 @@
+//! Import de fichiers et d’arborescences depuis le système de fichiers hôte.
+//!
+//! Le pendant d’`extract` : les répertoires hôtes sont recréés, les noms
+//! reçoivent un nom long et un alias 8.3, et chaque fichier est alloué en
+//! une fois (contigu si possible) puis copié par blocs. Les entrées d’un
+//! répertoire sont importées dans l’ordre de leur nom, pour qu’une même
+//! arborescence donne toujours la même image. Une erreur sur une entrée est
+//! notée dans le rapport et l’import continue avec les suivantes.
+
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::string::String;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::vec;
+use std::vec::Vec;
+
+use core::fmt;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::cd::Cd;
+use crate::fs::create::{Create, CreateError, EntryTimes};
+use crate::fs::directory::DirectoryReader;
+use crate::fs::error::{FsError, FsResult};
+use crate::fs::timestamp::DateTime;
+use crate::fs::walk::join;
+
+/// Erreur d’import d’une entrée.
+#[derive(Debug)]
+pub enum ImportError {
+    /// Écriture impossible dans l’image (le chemin est attaché à l’erreur).
+    Image(FsError),
+    /// Lecture impossible sur l’hôte.
+    Host { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Image(e) => write!(f, "{}", e),
+            ImportError::Host { path, .. } => write!(f, "{}: cannot read", path.display()),
+        }
+    }
+}
+
+impl core::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ImportError::Image(e) => e.source(),
+            ImportError::Host { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Options d’import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportOptions {
+    /// Reprend les dates de l’hôte (converties en UTC) ; sinon les dates
+    /// restent nulles.
+    pub preserve_times: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self { preserve_times: true }
+    }
+}
+
+/// Bilan d’un import.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub files: u32,
+    pub directories: u32,
+    pub bytes: u64,
+    pub errors: Vec<ImportError>,
+}
+
+pub struct Importer<'a, D: BlockDevice> {
+    cd: &'a Cd<'a, D>,
+    dirs: &'a DirectoryReader<'a, D>,
+    create: &'a Create<'a, D>,
+    chunk: usize,
+    options: ImportOptions,
+}
+
+impl<'a, D: BlockDevice> Importer<'a, D> {
+    pub fn new(
+        cd: &'a Cd<'a, D>,
+        dirs: &'a DirectoryReader<'a, D>,
+        create: &'a Create<'a, D>,
+        chunk: usize,
+        options: ImportOptions,
+    ) -> Self {
+        Self {
+            cd,
+            dirs,
+            create,
+            chunk: chunk.max(1),
+            options,
+        }
+    }
+
+    /// Copie `source` (fichier ou répertoire hôte) dans le répertoire `dest`
+    /// de l’image.
+    ///
+    /// Un fichier est créé sous son nom dans `dest` ; le contenu d’un
+    /// répertoire est copié directement dans `dest`, en fusionnant avec les
+    /// répertoires existants. Seule une erreur sur `dest` ou sur la lecture
+    /// de `source` fait échouer l’appel.
+    pub fn import(&self, source: &Path, dest: &str, cwd: u32) -> FsResult<ImportReport> {
+        let cluster = self
+            .cd
+            .cd(dest, cwd)
+            .map_err(|e| FsError::from(e).with_path(dest))?;
+
+        let mut report = ImportReport::default();
+        let metadata = match fs::symlink_metadata(source) {
+            Ok(metadata) => metadata,
+            Err(source_error) => {
+                report.errors.push(ImportError::Host {
+                    path: source.to_path_buf(),
+                    source: source_error,
+                });
+                return Ok(report);
+            }
+        };
+
+        if metadata.is_dir() {
+            self.tree(source, cluster, dest, &mut report);
+        } else {
+            let name = image_name(&source.file_name().unwrap_or_default().to_string_lossy());
+            self.entry(source, &metadata, cluster, &join(dest, &name), &name, &mut report);
+        }
+
+        Ok(report)
+    }
+
+    /// Importe le contenu du répertoire hôte `source` dans le répertoire
+    /// `cluster` de l’image, par ordre de nom.
+    fn tree(&self, source: &Path, cluster: u32, path: &str, report: &mut ImportReport) {
+        let children: io::Result<Vec<PathBuf>> =
+            fs::read_dir(source).and_then(|entries| entries.map(|e| e.map(|e| e.path())).collect());
+        let mut children = match children {
+            Ok(children) => children,
+            Err(e) => {
+                report.errors.push(ImportError::Host {
+                    path: source.to_path_buf(),
+                    source: e,
+                });
+                return;
+            }
+        };
+        children.sort();
+
+        for child in children {
+            let metadata = match fs::symlink_metadata(&child) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    report.errors.push(ImportError::Host { path: child, source: e });
+                    continue;
+                }
+            };
+            let name = image_name(&child.file_name().unwrap_or_default().to_string_lossy());
+            self.entry(&child, &metadata, cluster, &join(path, &name), &name, report);
+        }
+    }
+
+    fn entry(
+        &self,
+        source: &Path,
+        metadata: &Metadata,
+        parent: u32,
+        path: &str,
+        name: &str,
+        report: &mut ImportReport,
+    ) {
+        if metadata.is_dir() {
+            let cluster = match self.existing_dir(parent, name, path) {
+                Ok(Some(cluster)) => Ok(cluster),
+                Ok(None) => self
+                    .create
+                    .mkdir(parent, name, &self.times(metadata))
+                    .map(|dir| {
+                        report.directories += 1;
+                        dir.cluster
+                    })
+                    .map_err(|e| FsError::from(e).with_path(path)),
+                Err(e) => Err(e),
+            };
+
+            match cluster {
+                Ok(cluster) => self.tree(source, cluster, path, report),
+                Err(e) => report.errors.push(ImportError::Image(e)),
+            }
+        } else if metadata.is_file() {
+            self.file(source, metadata, parent, path, name, report);
+        } else {
+            // Les liens symboliques ne sont pas suivis : un lien vers un
+            // ancêtre ferait boucler l’import.
+            report.errors.push(ImportError::Host {
+                path: source.to_path_buf(),
+                source: io::Error::new(io::ErrorKind::Unsupported, "not a regular file"),
+            });
+        }
+    }
+
+    /// Cluster du répertoire `name` s’il existe déjà dans `parent` ; un
+    /// fichier de ce nom est une erreur.
+    fn existing_dir(&self, parent: u32, name: &str, path: &str) -> FsResult<Option<u32>> {
+        let entries = self
+            .dirs
+            .read_dir(parent)
+            .map_err(|e| FsError::from(e).with_path(path))?;
+
+        let upper = name.to_uppercase();
+        match entries.iter().find(|e| e.display_name().to_uppercase() == upper) {
+            Some(e) if e.entry_type.is_dir() => Ok(Some(e.start_cluster)),
+            Some(_) => Err(FsError::from(CreateError::AlreadyExists).with_path(path)),
+            None => Ok(None),
+        }
+    }
+
+    fn file(
+        &self,
+        source: &Path,
+        metadata: &Metadata,
+        parent: u32,
+        path: &str,
+        name: &str,
+        report: &mut ImportReport,
+    ) {
+        let host_error = |source_error| ImportError::Host {
+            path: source.to_path_buf(),
+            source: source_error,
+        };
+        let image_error = |e: CreateError| ImportError::Image(FsError::from(e).with_path(path));
+
+        let mut input = match File::open(source) {
+            Ok(input) => input,
+            Err(e) => {
+                report.errors.push(host_error(e));
+                return;
+            }
+        };
+
+        let size = metadata.len();
+        let file = match self.create.create_file(parent, name, size, &self.times(metadata)) {
+            Ok(file) => file,
+            Err(e) => {
+                report.errors.push(image_error(e));
+                return;
+            }
+        };
+
+        let mut buf = vec![0u8; self.chunk];
+        let mut pos = 0;
+        while pos < size {
+            let want = buf.len().min((size - pos) as usize);
+            // Un fichier raccourci pendant la copie n’est pas importé.
+            if let Err(e) = input.read_exact(&mut buf[..want]) {
+                self.create.discard(&file);
+                report.errors.push(host_error(e));
+                return;
+            }
+            if let Err(e) = self.create.write_at(&file, pos, &buf[..want]) {
+                self.create.discard(&file);
+                report.errors.push(image_error(e));
+                return;
+            }
+            pos += want as u64;
+        }
+        if let Err(e) = self.create.finish(&file) {
+            report.errors.push(image_error(e));
+            return;
+        }
+
+        report.files += 1;
+        report.bytes += size;
+    }
+
+    fn times(&self, metadata: &Metadata) -> EntryTimes {
+        if self.options.preserve_times {
+            entry_times(metadata)
+        } else {
+            EntryTimes::default()
+        }
+    }
+}
+
+/// Nom utilisable dans l’image : caractères interdits remplacés par `_`,
+/// espaces et points de fin retirés.
+pub fn image_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '"' | '*' | '/' | ':' | '<' | '>' | '?' | '\\' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    match name.trim_end_matches([' ', '.']) {
+        "" => String::from("_"),
+        name => String::from(name),
+    }
+}
+
+/// Dates de l’hôte, vues en UTC ; la date de création manquante reprend
+/// celle de modification.
+fn entry_times(metadata: &Metadata) -> EntryTimes {
+    let convert = |time: io::Result<SystemTime>| {
+        time.ok().map(|time| {
+            let seconds = match time.duration_since(UNIX_EPOCH) {
+                Ok(d) => d.as_secs() as i64,
+                Err(e) => -(e.duration().as_secs() as i64),
+            };
+            DateTime::from_unix(seconds)
+        })
+    };
+
+    let modified = convert(metadata.modified());
+    EntryTimes {
+        created: convert(metadata.created()).or(modified),
+        modified,
+        accessed: convert(metadata.accessed()),
+    }
+}
@@ -0,0 +1,299 @@
+//! Inspection bas niveau d’un volume : secteurs, clusters, entrées FAT et
+//! slots de répertoire bruts.
+//!
+//! Ces commandes ne passent pas par les fichiers : elles servent à examiner
+//! un volume corrompu là où les commandes habituelles échouent.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::device::block_device::{BlockDevice, BlockDeviceError};
+use crate::fs::boot_sector::BootSector;
+use crate::fs::clusters::ClusterReader;
+use crate::fs::directory::{parse_short_name, DirectorySlot, SlotLocation};
+use crate::fs::error::{FsError, FsResult};
+use crate::fs::fat::{ChainEnd, Fat, FAT_BAD, FAT_FREE};
+use crate::fs::lfn::{fragment_chars, LFN_LAST};
+use crate::fs::stat::attribute_flags;
+use crate::fs::timestamp::DateTime;
+
+/// Signification d’une entrée FAT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FatEntryKind {
+    Free,
+    /// Cluster suivant de la chaîne.
+    Next(u32),
+    /// Valeur réservée (1, ou 0x0FFFFFF0 à 0x0FFFFFF6).
+    Reserved,
+    Bad,
+    EndOfChain,
+}
+
+impl FatEntryKind {
+    /// Interprète une valeur FAT (28 bits utiles).
+    pub fn decode(value: u32) -> Self {
+        match value & 0x0FFF_FFFF {
+            FAT_FREE => FatEntryKind::Free,
+            1 | 0x0FFF_FFF0..=0x0FFF_FFF6 => FatEntryKind::Reserved,
+            FAT_BAD => FatEntryKind::Bad,
+            0x0FFF_FFF8..=0x0FFF_FFFF => FatEntryKind::EndOfChain,
+            next => FatEntryKind::Next(next),
+        }
+    }
+}
+
+/// Entrée FAT d’un cluster, brute et décodée.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FatEntry {
+    pub cluster: u32,
+    pub value: u32,
+    pub kind: FatEntryKind,
+    /// Faux si la valeur désigne un cluster hors du volume.
+    pub in_range: bool,
+}
+
+impl fmt::Display for FatEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FAT[{}] = {:#010x}: ", self.cluster, self.value)?;
+        match self.kind {
+            FatEntryKind::Free => write!(f, "free"),
+            FatEntryKind::Next(next) if self.in_range => write!(f, "next cluster {}", next),
+            FatEntryKind::Next(next) => write!(f, "next cluster {} (out of range)", next),
+            FatEntryKind::Reserved => write!(f, "reserved"),
+            FatEntryKind::Bad => write!(f, "bad cluster"),
+            FatEntryKind::EndOfChain => write!(f, "end of chain"),
+        }
+    }
+}
+
+/// Chaîne suivie depuis un cluster de départ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainDump {
+    pub clusters: Vec<u32>,
+    pub end: ChainEnd,
+}
+
+impl fmt::Display for ChainDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for cluster in &self.clusters {
+            write!(f, "{} -> ", cluster)?;
+        }
+        write!(f, "[{}] ({} clusters)", self.end, self.clusters.len())
+    }
+}
+
+/// Slot de répertoire décodé.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlotInfo {
+    /// Marqueur de fin (premier octet 0x00).
+    End,
+    /// Fragment de nom long.
+    LongName {
+        ordinal: u8,
+        last: bool,
+        checksum: u8,
+        deleted: bool,
+        /// Caractères du fragment, sans le bourrage.
+        text: String,
+    },
+    /// Entrée 8.3 (fichier, répertoire ou étiquette).
+    Short {
+        /// Nom décodé (le premier caractère est illisible si l’entrée est supprimée).
+        name: String,
+        deleted: bool,
+        attributes: u8,
+        start_cluster: u32,
+        size: u32,
+        created: Option<DateTime>,
+        modified: Option<DateTime>,
+        accessed: Option<DateTime>,
+    },
+}
+
+impl SlotInfo {
+    pub fn decode(slot: &DirectorySlot) -> Self {
+        let deleted = slot.is_deleted();
+
+        if slot.is_end() {
+            return SlotInfo::End;
+        }
+
+        if slot.is_lfn() {
+            let text = char::decode_utf16(
+                fragment_chars(&slot.raw)
+                    .into_iter()
+                    .take_while(|&c| c != 0x0000 && c != 0xFFFF),
+            )
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+
+            return SlotInfo::LongName {
+                ordinal: slot.raw[0] & 0x1F,
+                last: !deleted && slot.raw[0] & LFN_LAST != 0,
+                checksum: slot.raw[13],
+                deleted,
+                text,
+            };
+        }
+
+        let mut raw_name = [0u8; 11];
+        raw_name.copy_from_slice(slot.short_name());
+        if deleted {
+            raw_name[0] = b'?';
+        }
+        let name = parse_short_name(&raw_name)
+            .unwrap_or_else(|_| String::from_utf8_lossy(&raw_name).into_owned());
+
+        SlotInfo::Short {
+            name,
+            deleted,
+            attributes: slot.attributes(),
+            start_cluster: slot.start_cluster(),
+            size: slot.size(),
+            created: slot.created(),
+            modified: slot.modified(),
+            accessed: slot.accessed(),
+        }
+    }
+}
+
+impl fmt::Display for SlotInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let date = |d: &Option<DateTime>| d.map_or_else(|| String::from("-"), |d| d.to_string());
+
+        match self {
+            SlotInfo::End => write!(f, "end of directory"),
+            SlotInfo::LongName { ordinal, last, checksum, deleted, text } => write!(
+                f,
+                "LFN{} #{}{} checksum {:#04x} {:?}",
+                if *deleted { " (deleted)" } else { "" },
+                ordinal,
+                if *last { " (last)" } else { "" },
+                checksum,
+                text
+            ),
+            SlotInfo::Short {
+                name,
+                deleted,
+                attributes,
+                start_cluster,
+                size,
+                created,
+                modified,
+                accessed,
+            } => write!(
+                f,
+                "{}{} attrs {} ({:#04x}) cluster {} size {} created {} modified {} accessed {}",
+                name,
+                if *deleted { " (deleted)" } else { "" },
+                attribute_flags(*attributes),
+                attributes,
+                start_cluster,
+                size,
+                date(created),
+                date(modified),
+                date(accessed)
+            ),
+        }
+    }
+}
+
+/// Accès brut au volume.
+pub struct Inspect<'a, D: BlockDevice> {
+    device: &'a D,
+    boot: &'a BootSector,
+    fat_copy: u8,
+}
+
+impl<'a, D: BlockDevice> Inspect<'a, D> {
+    pub fn new(device: &'a D, boot: &'a BootSector) -> Self {
+        Self {
+            device,
+            boot,
+            fat_copy: boot.active_fat(),
+        }
+    }
+
+    /// Lit la copie `copy` de la FAT plutôt que la copie active.
+    pub fn with_fat_copy(mut self, copy: u8) -> Self {
+        self.fat_copy = copy;
+        self
+    }
+
+    /// Contenu brut d’un secteur (numéro LBA depuis le début du volume).
+    pub fn sector(&self, lba: u64) -> FsResult<Vec<u8>> {
+        let size = self.boot.bytes_per_sector as u64;
+        let mut buf = vec![0u8; size as usize];
+
+        let offset = lba.checked_mul(size).ok_or(BlockDeviceError::OutOfBounds)?;
+        self.device.read_at(offset, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Contenu brut d’un cluster de données.
+    pub fn cluster(&self, cluster: u32) -> FsResult<Vec<u8>> {
+        let fat = Fat::new(self.device, self.boot).with_copy(self.fat_copy);
+        let clusters = ClusterReader::new(self.device, self.boot, &fat);
+        let mut buf = vec![0u8; clusters.cluster_size()];
+
+        clusters
+            .read_cluster(cluster, &mut buf)
+            .map_err(|e| FsError::from(e).with_cluster(cluster))?;
+        Ok(buf)
+    }
+
+    /// Offset disque du premier octet d’un cluster.
+    pub fn cluster_offset(&self, cluster: u32) -> FsResult<u64> {
+        let fat = Fat::new(self.device, self.boot).with_copy(self.fat_copy);
+        let clusters = ClusterReader::new(self.device, self.boot, &fat);
+
+        clusters
+            .cluster_offset(cluster)
+            .map_err(|e| FsError::from(e).with_cluster(cluster))
+    }
+
+    /// Entrée FAT d’un cluster, dans la copie `copy`.
+    pub fn fat_entry(&self, copy: u8, cluster: u32) -> FsResult<FatEntry> {
+        let value = Fat::new(self.device, self.boot).with_copy(self.fat_copy)
+            .entry_in_copy(copy, cluster)
+            .map_err(|e| FsError::from(e).with_cluster(cluster))?;
+        let kind = FatEntryKind::decode(value);
+
+        let in_range = match kind {
+            FatEntryKind::Next(next) => (2..=self.boot.max_cluster()).contains(&next),
+            _ => true,
+        };
+
+        Ok(FatEntry { cluster, value, kind, in_range })
+    }
+
+    /// Chaîne complète depuis `start`, arrêtée à la première anomalie.
+    pub fn chain(&self, start: u32) -> FsResult<ChainDump> {
+        let fat = Fat::new(self.device, self.boot).with_copy(self.fat_copy);
+        let (clusters, end) = fat
+            .follow_chain(start)
+            .map_err(|e| FsError::from(e).with_cluster(start))?;
+
+        Ok(ChainDump { clusters, end })
+    }
+
+    /// Slot de 32 octets n° `index` du cluster `cluster`, brut et décodé.
+    pub fn slot(&self, cluster: u32, index: u32) -> FsResult<(DirectorySlot, SlotInfo)> {
+        let fat = Fat::new(self.device, self.boot).with_copy(self.fat_copy);
+        let clusters = ClusterReader::new(self.device, self.boot, &fat);
+
+        let mut raw = [0u8; 32];
+        clusters
+            .read_in_cluster(cluster, index as usize * 32, &mut raw)
+            .map_err(|e| FsError::from(e).with_cluster(cluster))?;
+
+        let slot = DirectorySlot {
+            location: SlotLocation { cluster, index },
+            raw,
+        };
+        let info = SlotInfo::decode(&slot);
+        Ok((slot, info))
+    }
+}
@@ -0,0 +1,186 @@
+//! Étiquette de volume FAT32.
+//!
+//! L’étiquette existe à deux endroits : le champ du BPB (offset 71 du Boot
+//! Sector, et de sa copie de secours) et une entrée d’attribut 0x08 dans la
+//! racine. Comme Windows, on considère l’entrée de la racine comme la
+//! référence et le BPB comme un repli.
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::device::block_device::{BlockDevice, BlockDeviceError};
+use crate::fs::boot_sector::BootSector;
+use crate::fs::clusters::ClusterReader;
+use crate::fs::directory::{is_valid_short_name, DirectoryError, DirectoryReader, ATTR_VOLUME_ID};
+use crate::fs::fat::Fat;
+
+/// Offset du champ étiquette dans le Boot Sector.
+const BPB_LABEL_OFFSET: u64 = 71;
+/// Étiquette par défaut, signifiant « pas d’étiquette ».
+const NO_NAME: [u8; 11] = *b"NO NAME    ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelError {
+    Io(BlockDeviceError),
+    Directory(DirectoryError),
+    /// Étiquette vide, trop longue ou contenant des caractères interdits.
+    InvalidLabel,
+}
+
+impl From<BlockDeviceError> for LabelError {
+    fn from(e: BlockDeviceError) -> Self {
+        LabelError::Io(e)
+    }
+}
+
+impl From<DirectoryError> for LabelError {
+    fn from(e: DirectoryError) -> Self {
+        LabelError::Directory(e)
+    }
+}
+
+impl fmt::Display for LabelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LabelError::Io(_) => write!(f, "cannot access boot sector label"),
+            LabelError::Directory(_) => write!(f, "cannot access root directory label"),
+            LabelError::InvalidLabel => write!(f, "invalid volume label"),
+        }
+    }
+}
+
+impl core::error::Error for LabelError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            LabelError::Io(e) => Some(e),
+            LabelError::Directory(e) => Some(e),
+            LabelError::InvalidLabel => None,
+        }
+    }
+}
+
+/// Convertit une étiquette en champ de 11 octets (majuscules, complété par des espaces).
+pub fn encode_label(label: &str) -> Option<[u8; 11]> {
+    if label.is_empty() || label.len() > 11 || !label.is_ascii() {
+        return None;
+    }
+
+    let mut raw = [b' '; 11];
+    for (dst, src) in raw.iter_mut().zip(label.bytes()) {
+        *dst = src.to_ascii_uppercase();
+    }
+
+    is_valid_short_name(&raw).then_some(raw)
+}
+
+/// Lecture et écriture de l’étiquette de volume.
+pub struct Label<'a, D: BlockDevice> {
+    device: &'a D,
+    boot: &'a BootSector,
+    fat_copy: u8,
+}
+
+impl<'a, D: BlockDevice> Label<'a, D> {
+    pub fn new(device: &'a D, boot: &'a BootSector) -> Self {
+        Self {
+            device,
+            boot,
+            fat_copy: boot.active_fat(),
+        }
+    }
+
+    /// Lit la copie `copy` de la FAT plutôt que la copie active.
+    pub fn with_fat_copy(mut self, copy: u8) -> Self {
+        self.fat_copy = copy;
+        self
+    }
+
+    /// Retourne l’étiquette de la racine, ou à défaut celle du BPB.
+    pub fn get(&self) -> Result<Option<String>, LabelError> {
+        let fat = Fat::new(self.device, self.boot).with_copy(self.fat_copy);
+        let clusters = ClusterReader::new(self.device, self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+
+        let root_label = dirs
+            .read_slots(self.boot.root_cluster)?
+            .into_iter()
+            .take_while(|s| !s.is_end())
+            .find(|s| !s.is_deleted() && s.is_volume_label());
+
+        if let Some(slot) = root_label {
+            return Ok(decode(slot.short_name()));
+        }
+
+        let mut sector = [0u8; 90];
+        self.device.read_at(0, &mut sector)?;
+
+        // Le champ n’existe que si la signature étendue est présente.
+        if sector[66] != 0x29 || sector[71..82] == NO_NAME {
+            return Ok(None);
+        }
+        Ok(decode(&sector[71..82]))
+    }
+
+    /// Remplace l’étiquette dans la racine et dans le(s) BPB.
+    ///
+    /// Une étiquette vide supprime l’étiquette. Le BPB n’est modifié que s’il
+    /// porte la signature étendue (0x29) qui rend le champ significatif.
+    pub fn set(&self, label: &str) -> Result<(), LabelError> {
+        let raw = if label.is_empty() {
+            None
+        } else {
+            Some(encode_label(label).ok_or(LabelError::InvalidLabel)?)
+        };
+
+        let fat = Fat::new(self.device, self.boot).with_copy(self.fat_copy);
+        let clusters = ClusterReader::new(self.device, self.boot, &fat);
+        let dirs = DirectoryReader::new(&clusters);
+
+        let existing = dirs
+            .read_slots(self.boot.root_cluster)?
+            .into_iter()
+            .take_while(|s| !s.is_end())
+            .find(|s| !s.is_deleted() && s.is_volume_label());
+
+        match (existing, raw) {
+            (Some(mut slot), Some(raw)) => {
+                slot.raw[0..11].copy_from_slice(&raw);
+                dirs.write_slot(&slot)?;
+            }
+            (Some(mut slot), None) => {
+                slot.raw[0] = 0xE5;
+                dirs.write_slot(&slot)?;
+            }
+            (None, Some(raw)) => {
+                let mut entry = [0u8; 32];
+                entry[0..11].copy_from_slice(&raw);
+                entry[11] = ATTR_VOLUME_ID;
+                dirs.add_slot(self.boot.root_cluster, entry)?;
+            }
+            (None, None) => {}
+        }
+
+        let raw = raw.unwrap_or(NO_NAME);
+        let mut sector = [0u8; 67];
+        self.device.read_at(0, &mut sector)?;
+        if sector[66] != 0x29 {
+            return Ok(());
+        }
+        let backup = u16::from_le_bytes([sector[50], sector[51]]);
+
+        self.device.write_at(BPB_LABEL_OFFSET, &raw)?;
+        if backup != 0 && backup != 0xFFFF && backup < self.boot.reserved_sectors {
+            let offset = backup as u64 * self.boot.bytes_per_sector as u64;
+            self.device.write_at(offset + BPB_LABEL_OFFSET, &raw)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn decode(raw: &[u8]) -> Option<String> {
+    let label = String::from_utf8_lossy(raw);
+    let label = label.trim_end();
+
+    (!label.is_empty()).then(|| String::from(label))
+}
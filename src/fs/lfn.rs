@@ -0,0 +1,231 @@
+//! Noms longs VFAT (LFN).
+//!
+//! Un nom long est stocké dans des slots d’attribut 0x0F placés juste avant
+//! l’entrée 8.3, du dernier fragment au premier. Chaque fragment porte
+//! 13 caractères UTF-16 et la somme de contrôle du nom court associé ; un
+//! nom long dont la suite ou la somme ne correspond pas est ignoré.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::fs::directory::is_valid_short_name;
+
+/// Bit marquant le dernier fragment (le premier rencontré sur le disque).
+pub const LFN_LAST: u8 = 0x40;
+/// Caractères par fragment.
+pub const LFN_CHARS: usize = 13;
+
+/// Offsets des 13 caractères UTF-16 dans un slot LFN.
+const CHAR_OFFSETS: [usize; LFN_CHARS] = [1, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+
+/// Somme de contrôle d’un nom 8.3 brut, recopiée dans chaque fragment.
+pub fn checksum(short_name: &[u8]) -> u8 {
+    short_name[..11]
+        .iter()
+        .fold(0u8, |sum, &c| sum.rotate_right(1).wrapping_add(c))
+}
+
+/// Caractères UTF-16 d’un fragment.
+pub fn fragment_chars(raw: &[u8; 32]) -> [u16; LFN_CHARS] {
+    CHAR_OFFSETS.map(|at| u16::from_le_bytes([raw[at], raw[at + 1]]))
+}
+
+/// Assemble les fragments qui précèdent une entrée 8.3.
+#[derive(Debug, Clone, Default)]
+pub struct LongNameCollector {
+    /// Fragments dans l’ordre du disque (dernier fragment d’abord).
+    parts: Vec<[u16; LFN_CHARS]>,
+    /// Numéro du prochain fragment attendu (0 : suite complète ou absente).
+    next: u8,
+    checksum: u8,
+    broken: bool,
+}
+
+impl LongNameCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute un slot LFN.
+    pub fn push(&mut self, raw: &[u8; 32]) {
+        let ordinal = raw[0] & 0x1F;
+
+        if raw[0] & LFN_LAST != 0 {
+            self.reset();
+            self.checksum = raw[13];
+        } else if ordinal != self.next || raw[13] != self.checksum {
+            self.broken = true;
+        }
+
+        if ordinal == 0 || self.broken {
+            self.broken = true;
+            return;
+        }
+
+        self.parts.push(fragment_chars(raw));
+        self.next = ordinal - 1;
+    }
+
+    /// Nom long associé à l’entrée 8.3 `short_name`, s’il est complet et
+    /// cohérent ; le collecteur est remis à zéro dans tous les cas.
+    pub fn finish(&mut self, short_name: &[u8]) -> Option<String> {
+        let complete = !self.broken
+            && !self.parts.is_empty()
+            && self.next == 0
+            && self.checksum == checksum(short_name);
+
+        let name = complete.then(|| {
+            let units = self
+                .parts
+                .iter()
+                .rev()
+                .flatten()
+                .copied()
+                .take_while(|&c| c != 0x0000);
+            char::decode_utf16(units)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect()
+        });
+
+        self.reset();
+        name
+    }
+
+    /// Abandonne les fragments en cours (entrée supprimée, étiquette…).
+    pub fn reset(&mut self) {
+        self.parts.clear();
+        self.next = 0;
+        self.checksum = 0;
+        self.broken = false;
+    }
+}
+
+/// Longueur maximale d’un nom long, en unités UTF-16.
+pub const LFN_MAX_LEN: usize = 255;
+
+/// Vrai si `name` peut être stocké comme nom long.
+///
+/// Les caractères de contrôle, `"*/:<>?\|` et les noms `.`/`..` sont refusés,
+/// comme un nom terminé par un espace ou un point.
+pub fn is_valid_long_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.ends_with([' ', '.'])
+        && name.encode_utf16().count() <= LFN_MAX_LEN
+        && !name
+            .chars()
+            .any(|c| c.is_control() || matches!(c, '"' | '*' | '/' | ':' | '<' | '>' | '?' | '\\' | '|'))
+}
+
+/// Slots LFN de `name` pour l’entrée 8.3 `short_name`, dans l’ordre du
+/// disque (dernier fragment d’abord) ; `None` si le nom est invalide.
+pub fn encode(name: &str, short_name: &[u8; 11]) -> Option<Vec<[u8; 32]>> {
+    if !is_valid_long_name(name) {
+        return None;
+    }
+
+    Some(encode_unchecked(name, short_name))
+}
+
+/// Slots LFN de `name`, sans contrôle du nom (images de test).
+pub(crate) fn encode_unchecked(name: &str, short_name: &[u8; 11]) -> Vec<[u8; 32]> {
+    let mut units: Vec<u16> = name.encode_utf16().collect();
+    if !units.len().is_multiple_of(LFN_CHARS) {
+        units.push(0x0000);
+        while !units.len().is_multiple_of(LFN_CHARS) {
+            units.push(0xFFFF);
+        }
+    }
+
+    let sum = checksum(short_name);
+    let count = units.len() / LFN_CHARS;
+    units
+        .chunks(LFN_CHARS)
+        .enumerate()
+        .rev()
+        .map(|(i, chars)| {
+            let mut raw = [0u8; 32];
+            raw[0] = (i + 1) as u8 | if i + 1 == count { LFN_LAST } else { 0 };
+            raw[11] = 0x0F;
+            raw[13] = sum;
+            for (&at, c) in CHAR_OFFSETS.iter().zip(chars) {
+                raw[at..at + 2].copy_from_slice(&c.to_le_bytes());
+            }
+            raw
+        })
+        .collect()
+}
+
+/// Nom 8.3 brut exactement équivalent à `name`, si le nom en est un
+/// (majuscules, 8 + 3 caractères autorisés) : aucun nom long n’est alors
+/// nécessaire.
+pub fn exact_short_name(name: &str) -> Option<[u8; 11]> {
+    let (base, ext) = match name.rsplit_once('.') {
+        Some((base, ext)) => (base, ext),
+        None => (name, ""),
+    };
+    if base.is_empty() || base.len() > 8 || ext.len() > 3 || name.ends_with('.') {
+        return None;
+    }
+
+    let mut raw = [b' '; 11];
+    for (dst, src) in raw.iter_mut().zip(base.bytes()) {
+        *dst = src;
+    }
+    for (dst, src) in raw[8..].iter_mut().zip(ext.bytes()) {
+        *dst = src;
+    }
+
+    (name.is_ascii() && !name.contains(' ') && is_valid_short_name(&raw)).then_some(raw)
+}
+
+/// Alias 8.3 d’un nom long, à la manière de Windows.
+///
+/// Un nom qui ne diffère d’un nom 8.3 que par la casse garde ce nom s’il est
+/// libre selon `taken`. Sinon, espaces et points de tête sont retirés, les
+/// caractères interdits remplacés par `_`, et le premier `NOM~N.EXT` libre
+/// est retenu.
+pub fn short_alias<F: Fn(&[u8; 11]) -> bool>(name: &str, taken: F) -> [u8; 11] {
+    if let Some(raw) = exact_short_name(&name.to_uppercase())
+        && !taken(&raw)
+    {
+        return raw;
+    }
+
+    let clean = |part: &str, max: usize| -> Vec<u8> {
+        part.chars()
+            .filter(|&c| c != ' ' && c != '.')
+            .map(|c| match c.to_ascii_uppercase() {
+                c @ ('A'..='Z' | '0'..='9') => c as u8,
+                c if c.is_ascii_punctuation() && !"\"*+,/:;<=>?[\\]|".contains(c) => c as u8,
+                _ => b'_',
+            })
+            .take(max)
+            .collect()
+    };
+
+    let trimmed = name.trim_start_matches(['.', ' ']);
+    let (base, ext) = match trimmed.rsplit_once('.') {
+        Some((base, ext)) if !base.is_empty() => (clean(base, 8), clean(ext, 3)),
+        _ => (clean(trimmed, 8), Vec::new()),
+    };
+    let base = if base.is_empty() { alloc::vec![b'_'] } else { base };
+
+    let mut raw = [b' '; 11];
+    for (dst, src) in raw[8..].iter_mut().zip(&ext) {
+        *dst = *src;
+    }
+
+    for n in 1u32.. {
+        let tail = alloc::format!("~{}", n);
+        let keep = base.len().min(8 - tail.len());
+        raw[..8].fill(b' ');
+        raw[..keep].copy_from_slice(&base[..keep]);
+        raw[keep..keep + tail.len()].copy_from_slice(tail.as_bytes());
+        if !taken(&raw) {
+            break;
+        }
+    }
+    raw
+}
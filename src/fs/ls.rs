@@ -22,10 +22,10 @@ impl<'a, D: BlockDevice> Ls<'a, D> {
             None => (cwd, None),
         };
 
-        if let Some(e) = entry {
-            if !e.entry_type.is_dir() {
-                return Err(PathError::NotADirectory);
-            }
+        if let Some(e) = entry
+            && !e.entry_type.is_dir()
+        {
+            return Err(PathError::NotADirectory);
         }
 
         self.resolver.read_dir(cluster)
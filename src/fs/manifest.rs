@@ -0,0 +1,374 @@
+//! Manifeste d’image : description textuelle lue par `ImageBuilder`.
+//!
+//! Le format est un sous-ensemble de TOML : une table `[volume]` puis des
+//! tables `[[dir]]` et `[[file]]`, dans l’ordre de création. Les valeurs sont
+//! des chaînes entre guillemets (échappements `\n`, `\t`, `\"`, `\\`) ou des
+//! entiers (décimaux ou `0x…`, `_` autorisé).
+//!
+//! ```text
+//! [volume]
+//! size = "64M"
+//! label = "FIRMWARE"
+//! volume_id = 0x1234_5678
+//! time = "2024-01-01 00:00:00"
+//!
+//! [[dir]]
+//! path = "/boot"
+//!
+//! [[file]]
+//! path = "/boot/config.txt"
+//! text = "dtparam=audio=on\n"
+//! attributes = "R"
+//!
+//! [[file]]
+//! path = "/boot/kernel.img"
+//! source = "build/kernel.img"
+//! ```
+//!
+//! `source` désigne un fichier hôte, chargé par l’appelant (`into_builder`).
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::fs::builder::{ImageBuilder, Node};
+use crate::fs::directory::ATTR_ARCHIVE;
+use crate::fs::format::FormatOptions;
+use crate::fs::stat::{ATTR_HIDDEN, ATTR_READ_ONLY, ATTR_SYSTEM};
+use crate::fs::timestamp::DateTime;
+
+/// Nature d’une erreur de manifeste.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestErrorKind {
+    /// Ligne qui n’est ni une table, ni `clé = valeur`.
+    Syntax,
+    UnknownTable(String),
+    UnknownKey(String),
+    DuplicateKey(String),
+    InvalidValue(String),
+    MissingKey(&'static str),
+}
+
+/// Erreur de manifeste, avec sa ligne (à partir de 1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestError {
+    pub line: usize,
+    pub kind: ManifestErrorKind,
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ManifestErrorKind::Syntax => write!(f, "syntax error"),
+            ManifestErrorKind::UnknownTable(name) => write!(f, "unknown table [{}]", name),
+            ManifestErrorKind::UnknownKey(key) => write!(f, "unknown key {}", key),
+            ManifestErrorKind::DuplicateKey(key) => write!(f, "duplicate key {}", key),
+            ManifestErrorKind::InvalidValue(key) => write!(f, "invalid value for {}", key),
+            ManifestErrorKind::MissingKey(key) => write!(f, "missing key {}", key),
+        }
+    }
+}
+
+impl core::error::Error for ManifestError {}
+
+/// Contenu d’une entrée du manifeste.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestContent {
+    Directory,
+    /// Contenu donné dans le manifeste.
+    Text(String),
+    /// Chemin d’un fichier hôte.
+    Source(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// Ligne de la table `[[dir]]` ou `[[file]]`.
+    pub line: usize,
+    pub path: String,
+    pub content: ManifestContent,
+    /// `None` : attributs par défaut.
+    pub attributes: Option<u8>,
+    pub time: Option<DateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub size: u64,
+    pub format: FormatOptions,
+    pub time: Option<DateTime>,
+    pub entries: Vec<ManifestEntry>,
+}
+
+/// Clés de la table `[volume]`.
+const VOLUME_KEYS: &[&str] = &[
+    "size",
+    "label",
+    "volume_id",
+    "bytes_per_sector",
+    "cluster_size",
+    "fats",
+    "reserved_sectors",
+    "time",
+];
+/// Clés des tables `[[dir]]` et `[[file]]` (`text` et `source` : fichiers seulement).
+const ENTRY_KEYS: &[&str] = &["path", "attributes", "time"];
+
+/// Valeur d’une ligne `clé = valeur`.
+enum Value {
+    Text(String),
+    Integer(u64),
+}
+
+/// Table en cours de lecture.
+enum Table {
+    None,
+    Volume,
+    Entry(ManifestEntry),
+}
+
+impl Manifest {
+    pub fn parse(text: &str) -> Result<Self, ManifestError> {
+        let mut manifest = Manifest {
+            size: 0,
+            format: FormatOptions::default(),
+            time: None,
+            entries: Vec::new(),
+        };
+        let mut volume_line = None;
+        let mut cluster_size = None;
+        let mut table = Table::None;
+        let mut seen: Vec<String> = Vec::new();
+
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let error = |kind| ManifestError { line, kind };
+            let content = strip_comment(raw).trim();
+            if content.is_empty() {
+                continue;
+            }
+
+            if content.starts_with('[') {
+                manifest.close(core::mem::replace(&mut table, Table::None))?;
+                seen.clear();
+                table = match content {
+                    "[volume]" if volume_line.is_none() => {
+                        volume_line = Some(line);
+                        Table::Volume
+                    }
+                    "[volume]" => return Err(error(ManifestErrorKind::DuplicateKey("volume".into()))),
+                    "[[dir]]" | "[[file]]" => Table::Entry(ManifestEntry {
+                        line,
+                        path: String::new(),
+                        content: if content == "[[dir]]" {
+                            ManifestContent::Directory
+                        } else {
+                            ManifestContent::Text(String::new())
+                        },
+                        attributes: None,
+                        time: None,
+                    }),
+                    _ => {
+                        let name = content.trim_matches(['[', ']']);
+                        return Err(error(ManifestErrorKind::UnknownTable(name.into())));
+                    }
+                };
+                continue;
+            }
+
+            let (key, value) = content
+                .split_once('=')
+                .ok_or_else(|| error(ManifestErrorKind::Syntax))?;
+            let key = key.trim();
+            let value = parse_value(value.trim()).ok_or_else(|| error(ManifestErrorKind::Syntax))?;
+            if seen.iter().any(|k| k == key) {
+                return Err(error(ManifestErrorKind::DuplicateKey(key.into())));
+            }
+            seen.push(key.into());
+
+            let invalid = || error(ManifestErrorKind::InvalidValue(key.into()));
+            match (&mut table, key, value) {
+                (Table::Volume, "size", Value::Integer(n)) => manifest.size = n,
+                (Table::Volume, "size", Value::Text(t)) => {
+                    manifest.size = parse_size(&t).ok_or_else(invalid)?
+                }
+                (Table::Volume, "label", Value::Text(t)) => manifest.format.label = Some(t),
+                (Table::Volume, "volume_id", Value::Integer(n)) => {
+                    manifest.format.volume_id = u32::try_from(n).map_err(|_| invalid())?
+                }
+                (Table::Volume, "bytes_per_sector", Value::Integer(n)) => {
+                    manifest.format.bytes_per_sector = u16::try_from(n).map_err(|_| invalid())?
+                }
+                (Table::Volume, "cluster_size", Value::Integer(n)) => cluster_size = Some(n),
+                (Table::Volume, "fats", Value::Integer(n)) => {
+                    manifest.format.fat_count = u8::try_from(n).map_err(|_| invalid())?
+                }
+                (Table::Volume, "reserved_sectors", Value::Integer(n)) => {
+                    manifest.format.reserved_sectors = u16::try_from(n).map_err(|_| invalid())?
+                }
+                (Table::Volume, "time", Value::Text(t)) => {
+                    manifest.time = Some(DateTime::parse(&t).ok_or_else(invalid)?)
+                }
+                (Table::Entry(entry), "path", Value::Text(t)) => entry.path = t,
+                (Table::Entry(entry), "attributes", Value::Text(t)) => {
+                    entry.attributes = Some(parse_attributes(&t).ok_or_else(invalid)?)
+                }
+                (Table::Entry(entry), "time", Value::Text(t)) => {
+                    entry.time = Some(DateTime::parse(&t).ok_or_else(invalid)?)
+                }
+                (Table::Entry(entry), "text" | "source", Value::Text(t))
+                    if entry.content != ManifestContent::Directory =>
+                {
+                    if seen.iter().any(|k| k == "text") && seen.iter().any(|k| k == "source") {
+                        return Err(error(ManifestErrorKind::DuplicateKey(key.into())));
+                    }
+                    entry.content = if key == "text" {
+                        ManifestContent::Text(t)
+                    } else {
+                        ManifestContent::Source(t)
+                    };
+                }
+                (Table::None, ..) => return Err(error(ManifestErrorKind::Syntax)),
+                (Table::Volume, key, _) if VOLUME_KEYS.contains(&key) => return Err(invalid()),
+                (Table::Entry(_), key, _) if ENTRY_KEYS.contains(&key) => return Err(invalid()),
+                _ => return Err(error(ManifestErrorKind::UnknownKey(key.into()))),
+            }
+        }
+        manifest.close(table)?;
+
+        let line = volume_line.unwrap_or(0);
+        if manifest.size == 0 {
+            return Err(ManifestError { line, kind: ManifestErrorKind::MissingKey("size") });
+        }
+        if let Some(bytes) = cluster_size {
+            let sectors = bytes / manifest.format.bytes_per_sector as u64;
+            if sectors == 0 || sectors * manifest.format.bytes_per_sector as u64 != bytes {
+                let kind = ManifestErrorKind::InvalidValue("cluster_size".into());
+                return Err(ManifestError { line, kind });
+            }
+            manifest.format.sectors_per_cluster =
+                Some(u8::try_from(sectors).map_err(|_| ManifestError {
+                    line,
+                    kind: ManifestErrorKind::InvalidValue("cluster_size".into()),
+                })?);
+        }
+
+        Ok(manifest)
+    }
+
+    /// Description prête à construire ; `load` fournit le contenu des
+    /// fichiers `source`.
+    pub fn into_builder<E, F>(self, mut load: F) -> Result<ImageBuilder, E>
+    where
+        F: FnMut(&ManifestEntry, &str) -> Result<Vec<u8>, E>,
+    {
+        let mut builder = ImageBuilder::new(self.size).format_options(self.format);
+        if let Some(time) = self.time {
+            builder = builder.time(time);
+        }
+
+        for entry in self.entries {
+            let mut node = match &entry.content {
+                ManifestContent::Directory => Node::directory(&entry.path),
+                ManifestContent::Text(text) => Node::file(&entry.path, text.as_bytes()),
+                ManifestContent::Source(source) => Node::file(&entry.path, load(&entry, source)?),
+            };
+            if let Some(attributes) = entry.attributes {
+                node.attributes = attributes;
+            }
+            node.time = entry.time;
+            builder = builder.node(node);
+        }
+
+        Ok(builder)
+    }
+
+    /// Termine une table : une entrée doit avoir un chemin.
+    fn close(&mut self, table: Table) -> Result<(), ManifestError> {
+        if let Table::Entry(entry) = table {
+            if entry.path.is_empty() {
+                return Err(ManifestError {
+                    line: entry.line,
+                    kind: ManifestErrorKind::MissingKey("path"),
+                });
+            }
+            self.entries.push(entry);
+        }
+        Ok(())
+    }
+}
+
+/// Retire un commentaire `#` situé hors d’une chaîne.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(text: &str) -> Option<Value> {
+    if let Some(body) = text.strip_prefix('"') {
+        let body = body.strip_suffix('"')?;
+        let mut out = String::new();
+        let mut chars = body.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => out.push(match chars.next()? {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    '0' => '\0',
+                    '"' => '"',
+                    '\\' => '\\',
+                    _ => return None,
+                }),
+                '"' => return None,
+                c => out.push(c),
+            }
+        }
+        return Some(Value::Text(out));
+    }
+
+    let digits: String = text.chars().filter(|&c| c != '_').collect();
+    let value = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse().ok()?,
+    };
+    Some(Value::Integer(value))
+}
+
+/// Taille avec suffixe facultatif `K`, `M` ou `G` (puissances de 1024).
+fn parse_size(text: &str) -> Option<u64> {
+    let (digits, shift) = match text.char_indices().last()? {
+        (i, 'K' | 'k') => (&text[..i], 10),
+        (i, 'M' | 'm') => (&text[..i], 20),
+        (i, 'G' | 'g') => (&text[..i], 30),
+        _ => (text, 0),
+    };
+
+    digits.parse::<u64>().ok()?.checked_mul(1 << shift)
+}
+
+/// Attributs `R`, `H`, `S` et `A` (dans n’importe quel ordre, chaîne vide
+/// pour aucun).
+fn parse_attributes(text: &str) -> Option<u8> {
+    text.chars().try_fold(0u8, |acc, c| {
+        let bit = match c.to_ascii_uppercase() {
+            'R' => ATTR_READ_ONLY,
+            'H' => ATTR_HIDDEN,
+            'S' => ATTR_SYSTEM,
+            'A' => ATTR_ARCHIVE,
+            _ => return None,
+        };
+        Some(acc | bit)
+    })
+}
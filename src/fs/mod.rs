@@ -1,8 +1,33 @@
 pub mod boot_sector;
+pub mod backup;
 pub mod fat;
+pub mod fsinfo;
 pub mod clusters;
+pub mod timestamp;
+pub mod lfn;
 pub mod directory;
 pub mod path;
 pub mod ls;
 pub mod cat;
-pub mod cd;
\ No newline at end of file
+pub mod view;
+pub mod cd;
+pub mod walk;
+pub mod tree;
+pub mod du;
+pub mod find;
+pub mod stat;
+pub mod inspect;
+pub mod create;
+#[cfg(feature = "std")]
+pub mod extract;
+#[cfg(feature = "std")]
+pub mod import;
+pub mod check;
+pub mod format;
+pub mod builder;
+pub mod manifest;
+pub mod label;
+pub mod statfs;
+pub mod filesystem;
+pub mod async_fs;
+pub mod error;
\ No newline at end of file
@@ -1,6 +1,8 @@
 //! Résolution des chemins FAT32 (absolus et relatifs)
+use core::fmt;
+
 use crate::fs::boot_sector::BootSector;
-use crate::fs::directory::{DirectoryReader, DirectoryEntry, EntryType};
+use crate::fs::directory::{DirectoryReader, DirectoryEntry, DirectoryError, EntryType};
 use crate::device::block_device::BlockDevice;
 use alloc::vec::Vec;
 
@@ -9,6 +11,33 @@ pub enum PathError {
     NotFound,
     NotADirectory,
     EmptyPath,
+    /// Le répertoire situé au cluster donné n’a pas pu être lu.
+    Directory {
+        cluster: u32,
+        source: DirectoryError,
+    },
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::NotFound => write!(f, "no such file or directory"),
+            PathError::NotADirectory => write!(f, "not a directory"),
+            PathError::EmptyPath => write!(f, "empty path"),
+            PathError::Directory { cluster, .. } => {
+                write!(f, "cannot read directory at cluster {}", cluster)
+            }
+        }
+    }
+}
+
+impl core::error::Error for PathError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            PathError::Directory { source, .. } => Some(source),
+            _ => None,
+        }
+    }
 }
 
 pub struct PathResolver<'a, D: crate::device::block_device::BlockDevice> {
@@ -30,34 +59,11 @@ impl<'a, D: crate::device::block_device::BlockDevice> PathResolver<'a, D> {
         path: &str,
         cwd: u32,
     ) -> Result<(u32, Option<DirectoryEntry>), PathError> {
-        if path.is_empty() {
-            return Err(PathError::EmptyPath);
-        }
-
-        let mut current_cluster = if path.starts_with('/') {
-            self.boot.root_cluster
-        } else {
-            cwd
-        };
-
-        let parts = path.split('/').filter(|p| !p.is_empty());
-
+        let mut current_cluster = start_cluster(self.boot, path, cwd)?;
         let mut last_entry = None;
 
-        for part in parts {
-            if part == "." {
-                continue;
-            }
-
-            let entries = self
-                .dir_reader
-                .read_dir(current_cluster)
-                .map_err(|_| PathError::NotFound)?;
-
-            let entry = entries
-                .into_iter()
-                .find(|e| e.name == part)
-                .ok_or(PathError::NotFound)?;
+        for part in components(path) {
+            let entry = find_entry(self.read_dir(current_cluster)?, part)?;
 
             match entry.entry_type {
                 EntryType::Directory => {
@@ -82,7 +88,29 @@ impl<'a, D: BlockDevice> PathResolver<'a, D> {
     {
         self.dir_reader
             .read_dir(cluster)
-            .map_err(|_| PathError::NotFound)
+            .map_err(|source| PathError::Directory { cluster, source })
+    }
+}
+
+
+/// Cluster d’où part la résolution : la racine pour un chemin absolu.
+pub(crate) fn start_cluster(boot: &BootSector, path: &str, cwd: u32) -> Result<u32, PathError> {
+    match path {
+        "" => Err(PathError::EmptyPath),
+        p if p.starts_with('/') => Ok(boot.root_cluster),
+        _ => Ok(cwd),
     }
 }
 
+/// Composants d’un chemin, sans les `.` ni les séparateurs répétés.
+pub(crate) fn components(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|p| !p.is_empty() && *p != ".")
+}
+
+/// Entrée nommée `part` (nom 8.3 ou nom long) d’un répertoire lu.
+pub(crate) fn find_entry(entries: Vec<DirectoryEntry>, part: &str) -> Result<DirectoryEntry, PathError> {
+    entries
+        .into_iter()
+        .find(|e| e.has_name(part))
+        .ok_or(PathError::NotFound)
+}
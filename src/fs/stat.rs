@@ -0,0 +1,196 @@
+//! Informations détaillées sur une entrée (équivalent de `stat`).
+//!
+//! Destiné au diagnostic : la chaîne de clusters est suivie entrée par
+//! entrée dans la FAT et s’arrête proprement sur une anomalie (cluster
+//! libre, défectueux, hors volume ou boucle), qui est rapportée au lieu
+//! de faire échouer la commande.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::boot_sector::BootSector;
+use crate::fs::clusters::{extents, Extent};
+use crate::fs::directory::{
+    DirectoryEntry, EntryType, SlotLocation, ATTR_ARCHIVE, ATTR_DIRECTORY, ATTR_VOLUME_ID,
+};
+use crate::fs::error::{FsError, FsResult};
+use crate::fs::fat::{ChainEnd, Fat};
+use crate::fs::path::PathResolver;
+use crate::fs::timestamp::DateTime;
+
+/// Attribut « lecture seule ».
+pub const ATTR_READ_ONLY: u8 = 0x01;
+/// Attribut « caché ».
+pub const ATTR_HIDDEN: u8 = 0x02;
+/// Attribut « système ».
+pub const ATTR_SYSTEM: u8 = 0x04;
+
+/// Résultat de `stat`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryStat {
+    pub path: String,
+    /// Nom 8.3 (`/` pour la racine).
+    pub short_name: String,
+    pub long_name: Option<String>,
+    pub entry_type: EntryType,
+    pub attributes: u8,
+    pub created: Option<DateTime>,
+    pub modified: Option<DateTime>,
+    pub accessed: Option<DateTime>,
+    /// Taille déclarée dans l’entrée.
+    pub size: u64,
+    pub cluster_size: u64,
+    pub first_cluster: u32,
+    /// Chaîne de clusters, regroupée en suites consécutives.
+    pub extents: Vec<Extent>,
+    pub chain_end: ChainEnd,
+    /// Slot 8.3 de l’entrée dans son répertoire (`None` pour la racine).
+    pub location: Option<SlotLocation>,
+}
+
+impl EntryStat {
+    pub fn cluster_count(&self) -> u64 {
+        self.extents.iter().map(|e| e.length as u64).sum()
+    }
+
+    /// Octets réellement alloués.
+    pub fn allocated(&self) -> u64 {
+        self.cluster_count() * self.cluster_size
+    }
+
+    /// Nombre de fragments (1 pour un fichier contigu, 0 sans clusters).
+    pub fn fragments(&self) -> usize {
+        self.extents.len()
+    }
+}
+
+impl fmt::Display for EntryStat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.entry_type {
+            EntryType::File => "file",
+            EntryType::Directory => "directory",
+        };
+
+        writeln!(f, "    Path: {}", self.path)?;
+        writeln!(f, "   Short: {}", self.short_name)?;
+        writeln!(f, "    Long: {}", self.long_name.as_deref().unwrap_or("-"))?;
+        writeln!(f, "    Type: {}", kind)?;
+        writeln!(f, "   Attrs: {} ({:#04x})", attribute_flags(self.attributes), self.attributes)?;
+        writeln!(f, "    Size: {}", self.size)?;
+        writeln!(
+            f,
+            "   Alloc: {} ({} clusters of {})",
+            self.allocated(),
+            self.cluster_count(),
+            self.cluster_size
+        )?;
+        for (name, value) in [
+            ("Created", self.created),
+            ("Modified", self.modified),
+            ("Accessed", self.accessed),
+        ] {
+            match value {
+                Some(value) => writeln!(f, "{:>8}: {}", name, value)?,
+                None => writeln!(f, "{:>8}: -", name)?,
+            }
+        }
+        match self.location {
+            Some(at) => writeln!(f, "   Entry: cluster {}, slot {}", at.cluster, at.index)?,
+            None => writeln!(f, "   Entry: -")?,
+        }
+        writeln!(f, "   First: {}", self.first_cluster)?;
+
+        write!(f, "   Chain:")?;
+        for extent in &self.extents {
+            match extent.length {
+                1 => write!(f, " {}", extent.start)?,
+                n => write!(f, " {}-{}", extent.start, extent.start + n - 1)?,
+            }
+        }
+        writeln!(f)?;
+        writeln!(f, "   Frags: {}", self.fragments())?;
+        write!(f, "     End: {}", self.chain_end)
+    }
+}
+
+/// Attributs sous forme `RHSVDA`, un tiret par attribut absent.
+pub fn attribute_flags(attributes: u8) -> String {
+    [
+        (ATTR_READ_ONLY, 'R'),
+        (ATTR_HIDDEN, 'H'),
+        (ATTR_SYSTEM, 'S'),
+        (ATTR_VOLUME_ID, 'V'),
+        (ATTR_DIRECTORY, 'D'),
+        (ATTR_ARCHIVE, 'A'),
+    ]
+    .iter()
+    .map(|&(bit, c)| if attributes & bit != 0 { c } else { '-' })
+    .collect()
+}
+
+pub struct Stat<'a, D: BlockDevice> {
+    resolver: &'a PathResolver<'a, D>,
+    fat: &'a Fat<'a, D>,
+    boot: &'a BootSector,
+}
+
+impl<'a, D: BlockDevice> Stat<'a, D> {
+    pub fn new(resolver: &'a PathResolver<'a, D>, fat: &'a Fat<'a, D>, boot: &'a BootSector) -> Self {
+        Self { resolver, fat, boot }
+    }
+
+    pub fn stat(&self, path: &str, cwd: u32) -> FsResult<EntryStat> {
+        let (cluster, entry) = self.resolver.resolve(path, cwd)?;
+
+        let mut stat = match entry {
+            Some(entry) => self.entry_stat(path, entry),
+            // Racine ou répertoire courant : pas d’entrée qui le décrive.
+            None => EntryStat {
+                path: String::from(path),
+                short_name: String::from(if cluster == self.boot.root_cluster { "/" } else { "." }),
+                long_name: None,
+                entry_type: EntryType::Directory,
+                attributes: ATTR_DIRECTORY,
+                created: None,
+                modified: None,
+                accessed: None,
+                size: 0,
+                cluster_size: self.boot.cluster_size() as u64,
+                first_cluster: cluster,
+                extents: Vec::new(),
+                chain_end: ChainEnd::Empty,
+                location: None,
+            },
+        };
+
+        let (chain, end) = self
+            .fat
+            .follow_chain(stat.first_cluster)
+            .map_err(|e| FsError::from(e).with_cluster(stat.first_cluster))?;
+        stat.extents = extents(&chain);
+        stat.chain_end = end;
+
+        Ok(stat)
+    }
+
+    fn entry_stat(&self, path: &str, entry: DirectoryEntry) -> EntryStat {
+        EntryStat {
+            path: String::from(path),
+            short_name: entry.name,
+            long_name: entry.long_name,
+            entry_type: entry.entry_type,
+            attributes: entry.attributes,
+            created: entry.created,
+            modified: entry.modified,
+            accessed: entry.accessed,
+            size: entry.size as u64,
+            cluster_size: self.boot.cluster_size() as u64,
+            first_cluster: entry.start_cluster,
+            extents: Vec::new(),
+            chain_end: ChainEnd::Empty,
+            location: Some(entry.location),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Statistiques d’occupation d’un volume (équivalent de `statfs`).
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::boot_sector::BootSector;
+use crate::fs::error::FsResult;
+use crate::fs::fat::{Fat, FAT_BAD, FAT_FREE};
+use crate::fs::fsinfo::{FsInfo, FSINFO_UNKNOWN};
+
+/// Méthode de calcul du nombre de clusters libres.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreeCount {
+    /// Parcours complet de la FAT (exact).
+    Scan,
+    /// Indication du FSInfo si elle est plausible, parcours complet sinon.
+    Hint,
+}
+
+/// Occupation d’un volume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsStats {
+    pub total_clusters: u32,
+    pub free_clusters: u32,
+    /// `None` si la FAT n’a pas été parcourue (indication FSInfo utilisée).
+    pub bad_clusters: Option<u32>,
+    pub cluster_size: u32,
+}
+
+impl FsStats {
+    pub fn total_bytes(&self) -> u64 {
+        self.total_clusters as u64 * self.cluster_size as u64
+    }
+
+    pub fn free_bytes(&self) -> u64 {
+        self.free_clusters as u64 * self.cluster_size as u64
+    }
+
+    /// Octets occupés, clusters défectueux compris.
+    pub fn used_bytes(&self) -> u64 {
+        self.total_bytes() - self.free_bytes()
+    }
+}
+
+/// Calcul des statistiques d’un volume.
+pub struct StatFs<'a, D: BlockDevice> {
+    device: &'a D,
+    boot: &'a BootSector,
+    fat_copy: u8,
+}
+
+impl<'a, D: BlockDevice> StatFs<'a, D> {
+    pub fn new(device: &'a D, boot: &'a BootSector) -> Self {
+        Self {
+            device,
+            boot,
+            fat_copy: boot.active_fat(),
+        }
+    }
+
+    /// Lit la copie `copy` de la FAT plutôt que la copie active.
+    pub fn with_fat_copy(mut self, copy: u8) -> Self {
+        self.fat_copy = copy;
+        self
+    }
+
+    pub fn stats(&self, mode: FreeCount) -> FsResult<FsStats> {
+        let total_clusters = self.boot.cluster_count();
+        let cluster_size = self.boot.cluster_size() as u32;
+
+        if mode == FreeCount::Hint
+            && let Ok(info) = FsInfo::read(self.device, self.boot)
+            && info.free_count != FSINFO_UNKNOWN
+            && info.free_count <= total_clusters
+        {
+            return Ok(FsStats {
+                total_clusters,
+                free_clusters: info.free_count,
+                bad_clusters: None,
+                cluster_size,
+            });
+        }
+
+        let mut free = 0;
+        let mut bad = 0;
+        Fat::new(self.device, self.boot).scan(self.fat_copy, |_, value| match value {
+            FAT_FREE => free += 1,
+            FAT_BAD => bad += 1,
+            _ => {}
+        })?;
+
+        Ok(FsStats {
+            total_clusters,
+            free_clusters: free,
+            bad_clusters: Some(bad),
+            cluster_size,
+        })
+    }
+}
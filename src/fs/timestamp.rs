@@ -0,0 +1,134 @@
+//! Dates et heures FAT (format MS-DOS).
+//!
+//! Une date tient sur 16 bits (année depuis 1980, mois, jour) et une heure
+//! sur 16 bits (heures, minutes, secondes / 2). Les dates sont locales :
+//! FAT ne connaît pas de fuseau horaire.
+
+use core::fmt;
+
+/// Date et heure d’une entrée de répertoire.
+///
+/// L’ordre des champs rend la comparaison chronologique.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateTime {
+    /// Décode une date et une heure FAT ; `None` pour une date nulle ou invalide.
+    pub fn from_fat(date: u16, time: u16) -> Option<Self> {
+        let month = ((date >> 5) & 0x0F) as u8;
+        let day = (date & 0x1F) as u8;
+        if !(1..=12).contains(&month) || day == 0 {
+            return None;
+        }
+
+        Some(Self {
+            year: 1980 + (date >> 9),
+            month,
+            day,
+            hour: (time >> 11) as u8,
+            minute: ((time >> 5) & 0x3F) as u8,
+            second: ((time & 0x1F) * 2) as u8,
+        })
+    }
+
+    /// Encode en `(date, heure)` FAT ; les années hors de 1980-2107 sont bornées.
+    pub fn to_fat(&self) -> (u16, u16) {
+        let year = self.year.clamp(1980, 2107) - 1980;
+        let date = (year << 9) | ((self.month as u16) << 5) | self.day as u16;
+        let time = ((self.hour as u16) << 11) | ((self.minute as u16) << 5) | (self.second as u16 / 2);
+        (date, time)
+    }
+
+    /// Analyse `AAAA-MM-JJ` ou `AAAA-MM-JJ HH:MM[:SS]` (séparateur espace ou `T`).
+    pub fn parse(text: &str) -> Option<Self> {
+        let (date, time) = match text.split_once([' ', 'T']) {
+            Some((date, time)) => (date, Some(time)),
+            None => (text, None),
+        };
+
+        let mut date = date.split('-');
+        let year = date.next()?.parse().ok()?;
+        let month = date.next()?.parse().ok()?;
+        let day = date.next()?.parse().ok()?;
+        if date.next().is_some() {
+            return None;
+        }
+
+        let (mut hour, mut minute, mut second) = (0, 0, 0);
+        if let Some(time) = time {
+            let mut time = time.split(':');
+            hour = time.next()?.parse().ok()?;
+            minute = time.next()?.parse().ok()?;
+            second = time.next().map_or(Some(0), |s| s.parse().ok())?;
+            if time.next().is_some() {
+                return None;
+            }
+        }
+
+        let value = Self { year, month, day, hour, minute, second };
+        value.is_valid().then_some(value)
+    }
+
+    /// Secondes depuis 1970-01-01 00:00:00, la date étant vue comme UTC.
+    pub fn to_unix(&self) -> i64 {
+        // Algorithme « days from civil ».
+        let (y, m, d) = (self.year as i64, self.month as i64, self.day as i64);
+        let y = if m <= 2 { y - 1 } else { y };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146_097 + doe - 719_468;
+
+        days * 86_400 + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64
+    }
+
+    /// Date UTC d’un instant en secondes depuis 1970-01-01 (inverse de `to_unix`).
+    pub fn from_unix(seconds: i64) -> Self {
+        // Algorithme « civil from days ».
+        let (days, rest) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year: year.clamp(0, u16::MAX as i64) as u16,
+            month,
+            day,
+            hour: (rest / 3600) as u8,
+            minute: (rest / 60 % 60) as u8,
+            second: (rest % 60) as u8,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month)
+            && (1..=31).contains(&self.day)
+            && self.hour < 24
+            && self.minute < 60
+            && self.second < 60
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
@@ -0,0 +1,84 @@
+//! Affichage d’une arborescence (équivalent de `tree`).
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::directory::DirectoryReader;
+use crate::fs::walk::{WalkEntry, WalkOptions, WalkOrder, Walker};
+
+pub struct Tree<'a, D: BlockDevice> {
+    dirs: &'a DirectoryReader<'a, D>,
+}
+
+impl<'a, D: BlockDevice> Tree<'a, D> {
+    pub fn new(dirs: &'a DirectoryReader<'a, D>) -> Self {
+        Self { dirs }
+    }
+
+    /// Rend l’arborescence du répertoire `start_cluster` sous forme de texte.
+    ///
+    /// Les répertoires illisibles apparaissent comme une ligne d’erreur.
+    pub fn render(&self, start_cluster: u32, path: &str, max_depth: Option<usize>) -> String {
+        let options = WalkOptions {
+            order: WalkOrder::DepthFirst,
+            max_depth,
+        };
+
+        // (profondeur, texte) ; une erreur se place au niveau du répertoire fautif.
+        let mut lines: Vec<(usize, String)> = Vec::new();
+        let (mut directories, mut files) = (0, 0);
+
+        for item in Walker::new(self.dirs).walk(start_cluster, path, options) {
+            match item {
+                Ok(WalkEntry { depth, entry, .. }) => {
+                    if entry.entry_type.is_dir() {
+                        directories += 1;
+                    } else {
+                        files += 1;
+                    }
+                    lines.push((depth, String::from(entry.display_name())));
+                }
+                Err(e) => {
+                    let depth = lines.last().map_or(1, |(d, _)| d + 1);
+                    lines.push((depth, format!("[{}]", e)));
+                }
+            }
+        }
+
+        // Une ligne est la dernière de sa fratrie si aucune ligne suivante
+        // n’a la même profondeur avant de remonter plus haut.
+        let mut last = alloc::vec![false; lines.len()];
+        let mut later: Vec<bool> = Vec::new();
+        for (i, (depth, _)) in lines.iter().enumerate().rev() {
+            later.resize(depth + 1, false);
+            last[i] = !later[*depth];
+            later[*depth] = true;
+        }
+
+        let mut out = String::new();
+        let _ = writeln!(out, "{}", path);
+
+        // open[k] : l’ancêtre de profondeur k + 1 a encore des frères à suivre.
+        let mut open: Vec<bool> = Vec::new();
+        for (i, (depth, name)) in lines.iter().enumerate() {
+            open.truncate(depth - 1);
+            for &more in &open {
+                out.push_str(if more { "│   " } else { "    " });
+            }
+            out.push_str(if last[i] { "└── " } else { "├── " });
+            out.push_str(name);
+            out.push('\n');
+            open.push(!last[i]);
+        }
+
+        let _ = write!(
+            out,
+            "\n{} directories, {} files",
+            directories, files
+        );
+        out
+    }
+}
@@ -0,0 +1,167 @@
+//! Lecture partielle de fichiers : `head`, `tail` et `hexdump -C`.
+//!
+//! Seuls les clusters couvrant la plage demandée sont lus : `tail` sur un
+//! gros fichier ne parcourt que la FAT pour atteindre la fin, puis lit les
+//! derniers clusters.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::cat::{Cat, CatError, OpenFile};
+
+/// Quantité à afficher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Count {
+    Bytes(u64),
+    Lines(u64),
+}
+
+pub struct View<'a, D: BlockDevice> {
+    cat: &'a Cat<'a, D>,
+    /// Taille des lectures successives (un cluster).
+    chunk: usize,
+}
+
+impl<'a, D: BlockDevice> View<'a, D> {
+    pub fn new(cat: &'a Cat<'a, D>, chunk: usize) -> Self {
+        Self { cat, chunk: chunk.max(1) }
+    }
+
+    /// Début du fichier.
+    pub fn head(&self, file: &OpenFile, count: Count) -> Result<Vec<u8>, CatError> {
+        let len = match count {
+            Count::Bytes(n) => n.min(file.size()),
+            Count::Lines(n) => self.head_len(file, n)?,
+        };
+
+        self.read(file, 0, len)
+    }
+
+    /// Fin du fichier.
+    pub fn tail(&self, file: &OpenFile, count: Count) -> Result<Vec<u8>, CatError> {
+        let size = self.cat.readable(file);
+        let start = match count {
+            Count::Bytes(n) => size.saturating_sub(n),
+            Count::Lines(n) => self.tail_start(file, n)?,
+        };
+
+        self.read(file, start, size - start)
+    }
+
+    /// Lit `len` octets à partir de `offset` (bornés à la fin du fichier,
+    /// ou de sa chaîne si elle est plus courte).
+    pub fn read(&self, file: &OpenFile, offset: u64, len: u64) -> Result<Vec<u8>, CatError> {
+        let len = len.min(self.cat.readable(file).saturating_sub(offset));
+        let mut data = vec![0u8; len as usize];
+
+        let n = self.cat.read_at(file, offset, &mut data)?;
+        data.truncate(n);
+        Ok(data)
+    }
+
+    /// Longueur des `lines` premières lignes, fin de ligne comprise.
+    fn head_len(&self, file: &OpenFile, lines: u64) -> Result<u64, CatError> {
+        if lines == 0 {
+            return Ok(0);
+        }
+
+        let mut buf = vec![0u8; self.chunk];
+        let mut seen = 0;
+        let mut pos = 0;
+        while pos < file.size() {
+            let n = self.cat.read_at(file, pos, &mut buf)?;
+            if n == 0 {
+                break;
+            }
+            for (i, &b) in buf[..n].iter().enumerate() {
+                if b == b'\n' {
+                    seen += 1;
+                    if seen == lines {
+                        return Ok(pos + i as u64 + 1);
+                    }
+                }
+            }
+            pos += n as u64;
+        }
+
+        Ok(file.size())
+    }
+
+    /// Début des `lines` dernières lignes ; un saut de ligne final ne
+    /// compte pas comme le début d’une ligne vide.
+    fn tail_start(&self, file: &OpenFile, lines: u64) -> Result<u64, CatError> {
+        let size = self.cat.readable(file);
+        if lines == 0 {
+            return Ok(size);
+        }
+
+        let mut buf = vec![0u8; self.chunk];
+        let mut seen = 0;
+        let mut end = size;
+        while end > 0 {
+            let start = end.saturating_sub(self.chunk as u64);
+            let len = (end - start) as usize;
+            self.cat.read_at(file, start, &mut buf[..len])?;
+
+            for i in (0..len).rev() {
+                let at = start + i as u64;
+                if buf[i] == b'\n' && at + 1 != size {
+                    seen += 1;
+                    if seen == lines {
+                        return Ok(at + 1);
+                    }
+                }
+            }
+            end = start;
+        }
+
+        Ok(0)
+    }
+}
+
+/// Mise en forme canonique de `hexdump -C` ; `offset` est l’adresse du
+/// premier octet. Les lignes identiques consécutives sont remplacées par `*`.
+pub fn hexdump(data: &[u8], offset: u64) -> String {
+    let mut out = String::new();
+    let mut previous: Option<&[u8]> = None;
+    let mut squeezed = false;
+
+    for (i, line) in data.chunks(16).enumerate() {
+        if line.len() == 16 && previous == Some(line) {
+            if !squeezed {
+                out.push_str("*\n");
+                squeezed = true;
+            }
+            continue;
+        }
+        previous = Some(line);
+        squeezed = false;
+
+        let _ = write!(out, "{:08x} ", offset + i as u64 * 16);
+        for col in 0..16 {
+            if col == 8 {
+                out.push(' ');
+            }
+            match line.get(col) {
+                Some(b) => {
+                    let _ = write!(out, " {:02x}", b);
+                }
+                None => out.push_str("   "),
+            }
+        }
+
+        let ascii: String = line
+            .iter()
+            .map(|&b| if (0x20..0x7F).contains(&b) { b as char } else { '.' })
+            .collect();
+        let _ = writeln!(out, "  |{}|", ascii);
+    }
+
+    if !data.is_empty() {
+        let _ = writeln!(out, "{:08x}", offset + data.len() as u64);
+    }
+    out
+}
@@ -0,0 +1,179 @@
+//! Parcours récursif d’une arborescence FAT32.
+//!
+//! Le parcours est paresseux : un répertoire n’est lu qu’au moment où ses
+//! entrées sont demandées. Les entrées `.` et `..` sont ignorées et chaque
+//! répertoire n’est visité qu’une fois, ce qui protège des cycles d’un
+//! volume corrompu.
+
+use alloc::collections::{BTreeSet, VecDeque};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+
+use crate::device::block_device::BlockDevice;
+use crate::fs::directory::{DirectoryEntry, DirectoryReader};
+use crate::fs::error::{FsError, FsResult};
+
+/// Ordre de parcours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkOrder {
+    /// Préfixe : un répertoire est suivi de tout son contenu.
+    DepthFirst,
+    /// Niveau par niveau.
+    BreadthFirst,
+}
+
+/// Options de parcours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalkOptions {
+    pub order: WalkOrder,
+    /// Profondeur maximale (1 : contenu direct du point de départ).
+    pub max_depth: Option<usize>,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            order: WalkOrder::DepthFirst,
+            max_depth: None,
+        }
+    }
+}
+
+/// Entrée rencontrée pendant le parcours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkEntry {
+    /// Chemin complet de l’entrée.
+    pub path: String,
+    /// Profondeur par rapport au point de départ (1 pour le contenu direct).
+    pub depth: usize,
+    pub entry: DirectoryEntry,
+}
+
+/// Répertoire en attente ou en cours de lecture.
+struct Frame {
+    cluster: u32,
+    path: String,
+    depth: usize,
+    entries: Option<vec::IntoIter<DirectoryEntry>>,
+}
+
+/// Crée des parcours à partir d’un lecteur de répertoires.
+pub struct Walker<'a, D: BlockDevice> {
+    dirs: &'a DirectoryReader<'a, D>,
+}
+
+impl<'a, D: BlockDevice> Walker<'a, D> {
+    pub fn new(dirs: &'a DirectoryReader<'a, D>) -> Self {
+        Self { dirs }
+    }
+
+    /// Parcourt le répertoire situé à `start_cluster`, dont le chemin est `path`.
+    pub fn walk(&self, start_cluster: u32, path: &str, options: WalkOptions) -> Walk<'a, D> {
+        let mut frames = VecDeque::new();
+        if options.max_depth != Some(0) {
+            frames.push_back(Frame {
+                cluster: start_cluster,
+                path: String::from(path),
+                depth: 0,
+                entries: None,
+            });
+        }
+
+        let mut visited = BTreeSet::new();
+        visited.insert(start_cluster);
+
+        Walk {
+            dirs: self.dirs,
+            options,
+            visited,
+            frames,
+        }
+    }
+}
+
+/// Itérateur de parcours ; une erreur de lecture d’un répertoire est
+/// renvoyée à sa place, puis le parcours continue.
+pub struct Walk<'a, D: BlockDevice> {
+    dirs: &'a DirectoryReader<'a, D>,
+    options: WalkOptions,
+    visited: BTreeSet<u32>,
+    /// Pile (profondeur) ou file (largeur) des répertoires à lire.
+    frames: VecDeque<Frame>,
+}
+
+impl<'a, D: BlockDevice> Walk<'a, D> {
+    fn current(&mut self) -> Option<&mut Frame> {
+        match self.options.order {
+            WalkOrder::DepthFirst => self.frames.back_mut(),
+            WalkOrder::BreadthFirst => self.frames.front_mut(),
+        }
+    }
+
+    fn pop_current(&mut self) {
+        match self.options.order {
+            WalkOrder::DepthFirst => self.frames.pop_back(),
+            WalkOrder::BreadthFirst => self.frames.pop_front(),
+        };
+    }
+}
+
+impl<'a, D: BlockDevice> Iterator for Walk<'a, D> {
+    type Item = FsResult<WalkEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let dirs = self.dirs;
+            let frame = self.current()?;
+
+            if frame.entries.is_none() {
+                match dirs.read_dir(frame.cluster) {
+                    Ok(entries) => frame.entries = Some(entries.into_iter()),
+                    Err(e) => {
+                        let err = FsError::from(e)
+                            .with_cluster(frame.cluster)
+                            .with_path(&frame.path);
+                        self.pop_current();
+                        return Some(Err(err));
+                    }
+                }
+            }
+
+            let Some(entry) = frame.entries.as_mut().and_then(Iterator::next) else {
+                self.pop_current();
+                continue;
+            };
+
+            if entry.name == "." || entry.name == ".." {
+                continue;
+            }
+
+            let path = join(&frame.path, entry.display_name());
+            let depth = frame.depth + 1;
+
+            let descend = entry.entry_type.is_dir()
+                && entry.start_cluster >= 2
+                && self.options.max_depth.is_none_or(|max| depth < max)
+                && self.visited.insert(entry.start_cluster);
+            if descend {
+                self.frames.push_back(Frame {
+                    cluster: entry.start_cluster,
+                    path: path.clone(),
+                    depth,
+                    entries: None,
+                });
+            }
+
+            return Some(Ok(WalkEntry { path, depth, entry }));
+        }
+    }
+}
+
+/// Concatène un chemin de répertoire et un nom.
+pub fn join(dir: &str, name: &str) -> String {
+    if dir.ends_with('/') {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
@@ -1,5 +1,9 @@
 #![no_std]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod device;
-pub mod fs;
\ No newline at end of file
+pub mod fs;
+#[cfg(feature = "testing")]
+pub mod testing;
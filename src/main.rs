@@ -1,3 +1,849 @@
-fn main() {
-    println!("Hello, world!");
+//! Simulateur FAT32 en ligne de commande.
+//!
+//! `rust_project <image> [--overlay <patch>] [--stats] [--salvage] [commande [arguments...]]`
+//!
+//! Sans commande, un shell interactif est lancé sur l’image ; il garde le
+//! répertoire courant entre deux commandes (`cd`).
+//!
+//! Avec `--overlay`, l’image n’est jamais modifiée : les écritures vont dans
+//! un delta, rechargé depuis le patch au démarrage et sauvegardé à la fin.
+//!
+//! Avec `--stats`, les accès à l’image sont comptés et affichés sur la sortie
+//! d’erreur après chaque commande.
+//!
+//! Une image tronquée n’est pas montée, sauf avec `--salvage` : les clusters
+//! situés au-delà de la fin de l’image sont alors illisibles.
+
+use std::env;
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use rust_project::device::block_device::BlockDevice;
+use rust_project::device::file::FileBlockDevice;
+use rust_project::device::overlay::OverlayDevice;
+use rust_project::device::stats::{IoStats, StatsDevice};
+use rust_project::fs::directory::EntryType;
+use rust_project::fs::extract::ExtractOptions;
+use rust_project::fs::backup::BootSource;
+use rust_project::fs::boot_sector::BootSectorError;
+use rust_project::fs::filesystem::{FileSystem, MountOptions};
+use rust_project::fs::find::FindOptions;
+use rust_project::fs::import::ImportOptions;
+use rust_project::fs::manifest::Manifest;
+use rust_project::fs::statfs::FreeCount;
+use rust_project::fs::timestamp::DateTime;
+use rust_project::fs::view::{hexdump, Count};
+
+const USAGE: &str = "usage: rust_project <image> [--overlay <patch>] [--stats] [--salvage] [command [args...]]
+
+  --overlay <patch>
+                   keep writes in a patch file instead of the image; the
+                   patch is loaded if it exists and saved on exit
+  --stats          print I/O counters for the image after each command
+  --salvage        mount a truncated image; clusters past its end are
+                   unreadable
+
+commands:
+  ls [path]        list a directory
+  cat <path>       print a file
+  cd <path>        change the current directory (interactive shell)
+  pwd              print the current directory
+  df [--fast]      show space usage (--fast trusts the FSInfo hint)
+  head [-c bytes | -n lines] <path>
+  tail [-c bytes | -n lines] <path>
+                   print the start or end of a file (default: 10 lines)
+  hexdump [-C] [-s offset] [-n length] <path>
+                   canonical hex+ASCII dump of a file
+  stat <path>      show entry details and cluster chain
+  extract [--no-times] <path> <host directory>
+                   copy a file or directory tree out of the image
+  build <manifest> create the image from a manifest (see src/fs/manifest.rs)
+  import [--no-times] <host path> <directory>
+                   copy a host file, or the contents of a host directory,
+                   into the image (opens the image read-write)
+  apply <patch>    write the sectors of an overlay patch into the image
+  bootcmp          list the fields that differ between the boot sector and
+                   its backup
+  bootfix primary|backup
+                   restore the other boot sector from the given one
+  sector <lba>     dump a raw sector
+  cluster <n>      dump a raw data cluster
+  fatent [-f copy] <n>
+                   decode the FAT entry of a cluster
+  fatdiff          list the FAT entries that differ between FAT copies
+  fatsync <copy>   copy a FAT copy over all the others
+  chain <n>        follow a cluster chain
+  slot <cluster> <index>
+                   decode a 32-byte directory slot
+  tree [path] [-L depth]
+                   show the directory hierarchy
+  du [path] [-d depth]
+                   show allocated and logical sizes per directory
+  find [path] [-name glob] [-case] [-type f|d] [-minsize n[K|M|G]]
+       [-maxsize n[K|M|G]] [-newer date] [-older date] [-maxdepth n]
+                   search entries (dates: YYYY-MM-DD[THH:MM[:SS]])";
+
+/// Commandes qui modifient l’image : elle est alors ouverte en écriture.
+const WRITE_COMMANDS: &[&str] = &["import", "apply", "bootfix", "fatsync"];
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some((image, command)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    if let Some(("build", rest)) = command.split_first().map(|(c, rest)| (c.as_str(), rest)) {
+        return match build(image, rest) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(message) => {
+                eprintln!("{}", message);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let mut overlay = None;
+    let mut stats = false;
+    let mut mount = MountOptions::default();
+    let mut command = command;
+    loop {
+        match command.split_first() {
+            Some((flag, rest)) if flag == "--overlay" => match rest.split_first() {
+                Some((patch, rest)) => {
+                    overlay = Some(patch.as_str());
+                    command = rest;
+                }
+                None => {
+                    eprintln!("--overlay: missing value");
+                    return ExitCode::FAILURE;
+                }
+            },
+            Some((flag, rest)) if flag == "--stats" => {
+                stats = true;
+                command = rest;
+            }
+            Some((flag, rest)) if flag == "--salvage" => {
+                mount.salvage = true;
+                command = rest;
+            }
+            _ => break,
+        }
+    }
+
+    // Avec un overlay, le volume est modifiable mais l’image reste en lecture seule.
+    mount.writable = command.first().is_some_and(|c| WRITE_COMMANDS.contains(&c.as_str()));
+    let opened = if mount.writable && overlay.is_none() {
+        FileBlockDevice::open_rw(image)
+    } else {
+        FileBlockDevice::open(image)
+    };
+    let device = match opened {
+        Ok(device) => device,
+        Err(e) => {
+            eprintln!("{}: {}", image, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if !stats {
+        return open(&device, image, overlay, command, &mount, None);
+    }
+
+    // Compte les accès à l’image elle-même, sous l’overlay éventuel.
+    let device = StatsDevice::new(&device);
+    let take = || {
+        let stats = device.stats();
+        device.reset();
+        stats
+    };
+    open(&device, image, overlay, command, &mount, Some(&take))
+}
+
+/// Relevé des compteurs d’accès, remis à zéro à chaque appel.
+type TakeStats<'a> = Option<&'a dyn Fn() -> IoStats>;
+
+fn open<D: BlockDevice>(
+    device: &D,
+    image: &str,
+    overlay: Option<&str>,
+    command: &[String],
+    mount: &MountOptions,
+    stats: TakeStats<'_>,
+) -> ExitCode {
+    match overlay {
+        Some(patch) => with_overlay(device, image, patch, command, mount, stats),
+        None => session(device, image, command, mount, stats),
+    }
+}
+
+/// Session sur un overlay de l’image, sauvegardé dans `patch` à la fin.
+fn with_overlay<D: BlockDevice>(
+    device: &D,
+    image: &str,
+    patch: &str,
+    command: &[String],
+    mount: &MountOptions,
+    stats: TakeStats<'_>,
+) -> ExitCode {
+    let overlay = OverlayDevice::new(device);
+    match std::fs::read(patch) {
+        Ok(bytes) => {
+            if let Err(e) = overlay.load_patch(&bytes) {
+                eprintln!("{}: {}", patch, e);
+                return ExitCode::FAILURE;
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => {
+            eprintln!("{}: {}", patch, e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let status = session(&overlay, image, command, mount, stats);
+
+    if overlay.is_dirty() {
+        if let Err(e) = std::fs::write(patch, overlay.export_patch()) {
+            eprintln!("{}: {}", patch, e);
+            return ExitCode::FAILURE;
+        }
+        eprintln!("{}: {} sectors modified", patch, overlay.dirty_sectors());
+    }
+    status
+}
+
+/// Monte l’image et exécute la commande, ou le shell interactif.
+fn session<'a, D: BlockDevice>(
+    device: &'a D,
+    image: &'a str,
+    command: &[String],
+    mount: &MountOptions,
+    stats: TakeStats<'a>,
+) -> ExitCode {
+    let fs = match FileSystem::mount_with(device, mount) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("{}: {}", image, report(&e));
+            return ExitCode::FAILURE;
+        }
+    };
+    if fs.boot_source() == BootSource::Backup {
+        eprintln!("{}: warning: boot sector is invalid, mounted from its backup", image);
+    }
+    if let Some(truncation) = fs.truncation() {
+        eprintln!("{}: warning: {}", image, BootSectorError::Truncated(truncation));
+    }
+    if let Ok(flags) = fs.volume_flags()
+        && flags.needs_check()
+    {
+        eprintln!("{}: warning: volume {}; a check is recommended", image, flags);
+    }
+
+    // Les accès du montage ne sont pas attribués à la première commande.
+    if let Some(take) = stats {
+        take();
+    }
+    let mut shell = Shell::new(fs, image, stats);
+
+    if command.is_empty() {
+        shell.interactive();
+        return ExitCode::SUCCESS;
+    }
+
+    let result = shell.run(command);
+    shell.print_stats();
+    if let Err(e) = shell.fs.unmount() {
+        eprintln!("{}: {}", image, report(&e));
+        return ExitCode::FAILURE;
+    }
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Crée l’image `image` décrite par un manifeste (commande `build`).
+fn build(image: &str, args: &[String]) -> Result<(), String> {
+    let [manifest_path] = args else {
+        return Err(String::from("usage: build <manifest>"));
+    };
+    let text = std::fs::read_to_string(manifest_path).map_err(|e| format!("{}: {}", manifest_path, e))?;
+    let manifest = Manifest::parse(&text).map_err(|e| format!("{}: {}", manifest_path, e))?;
+
+    // Les chemins `source` sont relatifs au manifeste.
+    let base = Path::new(manifest_path).parent().unwrap_or(Path::new("."));
+    let builder = manifest.into_builder(|entry, source| {
+        std::fs::read(base.join(source))
+            .map_err(|e| format!("{}: line {}: {}: {}", manifest_path, entry.line, source, e))
+    })?;
+
+    let device = FileBlockDevice::create(image, builder.size()).map_err(|e| format!("{}: {}", image, e))?;
+    builder.build(&device).map_err(|e| format!("{}: {}", image, report(&e)))?;
+
+    println!("{}: {} bytes, {} entries", image, builder.size(), builder.nodes().len());
+    Ok(())
+}
+
+/// État d’une session : volume monté et répertoire courant.
+struct Shell<'a, D: BlockDevice> {
+    fs: FileSystem<'a, D>,
+    image: &'a str,
+    cwd: u32,
+    cwd_path: String,
+    stats: TakeStats<'a>,
+}
+
+impl<'a, D: BlockDevice> Shell<'a, D> {
+    fn new(fs: FileSystem<'a, D>, image: &'a str, stats: TakeStats<'a>) -> Self {
+        let cwd = fs.root_cluster();
+        Self {
+            fs,
+            image,
+            cwd,
+            cwd_path: String::from("/"),
+            stats,
+        }
+    }
+
+    /// Affiche les accès de la dernière commande (`--stats`).
+    fn print_stats(&self) {
+        if let Some(take) = self.stats {
+            eprintln!("stats: {}", take());
+        }
+    }
+
+    fn interactive(&mut self) {
+        let stdin = io::stdin();
+        loop {
+            print!("{}> ", self.cwd_path);
+            let _ = io::stdout().flush();
+
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+                break;
+            }
+
+            let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+            match args.first().map(String::as_str) {
+                None => continue,
+                Some("exit") | Some("quit") => break,
+                Some(_) => {
+                    if let Err(message) = self.run(&args) {
+                        eprintln!("{}", message);
+                    }
+                    self.print_stats();
+                }
+            }
+        }
+    }
+
+    /// Exécute une commande ; l’erreur est le message à afficher.
+    fn run(&mut self, args: &[String]) -> Result<(), String> {
+        let (name, rest) = args.split_first().ok_or_else(|| String::from(USAGE))?;
+        let rest: Vec<&str> = rest.iter().map(String::as_str).collect();
+
+        match (name.as_str(), rest.as_slice()) {
+            ("ls", []) => self.ls(None),
+            ("ls", [path]) => self.ls(Some(path)),
+            ("cat", [path]) => self.cat(path),
+            ("cd", [path]) => self.cd(path),
+            ("pwd", []) => {
+                println!("{}", self.cwd_path);
+                Ok(())
+            }
+            ("df", []) => self.df(FreeCount::Scan),
+            ("df", ["--fast"]) => self.df(FreeCount::Hint),
+            ("stat", [path]) => self.stat(path),
+            ("extract", rest) => self.extract(&parse_args(rest, &[], &["--no-times"])?),
+            ("import", rest) => self.import(&parse_args(rest, &[], &["--no-times"])?),
+            ("apply", [patch]) => self.apply(patch),
+            ("bootcmp", []) => self.boot_compare(),
+            ("bootfix", ["primary"]) => self.boot_fix(BootSource::Primary),
+            ("bootfix", ["backup"]) => self.boot_fix(BootSource::Backup),
+            ("sector", [lba]) => self.sector(parse_number(lba)?),
+            ("cluster", [n]) => self.cluster(parse_number(n)?),
+            ("fatent", [n]) => self.fat_entry(self.fs.boot().active_fat(), parse_number(n)?),
+            ("fatent", ["-f", copy, n]) => self.fat_entry(parse_number(copy)?, parse_number(n)?),
+            ("fatdiff", []) => self.fat_diff(),
+            ("fatsync", [copy]) => self.fat_sync(parse_number(copy)?),
+            ("chain", [n]) => self.chain(parse_number(n)?),
+            ("slot", [cluster, index]) => self.slot(parse_number(cluster)?, parse_number(index)?),
+            ("head", rest) => self.head_tail(&parse_args(rest, &["-c", "-n"], &[])?, false),
+            ("tail", rest) => self.head_tail(&parse_args(rest, &["-c", "-n"], &[])?, true),
+            ("hexdump", rest) => self.hexdump(&parse_args(rest, &["-s", "-n"], &["-C"])?),
+            ("tree", rest) => self.tree(&parse_args(rest, &["-L"], &[])?),
+            ("du", rest) => self.du(&parse_args(rest, &["-d"], &[])?),
+            ("find", rest) => self.find(&parse_args(
+                rest,
+                &["-name", "-type", "-minsize", "-maxsize", "-newer", "-older", "-maxdepth"],
+                &["-case"],
+            )?),
+            _ => Err(format!("{}: invalid command\n{}", name, USAGE)),
+        }
+    }
+
+    fn ls(&self, path: Option<&str>) -> Result<(), String> {
+        let entries = self.fs.list(path, self.cwd).map_err(|e| report(&e))?;
+
+        for entry in entries {
+            match entry.entry_type {
+                EntryType::Directory => println!("{:>10}  {}/", "", entry.name),
+                EntryType::File => println!("{:>10}  {}", entry.size, entry.name),
+            }
+        }
+        Ok(())
+    }
+
+    fn cat(&self, path: &str) -> Result<(), String> {
+        let data = self.fs.read_file(path, self.cwd).map_err(|e| report(&e))?;
+        io::stdout().write_all(&data).map_err(|e| e.to_string())
+    }
+
+    fn cd(&mut self, path: &str) -> Result<(), String> {
+        let target = normalize(&self.cwd_path, path);
+        self.cwd = self.fs.change_dir(&target, self.cwd).map_err(|e| report(&e))?;
+        self.cwd_path = target;
+        Ok(())
+    }
+
+    fn head_tail(&self, args: &Args, tail: bool) -> Result<(), String> {
+        let path = normalize(&self.cwd_path, args.file()?);
+        let count = match (args.size("-c")?, args.number("-n")?) {
+            (Some(bytes), _) => Count::Bytes(bytes),
+            (None, lines) => Count::Lines(lines.unwrap_or(10) as u64),
+        };
+
+        let data = if tail {
+            self.fs.tail(&path, self.cwd, count)
+        } else {
+            self.fs.head(&path, self.cwd, count)
+        }
+        .map_err(|e| report(&e))?;
+
+        io::stdout().write_all(&data).map_err(|e| e.to_string())
+    }
+
+    /// `hexdump -C` ; `-C` est accepté pour l’habitude, c’est le seul format.
+    fn hexdump(&self, args: &Args) -> Result<(), String> {
+        let path = normalize(&self.cwd_path, args.file()?);
+        let offset = args.size("-s")?.unwrap_or(0);
+        let text = self
+            .fs
+            .hexdump(&path, self.cwd, offset, args.size("-n")?)
+            .map_err(|e| report(&e))?;
+
+        print!("{}", text);
+        Ok(())
+    }
+
+    fn extract(&self, args: &Args) -> Result<(), String> {
+        let [source, dest] = args.positional.as_slice() else {
+            return Err(String::from("usage: extract <path> <host directory>"));
+        };
+        let source = normalize(&self.cwd_path, source);
+        let options = ExtractOptions {
+            preserve_times: !args.has("--no-times"),
+        };
+
+        let summary = self
+            .fs
+            .extract(&source, self.cwd, Path::new(dest), options)
+            .map_err(|e| report(&e))?;
+
+        for error in &summary.errors {
+            eprintln!("extract: {}", report(error));
+        }
+        println!(
+            "{} files, {} directories, {} bytes",
+            summary.files, summary.directories, summary.bytes
+        );
+
+        match summary.errors.len() {
+            0 => Ok(()),
+            n => Err(format!("extract: {} entries could not be extracted", n)),
+        }
+    }
+
+    /// Applique un patch d’overlay à l’image.
+    fn apply(&self, patch: &str) -> Result<(), String> {
+        let bytes = std::fs::read(patch).map_err(|e| format!("{}: {}", patch, e))?;
+        let overlay = OverlayDevice::new(self.fs.device());
+        overlay.load_patch(&bytes).map_err(|e| format!("{}: {}", patch, e))?;
+
+        let sectors = overlay.dirty_sectors();
+        overlay.commit().map_err(|e| format!("{}: {}", self.image, e))?;
+        println!("{} sectors written", sectors);
+        Ok(())
+    }
+
+    fn import(&self, args: &Args) -> Result<(), String> {
+        let [source, dest] = args.positional.as_slice() else {
+            return Err(String::from("usage: import <host path> <directory>"));
+        };
+        let dest = normalize(&self.cwd_path, dest);
+        let options = ImportOptions {
+            preserve_times: !args.has("--no-times"),
+        };
+
+        let summary = self
+            .fs
+            .import(Path::new(source), &dest, self.cwd, options)
+            .map_err(|e| report(&e))?;
+
+        for error in &summary.errors {
+            eprintln!("import: {}", report(error));
+        }
+        println!(
+            "{} files, {} directories, {} bytes",
+            summary.files, summary.directories, summary.bytes
+        );
+
+        match summary.errors.len() {
+            0 => Ok(()),
+            n => Err(format!("import: {} entries could not be imported", n)),
+        }
+    }
+
+    fn boot_compare(&self) -> Result<(), String> {
+        let diffs = self.fs.compare_boot().map_err(|e| report(&e))?;
+
+        if diffs.is_empty() {
+            println!("boot sector and backup are identical");
+        }
+        for diff in diffs {
+            println!("{:#05x} {}", diff.offset, diff);
+        }
+        Ok(())
+    }
+
+    fn boot_fix(&self, from: BootSource) -> Result<(), String> {
+        self.fs.restore_boot(from).map_err(|e| report(&e))?;
+
+        let target = match from {
+            BootSource::Primary => BootSource::Backup,
+            BootSource::Backup => BootSource::Primary,
+        };
+        println!("{} restored from {}", target, from);
+        Ok(())
+    }
+
+    fn sector(&self, lba: u64) -> Result<(), String> {
+        let data = self.fs.inspect().sector(lba).map_err(|e| report(&e))?;
+
+        print!("{}", hexdump(&data, lba * self.fs.boot().bytes_per_sector as u64));
+        Ok(())
+    }
+
+    fn cluster(&self, cluster: u32) -> Result<(), String> {
+        let inspect = self.fs.inspect();
+        let data = inspect.cluster(cluster).map_err(|e| report(&e))?;
+        let offset = inspect.cluster_offset(cluster).map_err(|e| report(&e))?;
+
+        print!("{}", hexdump(&data, offset));
+        Ok(())
+    }
+
+    fn fat_entry(&self, copy: u8, cluster: u32) -> Result<(), String> {
+        let entry = self.fs.inspect().fat_entry(copy, cluster).map_err(|e| report(&e))?;
+
+        println!("{}", entry);
+        Ok(())
+    }
+
+    fn fat_diff(&self) -> Result<(), String> {
+        let diffs = self.fs.fat_diff().map_err(|e| report(&e))?;
+
+        for diff in &diffs {
+            let values: Vec<String> = diff.values.iter().map(|v| format!("{:#010x}", v)).collect();
+            println!("{:>10}  {}", diff.cluster, values.join(" "));
+        }
+        println!("{} differing entries", diffs.len());
+        Ok(())
+    }
+
+    fn fat_sync(&self, from: u8) -> Result<(), String> {
+        self.fs.resync_fat(from).map_err(|e| report(&e))?;
+
+        println!("FAT copy {} written to the other copies", from);
+        Ok(())
+    }
+
+    fn chain(&self, start: u32) -> Result<(), String> {
+        let chain = self.fs.inspect().chain(start).map_err(|e| report(&e))?;
+
+        println!("{}", chain);
+        Ok(())
+    }
+
+    fn slot(&self, cluster: u32, index: u32) -> Result<(), String> {
+        let (slot, info) = self.fs.inspect().slot(cluster, index).map_err(|e| report(&e))?;
+
+        print!("{}", hexdump(&slot.raw, 0));
+        println!("{}", info);
+        Ok(())
+    }
+
+    fn stat(&self, path: &str) -> Result<(), String> {
+        let path = normalize(&self.cwd_path, path);
+        let stat = self.fs.stat(&path, self.cwd).map_err(|e| report(&e))?;
+
+        println!("{}", stat);
+        Ok(())
+    }
+
+    fn df(&self, mode: FreeCount) -> Result<(), String> {
+        let stats = self.fs.statfs(mode).map_err(|e| report(&e))?;
+        let label = self.fs.label().map_err(|e| report(&e))?;
+
+        let used_percent = if stats.total_bytes() == 0 {
+            0
+        } else {
+            (stats.used_bytes() * 100).div_ceil(stats.total_bytes())
+        };
+
+        println!("{:<20} {:>8} {:>8} {:>8} {:>4}  Label", "Filesystem", "Size", "Used", "Avail", "Use%");
+        println!(
+            "{:<20} {:>8} {:>8} {:>8} {:>3}%  {}",
+            self.image,
+            human(stats.total_bytes()),
+            human(stats.used_bytes()),
+            human(stats.free_bytes()),
+            used_percent,
+            label.unwrap_or_default()
+        );
+
+        let bad = stats
+            .bad_clusters
+            .map_or_else(|| String::from("?"), |b| b.to_string());
+        println!(
+            "clusters: {} total, {} free, {} bad, {} bytes each",
+            stats.total_clusters, stats.free_clusters, bad, stats.cluster_size
+        );
+        Ok(())
+    }
+
+    fn tree(&self, args: &Args) -> Result<(), String> {
+        let path = normalize(&self.cwd_path, args.path()?);
+        let text = self
+            .fs
+            .tree(&path, self.cwd, args.number("-L")?)
+            .map_err(|e| report(&e))?;
+
+        println!("{}", text);
+        Ok(())
+    }
+
+    fn du(&self, args: &Args) -> Result<(), String> {
+        let path = normalize(&self.cwd_path, args.path()?);
+        let du = self
+            .fs
+            .du(&path, self.cwd, args.number("-d")?)
+            .map_err(|e| report(&e))?;
+
+        println!("{:>8} {:>8}  Path", "Alloc", "Size");
+        for entry in &du.entries {
+            println!("{:>8} {:>8}  {}", human(entry.allocated), human(entry.logical), entry.path);
+        }
+        for error in &du.errors {
+            eprintln!("du: {}", report(error));
+        }
+        Ok(())
+    }
+
+    fn find(&self, args: &Args) -> Result<(), String> {
+        let path = normalize(&self.cwd_path, args.path()?);
+        let entry_type = match args.value("-type") {
+            None => None,
+            Some("f") => Some(EntryType::File),
+            Some("d") => Some(EntryType::Directory),
+            Some(other) => return Err(format!("-type: invalid type {:?}", other)),
+        };
+
+        let options = FindOptions {
+            name: args.value("-name").map(String::from),
+            case_sensitive: args.has("-case"),
+            entry_type,
+            min_size: args.size("-minsize")?,
+            max_size: args.size("-maxsize")?,
+            modified_after: args.date("-newer")?,
+            modified_before: args.date("-older")?,
+            max_depth: args.number("-maxdepth")?,
+        };
+
+        self.fs
+            .find(&path, self.cwd, options, |item| match item {
+                Ok(found) => println!("{}", found.path),
+                Err(e) => eprintln!("find: {}", report(&e)),
+            })
+            .map_err(|e| report(&e))
+    }
+}
+
+/// Arguments d’une commande : positionnels et options `-x [valeur]`.
+struct Args<'s> {
+    positional: Vec<&'s str>,
+    options: Vec<(&'s str, Option<&'s str>)>,
+}
+
+impl<'s> Args<'s> {
+    /// Chemin positionnel unique, `.` par défaut.
+    fn path(&self) -> Result<&'s str, String> {
+        match self.positional.as_slice() {
+            [] => Ok("."),
+            [path] => Ok(path),
+            _ => Err(String::from("too many arguments")),
+        }
+    }
+
+    /// Chemin positionnel unique et obligatoire.
+    fn file(&self) -> Result<&'s str, String> {
+        match self.positional.as_slice() {
+            [] => Err(String::from("missing file operand")),
+            [path] => Ok(path),
+            _ => Err(String::from("too many arguments")),
+        }
+    }
+
+    fn value(&self, flag: &str) -> Option<&'s str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(f, _)| *f == flag)
+            .and_then(|(_, v)| *v)
+    }
+
+    fn has(&self, flag: &str) -> bool {
+        self.options.iter().any(|(f, _)| *f == flag)
+    }
+
+    fn number(&self, flag: &str) -> Result<Option<usize>, String> {
+        self.value(flag)
+            .map(|v| v.parse().map_err(|_| format!("{}: invalid number {:?}", flag, v)))
+            .transpose()
+    }
+
+    /// Taille avec suffixe optionnel `K`, `M` ou `G` (puissances de 1024).
+    fn size(&self, flag: &str) -> Result<Option<u64>, String> {
+        self.value(flag)
+            .map(|v| parse_size(v).ok_or_else(|| format!("{}: invalid size {:?}", flag, v)))
+            .transpose()
+    }
+
+    fn date(&self, flag: &str) -> Result<Option<DateTime>, String> {
+        self.value(flag)
+            .map(|v| DateTime::parse(v).ok_or_else(|| format!("{}: invalid date {:?}", flag, v)))
+            .transpose()
+    }
+}
+
+/// Sépare options et arguments ; les options de `with_value` prennent un
+/// argument, celles de `switches` non, toute autre option est refusée.
+fn parse_args<'s>(
+    args: &[&'s str],
+    with_value: &[&str],
+    switches: &[&str],
+) -> Result<Args<'s>, String> {
+    let mut parsed = Args {
+        positional: Vec::new(),
+        options: Vec::new(),
+    };
+
+    let mut iter = args.iter();
+    while let Some(&arg) = iter.next() {
+        if arg.starts_with('-') && arg.len() > 1 {
+            let value = if with_value.contains(&arg) {
+                Some(*iter.next().ok_or_else(|| format!("{}: missing value", arg))?)
+            } else if switches.contains(&arg) {
+                None
+            } else {
+                return Err(format!("{}: unknown option", arg));
+            };
+            parsed.options.push((arg, value));
+        } else {
+            parsed.positional.push(arg);
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Nombre décimal ou hexadécimal (`0x…`).
+fn parse_number<T: TryFrom<u64>>(text: &str) -> Result<T, String> {
+    let value = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+
+    value
+        .ok()
+        .and_then(|v| T::try_from(v).ok())
+        .ok_or_else(|| format!("invalid number {:?}", text))
+}
+
+fn parse_size(text: &str) -> Option<u64> {
+    let (digits, shift) = match text.char_indices().last()? {
+        (i, 'K' | 'k') => (&text[..i], 10),
+        (i, 'M' | 'm') => (&text[..i], 20),
+        (i, 'G' | 'g') => (&text[..i], 30),
+        _ => (text, 0),
+    };
+
+    digits.parse::<u64>().ok()?.checked_mul(1 << shift)
+}
+
+/// Chemin absolu obtenu en appliquant `path` depuis `cwd` (gère `.` et `..`).
+fn normalize(cwd: &str, path: &str) -> String {
+    let mut parts: Vec<&str> = if path.starts_with('/') {
+        Vec::new()
+    } else {
+        cwd.split('/').filter(|p| !p.is_empty()).collect()
+    };
+
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            p => parts.push(p),
+        }
+    }
+
+    format!("/{}", parts.join("/"))
+}
+
+/// Message d’erreur complet, causes comprises.
+fn report(err: &dyn Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Taille lisible en puissances de 1024 (`4.0M`).
+fn human(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["", "K", "M", "G", "T"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{}", bytes)
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
 }
@@ -0,0 +1,470 @@
+//! Fabrication d’images FAT32 pour les tests (feature `testing`).
+//!
+//! Contrairement à `ImageBuilder`, qui passe par le formatage et
+//! l’allocation normale, `ImageFixture` place chaque octet là où on le lui
+//! demande : numéros de clusters choisis, chaînes fragmentées ou qui
+//! bouclent, valeurs FAT invalides, slots supprimés ou noms longs incohérents.
+//! C’est l’outil des cas limites et des volumes corrompus.
+//!
+//! ```
+//! use rust_project::testing::{dir_entry, file_entry, long_name, ImageFixture};
+//!
+//! let mut root = vec![dir_entry("DIR", 3)];
+//! root.extend(long_name("Notes de réunion.txt", file_entry("NOTESD~1.TXT", 5, 1000)));
+//! let device = ImageFixture::new(8)
+//!     .directory(&[2], &root)
+//!     .directory(&[3], &[])
+//!     .file(&[5, 7], &[b'x'; 1000]) // chaîne fragmentée
+//!     .cycle(&[4, 6])               // boucle perdue
+//!     .device();
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::device::block_device::{BlockDevice, RamBlockDevice};
+use crate::fs::boot_sector::BootSector;
+use crate::fs::check::{CheckOptions, Checker};
+use crate::fs::directory::{ATTR_ARCHIVE, ATTR_DIRECTORY, ATTR_VOLUME_ID};
+use crate::fs::fat::{FAT_EOC, FAT_FREE};
+use crate::fs::filesystem::{FileSystem, MountOptions};
+use crate::fs::format::{format, FormatOptions};
+use crate::fs::fsinfo::FsInfo;
+use crate::fs::lfn;
+use crate::fs::statfs::FreeCount;
+use crate::fs::timestamp::DateTime;
+use crate::fs::view::Count;
+use crate::fs::walk::{WalkOptions, WalkOrder};
+
+/// Premier octet d’un slot supprimé.
+const DELETED: u8 = 0xE5;
+
+/// Nombre d’entrées examinées par `explore` après le parcours.
+const EXPLORE_ENTRIES: usize = 64;
+
+/// Taille des volumes de `formatted` : le plus petit FAT32 à clusters de
+/// 512 octets, avec un peu de marge.
+pub const FORMATTED_SIZE: u64 = 33 * 1024 * 1024;
+
+/// Volume en mémoire de `FORMATTED_SIZE` octets, formaté avec `options`.
+///
+/// # Panics
+///
+/// Si le formatage échoue.
+pub fn formatted(options: &FormatOptions) -> RamBlockDevice {
+    let device = RamBlockDevice::zeroed(FORMATTED_SIZE as usize);
+    format(&device, FORMATTED_SIZE, options).expect("cannot format test volume");
+    device
+}
+
+/// Image FAT32 décrite cluster par cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageFixture {
+    bytes_per_sector: u16,
+    sectors_per_cluster: u8,
+    reserved_sectors: u16,
+    fat_count: u8,
+    ext_flags: u16,
+    cluster_count: u32,
+    root_cluster: u32,
+    fs_info: bool,
+    /// Entrées FAT 0..cluster_count + 2.
+    fat: Vec<u32>,
+    /// Contenu des clusters écrits (le reste est nul).
+    data: BTreeMap<u32, Vec<u8>>,
+}
+
+impl ImageFixture {
+    /// Volume de `cluster_count` clusters de 512 octets (2 à
+    /// `cluster_count + 1`), avec 2 FAT, un FSInfo et une racine vide au
+    /// cluster 2.
+    pub fn new(cluster_count: u32) -> Self {
+        let mut fat = vec![FAT_FREE; cluster_count as usize + 2];
+        fat[0] = 0x0FFF_FFF8;
+        fat[1] = FAT_EOC;
+        fat[2] = FAT_EOC;
+
+        Self {
+            bytes_per_sector: 512,
+            sectors_per_cluster: 1,
+            reserved_sectors: 8,
+            fat_count: 2,
+            ext_flags: 0,
+            cluster_count,
+            root_cluster: 2,
+            fs_info: true,
+            fat,
+            data: BTreeMap::new(),
+        }
+    }
+
+    /// Petit volume sain qui contient un peu de tout : étiquette, slot
+    /// supprimé, noms longs, sous-répertoires et fichier fragmenté. Sert de
+    /// point de départ aux mutations du fuzzing.
+    pub fn sample() -> Self {
+        let mut root = vec![volume_label("SAMPLE")];
+        root.push(deleted(file_entry("OLD.TXT", 0, 0)));
+        root.extend(long_name("Read me first.txt", file_entry("README~1.TXT", 4, 30)));
+        root.push(dir_entry("DOCS", 3));
+        root.push(file_entry("EMPTY", 0, 0));
+
+        let mut docs = dot_entries(3, 0).to_vec();
+        docs.extend(long_name("notes", file_entry("NOTES", 5, 1100)));
+        docs.push(dir_entry("DEEP", 8));
+
+        let mut deep = dot_entries(8, 3).to_vec();
+        deep.push(file_entry("A.BIN", 10, 512));
+
+        let notes: Vec<u8> = (0..1100u32).map(|i| b"line\n"[i as usize % 5]).collect();
+        Self::new(16)
+            .directory(&[2], &root)
+            .directory(&[3], &docs)
+            .directory(&[8], &deep)
+            .file(&[4], b"FAT32 sample volume for tests\n")
+            .file(&[5, 9, 6], &notes)
+            .file(&[10], &[0xA5; 512])
+    }
+
+    pub fn sectors_per_cluster(mut self, sectors: u8) -> Self {
+        self.sectors_per_cluster = sectors;
+        self
+    }
+
+    pub fn fat_count(mut self, count: u8) -> Self {
+        self.fat_count = count;
+        self
+    }
+
+    /// Désactive le miroir des FAT : seule la copie `copy` fait foi.
+    pub fn active_fat(mut self, copy: u8) -> Self {
+        self.ext_flags = 0x0080 | copy as u16;
+        self
+    }
+
+    /// Secteurs réservés ; en dessous de 2, le FSInfo est omis.
+    pub fn reserved_sectors(mut self, sectors: u16) -> Self {
+        self.reserved_sectors = sectors;
+        self
+    }
+
+    /// Sans secteur FSInfo (champ du BPB à 0).
+    pub fn without_fs_info(mut self) -> Self {
+        self.fs_info = false;
+        self
+    }
+
+    /// Place la racine sur un autre cluster (l’ancien reste tel quel).
+    pub fn root_cluster(mut self, cluster: u32) -> Self {
+        self.root_cluster = cluster;
+        self
+    }
+
+    /// Écrit une valeur FAT brute, quelle qu’elle soit.
+    pub fn fat_entry(mut self, cluster: u32, value: u32) -> Self {
+        self.fat[cluster as usize] = value;
+        self
+    }
+
+    /// Chaîne les clusters dans l’ordre donné, le dernier en fin de chaîne.
+    pub fn chain(mut self, clusters: &[u32]) -> Self {
+        for pair in clusters.windows(2) {
+            self.fat[pair[0] as usize] = pair[1];
+        }
+        if let Some(&last) = clusters.last() {
+            self.fat[last as usize] = FAT_EOC;
+        }
+        self
+    }
+
+    /// Chaîne les clusters dans l’ordre donné et reboucle sur le premier.
+    pub fn cycle(mut self, clusters: &[u32]) -> Self {
+        for (i, &cluster) in clusters.iter().enumerate() {
+            self.fat[cluster as usize] = clusters[(i + 1) % clusters.len()];
+        }
+        self
+    }
+
+    /// Écrit des octets bruts dans un cluster, à partir de `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Si l’écriture dépasse la fin du cluster.
+    pub fn write(mut self, cluster: u32, offset: usize, bytes: &[u8]) -> Self {
+        let size = self.cluster_size();
+        assert!(offset + bytes.len() <= size, "write past the end of cluster {}", cluster);
+
+        let data = self.data.entry(cluster).or_insert_with(|| vec![0u8; size]);
+        data.resize(size, 0);
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        self
+    }
+
+    /// Chaîne `clusters` et y répartit `contents`.
+    pub fn file(mut self, clusters: &[u32], contents: &[u8]) -> Self {
+        self = self.chain(clusters);
+        let size = self.cluster_size();
+        for (&cluster, part) in clusters.iter().zip(contents.chunks(size)) {
+            self = self.write(cluster, 0, part);
+        }
+        self
+    }
+
+    /// Chaîne `clusters` et y écrit les slots à la suite ; le reste des
+    /// clusters est remis à zéro (fin de répertoire).
+    pub fn directory(mut self, clusters: &[u32], slots: &[[u8; 32]]) -> Self {
+        self = self.chain(clusters);
+        let per_cluster = self.cluster_size() / 32;
+        for &cluster in clusters {
+            self = self.write(cluster, 0, &vec![0u8; per_cluster * 32]);
+        }
+        for (i, slot) in slots.iter().enumerate() {
+            let cluster = clusters[i / per_cluster];
+            self = self.write(cluster, (i % per_cluster) * 32, slot);
+        }
+        self
+    }
+
+    pub fn cluster_size(&self) -> usize {
+        self.bytes_per_sector as usize * self.sectors_per_cluster as usize
+    }
+
+    /// Boot Sector correspondant à la géométrie de l’image.
+    pub fn boot_sector(&self) -> BootSector {
+        let entries_per_sector = self.bytes_per_sector as u32 / 4;
+        let sectors_per_fat = (self.cluster_count + 2).div_ceil(entries_per_sector);
+        let data_sectors = self.cluster_count * self.sectors_per_cluster as u32;
+
+        BootSector {
+            bytes_per_sector: self.bytes_per_sector,
+            sectors_per_cluster: self.sectors_per_cluster,
+            reserved_sectors: self.reserved_sectors,
+            fat_count: self.fat_count,
+            sectors_per_fat,
+            ext_flags: self.ext_flags,
+            root_cluster: self.root_cluster,
+            total_sectors: self.reserved_sectors as u32
+                + self.fat_count as u32 * sectors_per_fat
+                + data_sectors,
+            fs_info_sector: if self.has_fs_info() { 1 } else { 0 },
+        }
+    }
+
+    /// Octets de l’image complète.
+    pub fn build(&self) -> Vec<u8> {
+        let boot = self.boot_sector();
+        let bps = self.bytes_per_sector as usize;
+        let mut image = vec![0u8; boot.total_sectors as usize * bps];
+
+        let bpb = self.encode_boot_sector(&boot);
+        image[..bps].copy_from_slice(&bpb);
+        if self.reserved_sectors > 7 {
+            image[6 * bps..7 * bps].copy_from_slice(&bpb);
+        }
+
+        if self.has_fs_info() {
+            let info = FsInfo {
+                free_count: self.fat[2..].iter().filter(|&&v| v == FAT_FREE).count() as u32,
+                next_free: 2,
+            };
+            image[bps..bps + 512].copy_from_slice(&info.encode());
+        }
+
+        for copy in 0..self.fat_count {
+            let start = boot.fat_offset(copy) as usize;
+            for (i, value) in self.fat.iter().enumerate() {
+                image[start + i * 4..start + i * 4 + 4].copy_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        let data_start = boot.data_start_sector() as usize * bps;
+        let size = self.cluster_size();
+        for (&cluster, bytes) in &self.data {
+            if (2..self.cluster_count + 2).contains(&cluster) {
+                let at = data_start + (cluster as usize - 2) * size;
+                image[at..at + bytes.len()].copy_from_slice(bytes);
+            }
+        }
+
+        image
+    }
+
+    /// Image chargée dans un périphérique en mémoire.
+    pub fn device(&self) -> RamBlockDevice {
+        RamBlockDevice::new(self.build())
+    }
+
+    fn has_fs_info(&self) -> bool {
+        self.fs_info && self.reserved_sectors >= 2
+    }
+
+    fn encode_boot_sector(&self, boot: &BootSector) -> Vec<u8> {
+        let mut bs = vec![0u8; self.bytes_per_sector as usize];
+        bs[0..3].copy_from_slice(&[0xEB, 0x58, 0x90]);
+        bs[3..11].copy_from_slice(b"MSWIN4.1");
+        bs[11..13].copy_from_slice(&boot.bytes_per_sector.to_le_bytes());
+        bs[13] = boot.sectors_per_cluster;
+        bs[14..16].copy_from_slice(&boot.reserved_sectors.to_le_bytes());
+        bs[16] = boot.fat_count;
+        bs[21] = 0xF8;
+        bs[32..36].copy_from_slice(&boot.total_sectors.to_le_bytes());
+        bs[36..40].copy_from_slice(&boot.sectors_per_fat.to_le_bytes());
+        bs[40..42].copy_from_slice(&boot.ext_flags.to_le_bytes());
+        bs[44..48].copy_from_slice(&boot.root_cluster.to_le_bytes());
+        bs[48..50].copy_from_slice(&boot.fs_info_sector.to_le_bytes());
+        bs[50..52].copy_from_slice(&(if self.reserved_sectors > 7 { 6u16 } else { 0 }).to_le_bytes());
+        bs[66] = 0x29;
+        bs[71..82].copy_from_slice(b"NO NAME    ");
+        bs[82..90].copy_from_slice(b"FAT32   ");
+        bs[510] = 0x55;
+        bs[511] = 0xAA;
+        bs
+    }
+}
+
+/// Slot 8.3 brut ; `name` est écrit tel quel (`NOM.EXT`, sans contrôle,
+/// pour pouvoir fabriquer des noms invalides).
+pub fn short_entry(name: &str, attributes: u8, start_cluster: u32, size: u32) -> [u8; 32] {
+    let (base, ext) = match name {
+        "." | ".." => (name, ""),
+        _ => name.rsplit_once('.').unwrap_or((name, "")),
+    };
+
+    let mut slot = [0u8; 32];
+    slot[0..11].fill(b' ');
+    for (dst, src) in slot[0..8].iter_mut().zip(base.bytes()) {
+        *dst = src;
+    }
+    for (dst, src) in slot[8..11].iter_mut().zip(ext.bytes()) {
+        *dst = src;
+    }
+    slot[11] = attributes;
+    slot[20..22].copy_from_slice(&((start_cluster >> 16) as u16).to_le_bytes());
+    slot[26..28].copy_from_slice(&(start_cluster as u16).to_le_bytes());
+    slot[28..32].copy_from_slice(&size.to_le_bytes());
+    slot
+}
+
+/// Fichier ordinaire (attribut archive).
+pub fn file_entry(name: &str, start_cluster: u32, size: u32) -> [u8; 32] {
+    short_entry(name, ATTR_ARCHIVE, start_cluster, size)
+}
+
+pub fn dir_entry(name: &str, start_cluster: u32) -> [u8; 32] {
+    short_entry(name, ATTR_DIRECTORY, start_cluster, 0)
+}
+
+/// Entrées `.` et `..` d’un sous-répertoire (`parent` vaut 0 pour la racine).
+pub fn dot_entries(cluster: u32, parent: u32) -> [[u8; 32]; 2] {
+    [dir_entry(".", cluster), dir_entry("..", parent)]
+}
+
+/// Étiquette de volume dans la racine.
+pub fn volume_label(label: &str) -> [u8; 32] {
+    let mut slot = short_entry("", ATTR_VOLUME_ID, 0, 0);
+    for (dst, src) in slot[0..11].iter_mut().zip(label.bytes()) {
+        *dst = src;
+    }
+    slot
+}
+
+/// Fragments LFN de `name` suivis de l’entrée 8.3 `short`, dans l’ordre du
+/// disque.
+///
+/// # Panics
+///
+/// Si `name` n’est pas un nom long valide.
+pub fn long_name(name: &str, short: [u8; 32]) -> Vec<[u8; 32]> {
+    let mut raw_name = [0u8; 11];
+    raw_name.copy_from_slice(&short[..11]);
+
+    let mut slots = lfn::encode(name, &raw_name).expect("invalid long name");
+    slots.push(short);
+    slots
+}
+
+/// Comme `long_name`, sans contrôle du nom : écrit sur le disque un nom
+/// long que le crate refuserait de créer (`a/b` par exemple).
+pub fn raw_long_name(name: &str, short: [u8; 32]) -> Vec<[u8; 32]> {
+    let mut raw_name = [0u8; 11];
+    raw_name.copy_from_slice(&short[..11]);
+
+    let mut slots = lfn::encode_unchecked(name, &raw_name);
+    slots.push(short);
+    slots
+}
+
+/// Le même slot, marqué supprimé.
+pub fn deleted(mut slot: [u8; 32]) -> [u8; 32] {
+    slot[0] = DELETED;
+    slot
+}
+
+/// Le même slot, avec la date de création `when`.
+pub fn created(mut slot: [u8; 32], when: DateTime) -> [u8; 32] {
+    let (date, time) = when.to_fat();
+    slot[13] = 0;
+    slot[14..16].copy_from_slice(&time.to_le_bytes());
+    slot[16..18].copy_from_slice(&date.to_le_bytes());
+    slot
+}
+
+/// Le même slot, avec la date de modification `when`.
+pub fn modified(mut slot: [u8; 32], when: DateTime) -> [u8; 32] {
+    let (date, time) = when.to_fat();
+    slot[22..24].copy_from_slice(&time.to_le_bytes());
+    slot[24..26].copy_from_slice(&date.to_le_bytes());
+    slot
+}
+
+/// Monte une image quelconque, même tronquée (`salvage`), et y exerce les
+/// opérations en lecture seule : étiquette, occupation, `ls`, `cd`, `cat`,
+/// `stat`, parcours et vérification.
+///
+/// Les erreurs sont attendues et ignorées ; une panique, une boucle sans
+/// fin ou une allocation démesurée est en revanche un défaut. C’est la
+/// cible commune des harnais de `fuzz/` et des tests de régression.
+pub fn explore<D: BlockDevice>(device: &D) {
+    let salvage = MountOptions { salvage: true, ..MountOptions::default() };
+    let Ok(fs) = FileSystem::mount_with(device, &salvage) else {
+        return;
+    };
+    let root = fs.root_cluster();
+
+    let _ = fs.label();
+    let _ = fs.statfs(FreeCount::Hint);
+    let _ = fs.list(None, root);
+    let _ = fs.tree("/", root, Some(4));
+    let _ = fs.du("/", root, None);
+
+    let mut entries = Vec::new();
+    let breadth = WalkOptions {
+        order: WalkOrder::BreadthFirst,
+        max_depth: None,
+    };
+    let _ = fs.walk("/", root, breadth, |entry| {
+        if let Ok(entry) = entry
+            && entries.len() < EXPLORE_ENTRIES
+        {
+            entries.push(entry);
+        }
+    });
+
+    for entry in &entries {
+        let path = entry.path.as_str();
+        let _ = fs.stat(path, root);
+        if entry.entry.entry_type.is_dir() {
+            if let Ok(cluster) = fs.change_dir(path, root) {
+                let _ = fs.list(Some(".."), cluster);
+                let _ = fs.change_dir("../..", cluster);
+            }
+        } else {
+            let _ = fs.read_file(path, root);
+            let _ = fs.head(path, root, Count::Lines(3));
+            let _ = fs.tail(path, root, Count::Bytes(100));
+            let _ = fs.hexdump(path, root, 1000, Some(64));
+        }
+    }
+
+    let _ = Checker::new(device, fs.boot()).check(&CheckOptions::default());
+}
@@ -0,0 +1,186 @@
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use rust_project::device::async_device::{AsyncBlockDevice, Blocking};
+use rust_project::device::block_device::{BlockDevice, BlockDeviceResult, MemoryBlockDevice, RamBlockDevice};
+use rust_project::fs::async_fs::{read_boot, read_boot_sector, AsyncFileSystem};
+use rust_project::fs::backup::{self, BootSource};
+use rust_project::fs::boot_sector::BootSector;
+use rust_project::fs::cat::CatError;
+use rust_project::fs::clusters::ClusterError;
+use rust_project::fs::error::FsError;
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::path::PathError;
+use rust_project::testing::{file_entry, ImageFixture};
+
+/// Exécuteur minimal : relance la future jusqu’à ce qu’elle aboutisse.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Rend la main une fois avant d’aboutir.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Périphérique mémoire dont chaque accès suspend la tâche une fois.
+struct AsyncRam {
+    data: RamBlockDevice,
+    reads: Cell<usize>,
+}
+
+impl AsyncRam {
+    fn sample() -> Self {
+        Self {
+            data: ImageFixture::sample().device(),
+            reads: Cell::new(0),
+        }
+    }
+}
+
+impl AsyncBlockDevice for AsyncRam {
+    async fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        YieldOnce(false).await;
+        self.reads.set(self.reads.get() + 1);
+        self.data.read_at(offset, buf)
+    }
+}
+
+#[test]
+fn async_reads_match_sync_reads() {
+    let device = AsyncRam::sample();
+    let sync = FileSystem::mount(&device.data).unwrap();
+    let root = sync.root_cluster();
+
+    block_on(async {
+        let fs = AsyncFileSystem::mount(&device).await.unwrap();
+        assert_eq!(fs.boot(), sync.boot());
+
+        assert_eq!(fs.list("/", root).await.unwrap(), sync.list(Some("/"), root).unwrap());
+        assert_eq!(fs.list("/DOCS/DEEP", root).await.unwrap(), sync.list(Some("/DOCS/DEEP"), root).unwrap());
+
+        for path in ["/Read me first.txt", "/DOCS/notes", "/DOCS/DEEP/A.BIN"] {
+            assert_eq!(fs.read_file(path, root).await.unwrap(), sync.read_file(path, root).unwrap(), "{}", path);
+        }
+    });
+    assert!(device.reads.get() > 0);
+}
+
+#[test]
+fn chain_walking_and_directory_iteration() {
+    let device = AsyncRam::sample();
+    let sync = FileSystem::mount(&device.data).unwrap();
+    let notes = sync.stat("/DOCS/notes", sync.root_cluster()).unwrap();
+
+    block_on(async {
+        let fs = AsyncFileSystem::mount(&device).await.unwrap();
+        assert_eq!(fs.chain(5).await.unwrap(), [5, 9, 6]);
+        assert_eq!(fs.chain(5).await.unwrap().len() as u64, notes.cluster_count());
+        assert_eq!(fs.next_cluster(6).await.unwrap(), None);
+
+        let docs = fs.read_dir(3).await.unwrap();
+        let names: Vec<&str> = docs.iter().map(|e| e.display_name()).collect();
+        assert!(names.contains(&"notes") && names.contains(&"DEEP"), "{:?}", names);
+    });
+}
+
+#[test]
+fn errors_match_sync_path() {
+    let device = AsyncRam::sample();
+    block_on(async {
+        let fs = AsyncFileSystem::mount(&device).await.unwrap();
+        let root = fs.root_cluster();
+
+        let err = fs.read_file("/NOPE.TXT", root).await.unwrap_err();
+        assert_eq!(err.kind(), &FsError::Cat(CatError::NotFound));
+        let err = fs.read_file("/DOCS", root).await.unwrap_err();
+        assert_eq!(err.kind(), &FsError::Cat(CatError::NotAFile));
+        let err = fs.list("/DOCS/notes", root).await.unwrap_err();
+        assert_eq!(err.kind(), &FsError::Path(PathError::NotADirectory));
+        assert_eq!(err.to_string(), "/DOCS/notes: not a directory");
+    });
+}
+
+#[test]
+fn looping_chain_is_rejected_without_reading_data() {
+    let device = AsyncRam {
+        data: ImageFixture::new(4096)
+            .directory(&[2], &[file_entry("LOOP.BIN", 3, 100)])
+            .cycle(&[3, 4])
+            .device(),
+        reads: Cell::new(0),
+    };
+
+    block_on(async {
+        let fs = AsyncFileSystem::mount(&device).await.unwrap();
+        assert_eq!(fs.chain(3).await, Err(ClusterError::ChainLoop));
+
+        let before = device.reads.get();
+        let err = fs.read_file("/LOOP.BIN", fs.root_cluster()).await.unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &FsError::Cat(CatError::Cluster { cluster: 3, source: ClusterError::ChainLoop })
+        );
+        // Racine : une entrée FAT et un cluster ; chaîne : deux entrées FAT.
+        assert_eq!(device.reads.get() - before, 4);
+    });
+}
+
+#[test]
+fn damaged_boot_sector_mounts_from_backup() {
+    let mut image = ImageFixture::sample().build();
+    image[510] = 0;
+    let device = Blocking(MemoryBlockDevice::new(&image));
+
+    block_on(async {
+        let fs = AsyncFileSystem::mount(&device).await.unwrap();
+        assert_eq!(fs.boot_source(), BootSource::Backup);
+        assert_eq!(fs.boot(), &ImageFixture::sample().boot_sector());
+        let data = fs.read_file("/Read me first.txt", fs.root_cluster()).await.unwrap();
+        assert_eq!(data, b"FAT32 sample volume for tests\n");
+    });
+
+    // Mêmes choix que le chemin synchrone, y compris quand `BPB_BkBootSec`
+    // désigne un secteur vide ou qu’aucune copie n’est utilisable.
+    let mut image = ImageFixture::sample().build();
+    image[16] = 0;
+    image[50..52].copy_from_slice(&2u16.to_le_bytes());
+    let mut without_backup = ImageFixture::sample().build();
+    without_backup[510] = 0;
+    without_backup[6 * 512..7 * 512].fill(0);
+    for (image, mounts) in [(image, true), (without_backup, false)] {
+        let device = Blocking(MemoryBlockDevice::new(&image));
+        let read = block_on(read_boot(&device));
+        assert_eq!(read.is_ok(), mounts);
+        assert_eq!(read, backup::read_boot(&device.0));
+    }
+}
+
+#[test]
+fn blocking_adapter_wraps_sync_devices() {
+    let device = Blocking(ImageFixture::sample().device());
+    let boot = block_on(read_boot_sector(&device)).unwrap();
+    assert_eq!(boot, BootSector::read(&device.0).unwrap());
+
+    let zeroed = Blocking(RamBlockDevice::zeroed(4096));
+    assert!(block_on(AsyncFileSystem::mount(&zeroed)).is_err());
+}
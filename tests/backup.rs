@@ -0,0 +1,116 @@
+use rust_project::device::block_device::{BlockDevice, MemoryBlockDevice, RamBlockDevice};
+use rust_project::fs::backup::{compare, read_boot, restore, BootSource};
+use rust_project::fs::boot_sector::{BootSector, BootSectorError};
+use rust_project::fs::error::FsError;
+use rust_project::fs::filesystem::{FileSystem, MountOptions};
+use rust_project::testing::ImageFixture;
+
+/// Copie de secours au secteur 6, comme l’écrit `ImageFixture`.
+const BACKUP: usize = 6 * 512;
+
+#[test]
+fn damaged_boot_sector_mounts_from_backup() {
+    let mut image = ImageFixture::sample().build();
+    image[510] = 0;
+    let device = MemoryBlockDevice::new(&image);
+
+    assert_eq!(BootSector::read(&device), Err(BootSectorError::InvalidSignature));
+
+    let fs = FileSystem::mount(&device).unwrap();
+    assert_eq!(fs.boot_source(), BootSource::Backup);
+    assert_eq!(fs.boot(), &ImageFixture::sample().boot_sector());
+    let data = fs.read_file("/Read me first.txt", fs.root_cluster()).unwrap();
+    assert_eq!(data, b"FAT32 sample volume for tests\n");
+}
+
+#[test]
+fn mount_reports_primary_error_without_backup() {
+    let mut image = ImageFixture::sample().build();
+    image[510] = 0;
+    image[BACKUP..BACKUP + 512].fill(0);
+    let device = MemoryBlockDevice::new(&image);
+
+    assert_eq!(read_boot(&device), Err(BootSectorError::InvalidSignature));
+    let err = FileSystem::mount(&device).err().unwrap();
+    assert_eq!(err.kind(), &FsError::BootSector(BootSectorError::InvalidSignature));
+
+    // Une géométrie incohérente dans l’original suffit aussi à basculer.
+    let mut image = ImageFixture::sample().build();
+    image[16] = 0;
+    let device = MemoryBlockDevice::new(&image);
+    assert_eq!(read_boot(&device).unwrap().1, BootSource::Backup);
+}
+
+#[test]
+fn compare_lists_differing_fields() {
+    let mut image = ImageFixture::sample().build();
+    assert!(compare(&MemoryBlockDevice::new(&image)).unwrap().is_empty());
+
+    image[13] = 2;
+    image[67..71].copy_from_slice(&0x1234_5678u32.to_le_bytes());
+    image[BACKUP + 100] = 0xCC;
+    let diffs = compare(&MemoryBlockDevice::new(&image)).unwrap();
+
+    let fields: Vec<&str> = diffs.iter().map(|d| d.field).collect();
+    assert_eq!(fields, ["sectors_per_cluster", "volume_id", "boot_code"]);
+    assert_eq!(diffs[0].offset, 13);
+    assert_eq!((diffs[0].primary.as_slice(), diffs[0].backup.as_slice()), (&[2u8][..], &[1u8][..]));
+    assert_eq!(diffs[0].to_string(), "sectors_per_cluster: primary 0x2, backup 0x1");
+    assert_eq!(diffs[2].to_string(), "boot_code: differs");
+}
+
+#[test]
+fn restore_repairs_either_copy() {
+    let sample = ImageFixture::sample();
+    let mut image = sample.build();
+    image[..512].fill(0);
+    let device = RamBlockDevice::new(image);
+
+    // L’original invalide ne peut pas servir de source.
+    assert_eq!(restore(&device, BootSource::Primary), Err(BootSectorError::InvalidSignature));
+
+    restore(&device, BootSource::Backup).unwrap();
+    assert_eq!(BootSector::read(&device).unwrap(), sample.boot_sector());
+    assert!(compare(&device).unwrap().is_empty());
+
+    device.write_at(BACKUP as u64, &[0u8; 512]).unwrap();
+    let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+    assert_eq!(fs.boot_source(), BootSource::Primary);
+    fs.restore_boot(BootSource::Primary).unwrap();
+    assert!(fs.compare_boot().unwrap().is_empty());
+
+    // Sur un périphérique en lecture seule, la réparation échoue.
+    let image = sample.build();
+    let read_only = MemoryBlockDevice::new(&image);
+    assert!(matches!(
+        restore(&read_only, BootSource::Primary),
+        Err(BootSectorError::Io(_))
+    ));
+}
+
+#[test]
+fn damaged_backup_field_falls_back_to_sector_six() {
+    // L’original se décode mais sa géométrie est fausse, et son champ
+    // `BPB_BkBootSec` désigne un secteur réservé vide.
+    let mut image = ImageFixture::sample().build();
+    image[16] = 0;
+    image[50..52].copy_from_slice(&2u16.to_le_bytes());
+    let device = MemoryBlockDevice::new(&image);
+
+    let (boot, source) = read_boot(&device).unwrap();
+    assert_eq!(source, BootSource::Backup);
+    assert_eq!(boot, ImageFixture::sample().boot_sector());
+}
+
+#[test]
+fn restore_refuses_to_write_outside_reserved_area() {
+    // Quatre secteurs réservés : pas de copie, et le secteur 6 est dans la FAT.
+    let fixture = ImageFixture::new(16).reserved_sectors(4);
+    let image = fixture.build();
+    let device = RamBlockDevice::new(image.clone());
+
+    assert_eq!(restore(&device, BootSource::Primary), Err(BootSectorError::NoBackup));
+    let mut after = vec![0u8; image.len()];
+    device.read_at(0, &mut after).unwrap();
+    assert_eq!(after, image);
+}
@@ -2,7 +2,9 @@ use rust_project::device::block_device::{
     BlockDevice,
     BlockDeviceError,
     MemoryBlockDevice,
+    RamBlockDevice,
 };
+use rust_project::device::file::FileBlockDevice;
 
 #[test]
 fn read_single_byte() {
@@ -58,3 +60,78 @@ fn read_with_offset_out_of_bounds() {
 
     assert_eq!(result, Err(BlockDeviceError::OutOfBounds));
 }
+
+#[test]
+fn memory_device_is_read_only() {
+    let data = [1, 2, 3];
+    let device = MemoryBlockDevice::new(&data);
+
+    assert_eq!(device.write_at(0, &[9]), Err(BlockDeviceError::ReadOnly));
+}
+
+#[test]
+fn ram_device_write_then_read() {
+    let device = RamBlockDevice::zeroed(4);
+    device.write_at(1, &[7, 8]).unwrap();
+
+    let mut buf = [0u8; 4];
+    device.read_at(0, &mut buf).unwrap();
+    assert_eq!(buf, [0, 7, 8, 0]);
+
+    assert_eq!(device.write_at(3, &[1, 2]), Err(BlockDeviceError::OutOfBounds));
+}
+
+#[test]
+fn file_device_round_trip() {
+    let path = std::env::temp_dir().join(format!("rust_project_dev_{}.img", std::process::id()));
+
+    let device = FileBlockDevice::create(&path, 1024).unwrap();
+    device.write_at(512, b"FAT").unwrap();
+    assert_eq!(device.write_at(1023, b"XY"), Err(BlockDeviceError::OutOfBounds));
+    drop(device);
+
+    let device = FileBlockDevice::open(&path).unwrap();
+    let mut buf = [0u8; 3];
+    device.read_at(512, &mut buf).unwrap();
+    assert_eq!(&buf, b"FAT");
+    assert_eq!(device.len(), 1024);
+    assert_eq!(device.write_at(0, b"X"), Err(BlockDeviceError::ReadOnly));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn vectored_read_fills_every_range() {
+    let data: Vec<u8> = (0..64).collect();
+    let device = MemoryBlockDevice::new(&data);
+
+    let (mut a, mut b) = ([0u8; 4], [0u8; 8]);
+    device.read_vectored_at(&mut [(10, &mut a[..]), (40, &mut b[..])]).unwrap();
+    assert_eq!(a, [10, 11, 12, 13]);
+    assert_eq!(b, [40, 41, 42, 43, 44, 45, 46, 47]);
+
+    let err = device.read_vectored_at(&mut [(0, &mut a[..]), (62, &mut b[..])]);
+    assert_eq!(err, Err(BlockDeviceError::OutOfBounds));
+}
+
+#[test]
+fn file_device_vectored_read() {
+    let path = std::env::temp_dir().join(format!("rust_project_dev_vec_{}.img", std::process::id()));
+    let device = FileBlockDevice::create(&path, 2048).unwrap();
+    device.write_at(0, &(0..=255).collect::<Vec<u8>>()).unwrap();
+    device.write_at(1024, b"far").unwrap();
+
+    let (mut a, mut b, mut c) = ([0u8; 2], [0u8; 2], [0u8; 3]);
+    device
+        .read_vectored_at(&mut [(4, &mut a[..]), (6, &mut b[..]), (1024, &mut c[..])])
+        .unwrap();
+    assert_eq!((a, b, &c), ([4, 5], [6, 7], b"far"));
+
+    // Aucune plage n’est lue si l’une sort de l’image.
+    let mut d = [0xEEu8; 4];
+    let err = device.read_vectored_at(&mut [(0, &mut d[..]), (2047, &mut c[..])]);
+    assert_eq!(err, Err(BlockDeviceError::OutOfBounds));
+    assert_eq!(d, [0xEE; 4]);
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,125 @@
+use std::cell::{Cell, RefCell};
+
+use rust_project::device::block_device::{BlockDevice, BlockDeviceError, BlockDeviceResult};
+use rust_project::device::blocks::{BlockAdapter, BlockStorage};
+use rust_project::fs::check::{CheckOptions, Checker};
+use rust_project::fs::create::EntryTimes;
+use rust_project::fs::filesystem::{FileSystem, MountOptions};
+use rust_project::fs::format::{format, FormatOptions};
+
+/// Carte SD simulée : n’accepte que des blocs entiers de 512 octets.
+struct SdCard {
+    data: RefCell<Vec<u8>>,
+    writable: bool,
+    reads: Cell<usize>,
+}
+
+impl SdCard {
+    fn new(blocks: usize) -> Self {
+        Self {
+            data: RefCell::new((0..blocks * 512).map(|i| (i % 251) as u8).collect()),
+            writable: true,
+            reads: Cell::new(0),
+        }
+    }
+
+    fn span(&self, first: u64, len: usize) -> BlockDeviceResult<std::ops::Range<usize>> {
+        assert_eq!(len % 512, 0, "partial block access");
+        let start = first as usize * 512;
+        if start + len > self.data.borrow().len() {
+            return Err(BlockDeviceError::OutOfBounds);
+        }
+        Ok(start..start + len)
+    }
+}
+
+impl BlockStorage for SdCard {
+    fn block_size(&self) -> usize {
+        512
+    }
+
+    fn num_blocks(&self) -> u64 {
+        self.data.borrow().len() as u64 / 512
+    }
+
+    fn read_blocks(&self, first: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        self.reads.set(self.reads.get() + 1);
+        let span = self.span(first, buf.len())?;
+        buf.copy_from_slice(&self.data.borrow()[span]);
+        Ok(())
+    }
+
+    fn write_blocks(&self, first: u64, buf: &[u8]) -> BlockDeviceResult<()> {
+        if !self.writable {
+            return Err(BlockDeviceError::ReadOnly);
+        }
+        let span = self.span(first, buf.len())?;
+        self.data.borrow_mut()[span].copy_from_slice(buf);
+        Ok(())
+    }
+}
+
+#[test]
+fn unaligned_accesses_use_whole_blocks() {
+    let card = SdCard::new(8);
+    let mut expected = card.data.borrow().clone();
+    let device = BlockAdapter::new(&card);
+    assert_eq!(device.len(), 4096);
+
+    device.write_at(510, &[0xAA; 4]).unwrap();
+    device.write_at(1024, &[0xBB; 1024]).unwrap();
+    device.write_at(2100, &[0xCC; 1200]).unwrap();
+    expected[510..514].fill(0xAA);
+    expected[1024..2048].fill(0xBB);
+    expected[2100..3300].fill(0xCC);
+    assert_eq!(*card.data.borrow(), expected);
+
+    let mut buf = vec![0u8; 3000];
+    device.read_at(300, &mut buf).unwrap();
+    assert_eq!(buf, expected[300..3300]);
+
+    let mut entry = [0u8; 4];
+    device.read_at(1022, &mut entry).unwrap();
+    assert_eq!(entry, expected[1022..1026]);
+}
+
+#[test]
+fn aligned_middle_is_one_request() {
+    let card = SdCard::new(16);
+    let device = BlockAdapter::new(&card);
+
+    // Bloc partiel en tête, 6 blocs entiers, bloc partiel en queue.
+    let mut buf = vec![0u8; 7 * 512];
+    device.read_at(100, &mut buf).unwrap();
+    assert_eq!(card.reads.get(), 3);
+    assert_eq!(buf, card.data.borrow()[100..100 + 7 * 512]);
+}
+
+#[test]
+fn bounds_and_read_only_media() {
+    let card = SdCard { writable: false, ..SdCard::new(2) };
+    let device = BlockAdapter::new(&card);
+
+    assert_eq!(device.read_at(1000, &mut [0u8; 30]), Err(BlockDeviceError::OutOfBounds));
+    assert_eq!(device.read_at(u64::MAX, &mut [0u8; 2]), Err(BlockDeviceError::OutOfBounds));
+    assert_eq!(device.write_at(10, &[1]), Err(BlockDeviceError::ReadOnly));
+    device.read_at(1023, &mut [0u8; 1]).unwrap();
+}
+
+#[test]
+fn fat_volume_on_block_storage() {
+    const BLOCKS: usize = 33 * 2048;
+    let card = SdCard::new(BLOCKS);
+    let device = BlockAdapter::new(&card);
+    format(&device, device.len(), &FormatOptions::default()).unwrap();
+
+    let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+    let root = fs.root_cluster();
+    fs.mkdir("/logs", root, &EntryTimes::default()).unwrap();
+    let contents: Vec<u8> = (0..3000u32).map(|i| (i * 7) as u8).collect();
+    fs.write_file("/logs/boot.log", root, &contents, &EntryTimes::default()).unwrap();
+
+    assert_eq!(fs.read_file("/logs/boot.log", root).unwrap(), contents);
+    let report = Checker::new(&device, fs.boot()).check(&CheckOptions::default()).unwrap();
+    assert!(report.is_clean(), "{:?}", report.findings);
+}
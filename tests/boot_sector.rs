@@ -1,5 +1,8 @@
 use rust_project::device::block_device::MemoryBlockDevice;
-use rust_project::fs::boot_sector::{BootSector, BootSectorError};
+use rust_project::fs::boot_sector::{BootSector, BootSectorError, Truncation};
+use rust_project::fs::error::FsError;
+use rust_project::fs::filesystem::{FileSystem, MountOptions};
+use rust_project::testing::ImageFixture;
 
 fn make_valid_boot_sector() -> [u8; 512] {
     let mut bs = [0u8; 512];
@@ -56,3 +59,54 @@ fn invalid_signature() {
 
     assert_eq!(result, Err(BootSectorError::InvalidSignature));
 }
+
+#[test]
+fn geometry_must_fit_the_volume() {
+    let mut boot = BootSector::read(&MemoryBlockDevice::new(&make_valid_boot_sector())).unwrap();
+    boot.total_sectors = 32 + 2 * 1234 + 8 * 100;
+    assert_eq!(boot.validate(None), Ok(()));
+
+    let mut no_fat = boot.clone();
+    no_fat.fat_count = 0;
+    assert_eq!(no_fat.validate(None), Err(BootSectorError::InvalidGeometry));
+
+    let mut no_data = boot.clone();
+    no_data.total_sectors = 32 + 2 * 1234;
+    assert_eq!(no_data.validate(None), Err(BootSectorError::InvalidGeometry));
+
+    let volume = boot.total_sectors as u64 * 512;
+    assert_eq!(boot.validate(Some(volume)), Ok(()));
+    assert_eq!(
+        boot.validate(Some(volume - 1)),
+        Err(BootSectorError::Truncated(Truncation { volume, device: volume - 1 }))
+    );
+}
+
+#[test]
+fn truncated_image_is_reported_at_mount() {
+    let sample = ImageFixture::sample();
+    let image = sample.build();
+    let boot = sample.boot_sector();
+    // Coupée au milieu du cluster 10, celui de /DOCS/DEEP/A.BIN.
+    let end = (boot.data_start_sector() + 8 * boot.sectors_per_cluster as u64) * 512 + 100;
+    let truncated = &image[..end as usize];
+    let device = MemoryBlockDevice::new(truncated);
+
+    let err = FileSystem::mount(&device).err().unwrap();
+    assert_eq!(
+        err.kind(),
+        &FsError::BootSector(BootSectorError::Truncated(Truncation {
+            volume: image.len() as u64,
+            device: end,
+        }))
+    );
+    assert!(err.to_string().starts_with("image is truncated"), "{}", err);
+
+    // En mode salvage, les clusters présents restent lisibles.
+    let fs = FileSystem::mount_with(&device, &MountOptions { salvage: true, ..MountOptions::default() }).unwrap();
+    let root = fs.root_cluster();
+    assert_eq!(fs.truncation().unwrap().device, end);
+    assert_eq!(fs.read_file("/Read me first.txt", root).unwrap().len(), 30);
+    assert_eq!(fs.read_file("/DOCS/notes", root).unwrap().len(), 1100);
+    assert!(fs.read_file("/DOCS/DEEP/A.BIN", root).is_err());
+}
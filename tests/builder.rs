@@ -0,0 +1,153 @@
+use rust_project::device::block_device::RamBlockDevice;
+use rust_project::fs::builder::{ImageBuilder, Node};
+use rust_project::fs::check::{CheckOptions, Checker};
+use rust_project::fs::create::CreateError;
+use rust_project::fs::error::FsError;
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::manifest::{Manifest, ManifestContent, ManifestError, ManifestErrorKind};
+use rust_project::fs::path::PathError;
+use rust_project::fs::stat::{ATTR_HIDDEN, ATTR_READ_ONLY};
+use rust_project::fs::timestamp::DateTime;
+
+const SIZE: u64 = 33 * 1024 * 1024;
+
+fn sample() -> ImageBuilder {
+    let kernel: Vec<u8> = (0..3000u32).map(|i| (i % 241) as u8).collect();
+    ImageBuilder::new(SIZE)
+        .label("FIRMWARE")
+        .volume_id(0x1234_5678)
+        .time(DateTime::parse("2024-01-01 12:00:00").unwrap())
+        .directory("/boot")
+        .file("/boot/kernel.img", kernel)
+        .node(Node {
+            attributes: ATTR_READ_ONLY | ATTR_HIDDEN,
+            time: DateTime::parse("2023-06-07 08:09:10"),
+            ..Node::file("/boot/Config File.txt", "dtparam=audio=on\n")
+        })
+        .file("/data/logs/empty.log", "")
+}
+
+fn build(builder: &ImageBuilder) -> Vec<u8> {
+    let device = RamBlockDevice::zeroed(builder.size() as usize);
+    builder.build(&device).unwrap();
+    device.into_inner()
+}
+
+#[test]
+fn builds_identical_images() {
+    let builder = sample();
+    let image = build(&builder);
+    assert_eq!(image, build(&builder));
+    assert_eq!(&image[67..71], &0x1234_5678u32.to_le_bytes());
+
+    let device = RamBlockDevice::zeroed(SIZE as usize);
+    builder.build(&device).unwrap();
+    let fs = FileSystem::mount(&device).unwrap();
+    let root = fs.root_cluster();
+
+    assert_eq!(fs.label().unwrap().as_deref(), Some("FIRMWARE"));
+    assert_eq!(fs.read_file("/boot/kernel.img", root).unwrap().len(), 3000);
+    assert_eq!(fs.stat("/boot/kernel.img", root).unwrap().fragments(), 1);
+    assert_eq!(fs.read_file("/boot/Config File.txt", root).unwrap(), b"dtparam=audio=on\n");
+
+    let config = fs.list(Some("/boot"), root).unwrap().into_iter().find(|e| e.name == "CONFIG~1.TXT").unwrap();
+    assert_eq!(config.attributes, ATTR_READ_ONLY | ATTR_HIDDEN);
+    assert_eq!(config.modified, DateTime::parse("2023-06-07 08:09:10"));
+
+    // Parents créés à la volée, avec la date commune.
+    let data = fs.list(Some("/"), root).unwrap().into_iter().find(|e| e.name == "DATA").unwrap();
+    assert_eq!(data.modified, DateTime::parse("2024-01-01 12:00:00"));
+    assert!(fs.list(Some("/data/logs"), root).unwrap().iter().any(|e| e.name == "EMPTY.LOG"));
+
+    let check = Checker::new(&device, fs.boot()).check(&CheckOptions::default()).unwrap();
+    assert!(check.is_clean(), "{:?}", check);
+}
+
+#[test]
+fn reports_conflicting_entries() {
+    let device = RamBlockDevice::zeroed(SIZE as usize);
+
+    let err = ImageBuilder::new(SIZE).file("/a", "x").file("/A", "y").build(&device).unwrap_err();
+    assert_eq!(err.path(), Some("/A"));
+    assert_eq!(err.kind(), &FsError::Create(CreateError::AlreadyExists));
+
+    let err = ImageBuilder::new(SIZE).file("/a", "x").file("/a/b", "y").build(&device).unwrap_err();
+    assert_eq!(err.path(), Some("/a/b"));
+    assert_eq!(err.kind(), &FsError::Path(PathError::NotADirectory));
+}
+
+#[test]
+fn manifest_describes_an_image() {
+    let text = r#"
+# Image de test
+[volume]
+size = "33M"
+label = "FIRMWARE"   # étiquette
+volume_id = 0x1234_5678
+time = "2024-01-01 12:00:00"
+
+[[dir]]
+path = "/boot"
+
+[[file]]
+path = "/boot/kernel.img"
+source = "kernel.img"
+
+[[file]]
+path = "/boot/Config File.txt"
+text = "dtparam=audio=on\n"
+attributes = "RH"
+time = "2023-06-07 08:09:10"
+"#;
+    let manifest = Manifest::parse(text).unwrap();
+    assert_eq!(manifest.entries.len(), 3);
+    assert_eq!(manifest.entries[1].content, ManifestContent::Source("kernel.img".into()));
+    assert_eq!(manifest.entries[1].line, 12);
+
+    let builder = manifest
+        .into_builder(|_, source| {
+            assert_eq!(source, "kernel.img");
+            Ok::<_, ()>((0..3000u32).map(|i| (i % 241) as u8).collect())
+        })
+        .unwrap();
+
+    // Même description que `sample()`, au répertoire /data près.
+    let expected = sample()
+        .nodes()
+        .iter()
+        .filter(|n| !n.path.starts_with("/data"))
+        .cloned()
+        .fold(
+            ImageBuilder::new(SIZE)
+                .label("FIRMWARE")
+                .volume_id(0x1234_5678)
+                .time(DateTime::parse("2024-01-01 12:00:00").unwrap()),
+            ImageBuilder::node,
+        );
+    assert_eq!(build(&builder), build(&expected));
+}
+
+#[test]
+fn manifest_errors_carry_line_numbers() {
+    let error = |text: &str| Manifest::parse(text).unwrap_err();
+
+    assert_eq!(
+        error("[volume]\nsize = 1024\ncolor = \"red\"\n"),
+        ManifestError { line: 3, kind: ManifestErrorKind::UnknownKey("color".into()) }
+    );
+    assert_eq!(
+        error("[volume]\nsize = 1024\n\n[[file]]\ntext = \"x\"\n"),
+        ManifestError { line: 4, kind: ManifestErrorKind::MissingKey("path") }
+    );
+    assert_eq!(
+        error("[volume]\nsize = 1024\n[[dir]]\npath = \"/a\"\nattributes = \"Q\"\n"),
+        ManifestError { line: 5, kind: ManifestErrorKind::InvalidValue("attributes".into()) }
+    );
+    assert_eq!(
+        error("[volume]\nsize = \"2M\"\nsize = \"4M\"\n"),
+        ManifestError { line: 3, kind: ManifestErrorKind::DuplicateKey("size".into()) }
+    );
+    assert_eq!(error("[volume]\nlabel = \"X\"\n").kind, ManifestErrorKind::MissingKey("size"));
+    assert_eq!(error("[volume]\nsize = \"2M\n").kind, ManifestErrorKind::Syntax);
+    assert_eq!(error("[disk]\n").to_string(), "line 1: unknown table [disk]");
+}
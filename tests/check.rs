@@ -0,0 +1,229 @@
+mod common;
+
+use common::{make_boot_sector, make_dir_entry, make_disk_image};
+
+use rust_project::device::block_device::{MemoryBlockDevice, RamBlockDevice};
+use rust_project::fs::boot_sector::BootSector;
+use rust_project::fs::check::{CheckOptions, Checker, Issue, LostChainAction};
+use rust_project::fs::clusters::ClusterReader;
+use rust_project::fs::directory::{DirectoryReader, ATTR_ARCHIVE};
+use rust_project::fs::error::FsError;
+use rust_project::fs::fat::{Fat, FatError, VolumeFlags, FAT_EOC};
+use rust_project::fs::fsinfo::FsInfo;
+use rust_project::testing::{short_entry, ImageFixture};
+
+const FAT0: usize = 2 * 512;
+const FAT1: usize = 3 * 512;
+
+/// Volume cohérent : 2 FAT, FSInfo, clusters 2 à 9.
+///
+/// Cluster 2 : racine → FILE.TXT, SUB
+/// Cluster 3 : SUB → `.`, `..`
+/// Clusters 4-5 : FILE.TXT (600 octets)
+fn make_clean_image() -> Vec<u8> {
+    let mut img = vec![0u8; 12 * 512];
+
+    let bs = &mut img[0..512];
+    bs[11..13].copy_from_slice(&512u16.to_le_bytes());
+    bs[13] = 1;
+    bs[14..16].copy_from_slice(&2u16.to_le_bytes());
+    bs[16] = 2;
+    bs[32..36].copy_from_slice(&12u32.to_le_bytes());
+    bs[36..40].copy_from_slice(&1u32.to_le_bytes());
+    bs[44..48].copy_from_slice(&2u32.to_le_bytes());
+    bs[48..50].copy_from_slice(&1u16.to_le_bytes());
+    bs[510] = 0x55;
+    bs[511] = 0xAA;
+
+    for fat in [FAT0, FAT1] {
+        for (cluster, value) in [(2, 0x0FFF_FFFFu32), (3, 0x0FFF_FFFF), (4, 5), (5, 0x0FFF_FFFF)] {
+            img[fat + cluster * 4..fat + cluster * 4 + 4].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    let root = 4 * 512;
+    img[root..root + 32].copy_from_slice(&make_dir_entry("FILE", "TXT", 0x20, 4, 600));
+    img[root + 32..root + 64].copy_from_slice(&make_dir_entry("SUB", "", 0x10, 3, 0));
+
+    let sub = 5 * 512;
+    img[sub..sub + 32].copy_from_slice(&make_dir_entry(".", "", 0x10, 3, 0));
+    img[sub + 32..sub + 64].copy_from_slice(&make_dir_entry("..", "", 0x10, 0, 0));
+
+    let device = RamBlockDevice::new(img);
+    let boot = BootSector::read(&device).unwrap();
+    FsInfo { free_count: 4, next_free: 6 }.write(&device, &boot).unwrap();
+
+    device.into_inner()
+}
+
+fn set_fat(img: &mut [u8], cluster: usize, value: u32) {
+    for fat in [FAT0, FAT1] {
+        img[fat + cluster * 4..fat + cluster * 4 + 4].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn issues(report: &rust_project::fs::check::CheckReport) -> Vec<Issue> {
+    report.findings.iter().map(|f| f.issue.clone()).collect()
+}
+
+#[test]
+fn clean_volume_has_no_findings() {
+    let img = make_clean_image();
+    let device = MemoryBlockDevice::new(&img);
+    let boot = BootSector::read(&device).unwrap();
+
+    let report = Checker::new(&device, &boot).check(&CheckOptions::default()).unwrap();
+
+    assert!(report.is_clean(), "{}", report);
+    assert_eq!(report.files, 1);
+    assert_eq!(report.directories, 2);
+    assert_eq!(report.used_clusters, 4);
+    assert_eq!(report.free_clusters, 4);
+}
+
+#[test]
+fn fixture_is_cross_linked_and_lacks_dot_entries() {
+    let img = make_disk_image();
+    let device = MemoryBlockDevice::new(&img);
+    let boot = make_boot_sector();
+
+    let report = Checker::new(&device, &boot).check(&CheckOptions::default()).unwrap();
+    let issues = issues(&report);
+
+    assert!(issues.contains(&Issue::MissingDotEntry { path: "/DIR".into(), name: "." }));
+    assert!(issues.contains(&Issue::CrossLinked {
+        path: "/DIR/FILE.TXT".into(),
+        cluster: 4,
+        other: "/DIR".into(),
+    }));
+}
+
+#[test]
+fn lost_chain_is_saved_as_rec_file() {
+    let mut img = make_clean_image();
+    set_fat(&mut img, 6, 7);
+    set_fat(&mut img, 7, 0x0FFF_FFFF);
+
+    let device = RamBlockDevice::new(img);
+    let boot = BootSector::read(&device).unwrap();
+    let options = CheckOptions { repair: true, lost_chains: LostChainAction::Save };
+
+    let report = Checker::new(&device, &boot).check(&options).unwrap();
+    let issues = issues(&report);
+    assert!(issues.contains(&Issue::LostChain { start: 6, clusters: 2 }));
+    assert!(issues.contains(&Issue::FsInfoFreeCount { recorded: 4, actual: 2 }));
+
+    let fat = Fat::new(&device, &boot);
+    let clusters = ClusterReader::new(&device, &boot, &fat);
+    let entries = DirectoryReader::new(&clusters).read_dir(2).unwrap();
+    let rec = entries.iter().find(|e| e.name == "FSCK0000.REC").unwrap();
+    assert_eq!((rec.start_cluster, rec.size), (6, 1024));
+
+    let again = Checker::new(&device, &boot).check(&CheckOptions::default()).unwrap();
+    assert!(again.is_clean(), "{}", again);
+}
+
+#[test]
+fn repair_truncates_long_chain_and_syncs_fats() {
+    let mut img = make_clean_image();
+    set_fat(&mut img, 5, 6);
+    set_fat(&mut img, 6, 0x0FFF_FFFF);
+    img[FAT1 + 9 * 4] = 0x42; // copie 1 divergente
+
+    let device = RamBlockDevice::new(img);
+    let boot = BootSector::read(&device).unwrap();
+    let options = CheckOptions { repair: true, ..CheckOptions::default() };
+
+    let report = Checker::new(&device, &boot).check(&options).unwrap();
+    let issues = issues(&report);
+    assert!(issues.contains(&Issue::FatCopyMismatch { copy: 1, entries: 1, first_cluster: 9 }));
+    assert!(issues.contains(&Issue::ChainTooLong {
+        path: "/FILE.TXT".into(),
+        size: 600,
+        allocated: 1536,
+    }));
+
+    let fat = Fat::new(&device, &boot);
+    assert_eq!(fat.next_cluster(5), Ok(None));
+    assert_eq!(fat.entry_in_copy(1, 6), Ok(0));
+    assert_eq!(fat.entry_in_copy(1, 9), Ok(0));
+
+    let again = Checker::new(&device, &boot).check(&CheckOptions::default()).unwrap();
+    assert!(again.is_clean(), "{}", again);
+}
+
+#[test]
+fn unclean_volume_recommends_a_scan() {
+    let mut img = make_clean_image();
+    set_fat(&mut img, 1, FAT_EOC & !0x0800_0000);
+    let device = RamBlockDevice::new(img);
+    let boot = BootSector::read(&device).unwrap();
+    let checker = Checker::new(&device, &boot);
+    assert!(checker.scan_recommended().unwrap());
+
+    let report = checker.check(&CheckOptions::default()).unwrap();
+    assert!(report.is_clean(), "{}", report);
+    assert!(report.scan_recommended());
+    assert_eq!(report.volume, VolumeFlags { clean: false, hard_error: false });
+    assert!(report.to_string().contains("volume: not cleanly unmounted"), "{}", report);
+
+    // Une réparation sans problème restant marque le volume propre.
+    let options = CheckOptions { repair: true, ..CheckOptions::default() };
+    checker.check(&options).unwrap();
+    assert!(!checker.scan_recommended().unwrap());
+    assert_eq!(Fat::new(&device, &boot).volume_flags(), Ok(VolumeFlags::default()));
+}
+
+#[test]
+fn recovery_names_stop_at_fsck9999() {
+    // Racine déjà remplie de FSCK0000.REC à FSCK9999.REC (625 clusters).
+    let names: Vec<[u8; 32]> = (0..10000)
+        .map(|i| short_entry(&format!("FSCK{:04}.REC", i), ATTR_ARCHIVE, 0, 0))
+        .collect();
+    let root: Vec<u32> = (2..2 + 625).collect();
+    let fixture = ImageFixture::new(700).directory(&root, &names).chain(&[650, 651]);
+    let device = RamBlockDevice::new(fixture.build());
+    let boot = fixture.boot_sector();
+    let options = CheckOptions { repair: true, lost_chains: LostChainAction::Save };
+
+    let report = Checker::new(&device, &boot).check(&options).unwrap();
+    let unrepaired: Vec<&Issue> = report.unrepaired().collect();
+    assert_eq!(unrepaired, [&Issue::LostChain { start: 650, clusters: 2 }]);
+    assert_eq!(Fat::new(&device, &boot).next_cluster(650), Ok(Some(651)));
+}
+
+#[test]
+fn saving_into_a_full_volume_reports_no_free_cluster() {
+    let root: Vec<[u8; 32]> = (0..16)
+        .map(|i| short_entry(&format!("F{}", i), ATTR_ARCHIVE, 0, 0))
+        .collect();
+    let lost: Vec<u32> = (3..=9).collect();
+    let fixture = ImageFixture::new(8).directory(&[2], &root).chain(&lost);
+    let device = RamBlockDevice::new(fixture.build());
+    let boot = fixture.boot_sector();
+    let options = CheckOptions { repair: true, lost_chains: LostChainAction::Save };
+
+    let err = Checker::new(&device, &boot).check(&options).unwrap_err();
+    assert_eq!(err.kind(), &FsError::Fat(FatError::NoFreeCluster));
+}
+
+#[test]
+fn cross_linked_empty_file_is_not_reported_repaired() {
+    // Fichier vide dont le premier cluster appartient à FILE.TXT : il n’y a
+    // aucun cluster à couper, l’entrée reste en l’état.
+    let mut img = make_clean_image();
+    let root = 4 * 512;
+    img[root + 64..root + 96].copy_from_slice(&make_dir_entry("EMPTY", "TXT", 0x20, 4, 0));
+
+    let device = RamBlockDevice::new(img);
+    let boot = BootSector::read(&device).unwrap();
+    let options = CheckOptions { repair: true, ..CheckOptions::default() };
+    let report = Checker::new(&device, &boot).check(&options).unwrap();
+
+    let finding = report
+        .findings
+        .iter()
+        .find(|f| matches!(&f.issue, Issue::CrossLinked { path, .. } if path == "/EMPTY.TXT"))
+        .unwrap();
+    assert!(!finding.repaired, "{}", report);
+}
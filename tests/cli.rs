@@ -0,0 +1,197 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use rust_project::fs::format::FormatOptions;
+use rust_project::testing::formatted;
+
+/// Écrit une image formatée dans un fichier temporaire propre au test.
+fn image_file(name: &str) -> PathBuf {
+    let options = FormatOptions { label: Some("CLI".into()), ..FormatOptions::default() };
+    let device = formatted(&options);
+
+    let path = std::env::temp_dir().join(format!("rust_project_{}_{}.img", name, std::process::id()));
+    std::fs::write(&path, device.into_inner()).unwrap();
+    path
+}
+
+fn run(image: &PathBuf, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rust_project"))
+        .arg(image)
+        .args(args)
+        .output()
+        .unwrap();
+
+    let text = String::from_utf8_lossy(if output.status.success() {
+        &output.stdout
+    } else {
+        &output.stderr
+    })
+    .into_owned();
+    (output.status.success(), text)
+}
+
+#[test]
+fn df_reports_size_and_label() {
+    let image = image_file("df");
+    let (ok, out) = run(&image, &["df"]);
+    std::fs::remove_file(&image).unwrap();
+
+    assert!(ok, "{}", out);
+    assert!(out.contains("CLI"));
+    assert!(out.contains("0 bad"));
+}
+
+#[test]
+fn missing_file_reports_path() {
+    let image = image_file("cat");
+    let (ok, out) = run(&image, &["cat", "/NOPE.TXT"]);
+    std::fs::remove_file(&image).unwrap();
+
+    assert!(!ok);
+    assert!(out.starts_with("/NOPE.TXT: no such file"), "{}", out);
+}
+
+#[test]
+fn tree_rejects_bad_depth() {
+    let image = image_file("tree");
+    let (ok, out) = run(&image, &["tree", "/", "-L", "x"]);
+    let (ok_root, root) = run(&image, &["tree"]);
+    std::fs::remove_file(&image).unwrap();
+
+    assert!(!ok);
+    assert!(out.contains("-L: invalid number"), "{}", out);
+    assert!(ok_root, "{}", root);
+    assert!(root.ends_with("0 directories, 0 files\n"), "{}", root);
+}
+
+#[test]
+fn import_writes_into_image() {
+    let image = image_file("import");
+    let host = std::env::temp_dir().join(format!("rust_project_cli_import_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&host);
+    std::fs::create_dir_all(host.join("boot")).unwrap();
+    std::fs::write(host.join("boot/config.txt"), b"dtparam=audio=on\n").unwrap();
+
+    let (ok, out) = run(&image, &["import", host.to_str().unwrap(), "/"]);
+    assert!(ok, "{}", out);
+    assert!(out.contains("1 files, 1 directories, 17 bytes"), "{}", out);
+
+    let (ok, out) = run(&image, &["cat", "/boot/config.txt"]);
+    std::fs::remove_file(&image).unwrap();
+    std::fs::remove_dir_all(&host).unwrap();
+
+    assert!(ok, "{}", out);
+    assert_eq!(out, "dtparam=audio=on\n");
+}
+
+#[test]
+fn build_creates_image_from_manifest() {
+    let dir = std::env::temp_dir().join(format!("rust_project_cli_build_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("kernel.img"), [7u8; 1000]).unwrap();
+    std::fs::write(
+        dir.join("image.toml"),
+        "[volume]\nsize = \"33M\"\nlabel = \"CI\"\n\n[[file]]\npath = \"/boot/kernel.img\"\nsource = \"kernel.img\"\n",
+    )
+    .unwrap();
+    let image = dir.join("out.img");
+
+    let (ok, out) = run(&image, &["build", dir.join("image.toml").to_str().unwrap()]);
+    assert!(ok, "{}", out);
+    let first = std::fs::read(&image).unwrap();
+
+    run(&image, &["build", dir.join("image.toml").to_str().unwrap()]);
+    let (ok, out) = run(&image, &["stat", "/boot/kernel.img"]);
+    let second = std::fs::read(&image).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(ok, "{}", out);
+    assert!(out.contains("1000"), "{}", out);
+    assert_eq!(first.len(), 33 * 1024 * 1024);
+    assert!(first == second);
+}
+
+#[test]
+fn overlay_keeps_image_until_applied() {
+    let image = image_file("overlay");
+    let patch = image.with_extension("patch");
+    let _ = std::fs::remove_file(&patch);
+    let host = std::env::temp_dir().join(format!("rust_project_cli_overlay_{}.txt", std::process::id()));
+    std::fs::write(&host, b"trial\n").unwrap();
+    let original = std::fs::read(&image).unwrap();
+
+    let (ok, out) = run(&image, &["--overlay", patch.to_str().unwrap(), "import", host.to_str().unwrap(), "/"]);
+    assert!(ok, "{}", out);
+    assert_eq!(std::fs::read(&image).unwrap(), original);
+
+    // Le patch est rechargé : le fichier est visible à travers l’overlay.
+    let name = host.file_name().unwrap().to_str().unwrap();
+    let (ok, out) = run(&image, &["--overlay", patch.to_str().unwrap(), "cat", name]);
+    assert!(ok, "{}", out);
+    assert_eq!(out, "trial\n");
+
+    let (ok, out) = run(&image, &["apply", patch.to_str().unwrap()]);
+    assert!(ok, "{}", out);
+    let (ok, out) = run(&image, &["cat", name]);
+    std::fs::remove_file(&image).unwrap();
+    std::fs::remove_file(&patch).unwrap();
+    std::fs::remove_file(&host).unwrap();
+
+    assert!(ok, "{}", out);
+    assert_eq!(out, "trial\n");
+}
+
+#[test]
+fn stats_are_printed_after_command() {
+    let image = image_file("stats");
+    let output = Command::new(env!("CARGO_BIN_EXE_rust_project"))
+        .arg(&image)
+        .args(["--stats", "ls"])
+        .output()
+        .unwrap();
+    std::fs::remove_file(&image).unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.starts_with("stats: "), "{}", stderr);
+    assert!(stderr.contains(", 0 writes (0 bytes)"), "{}", stderr);
+}
+
+#[test]
+fn truncated_image_needs_salvage() {
+    let image = image_file("salvage");
+    let file = std::fs::OpenOptions::new().write(true).open(&image).unwrap();
+    file.set_len(1536 * 1024).unwrap();
+    drop(file);
+
+    let (ok, out) = run(&image, &["ls"]);
+    assert!(!ok);
+    assert!(out.contains("image is truncated"), "{}", out);
+
+    let (ok, out) = run(&image, &["--salvage", "df"]);
+    std::fs::remove_file(&image).unwrap();
+    assert!(ok, "{}", out);
+    assert!(out.contains("CLI"), "{}", out);
+}
+
+#[test]
+fn damaged_boot_sector_is_fixed_from_backup() {
+    let image = image_file("bootfix");
+    let mut bytes = std::fs::read(&image).unwrap();
+    bytes[510] = 0;
+    std::fs::write(&image, &bytes).unwrap();
+
+    let (ok, out) = run(&image, &["bootcmp"]);
+    assert!(ok, "{}", out);
+    assert!(out.contains("signature: primary 0xaa00, backup 0xaa55"), "{}", out);
+
+    let (ok, out) = run(&image, &["bootfix", "backup"]);
+    assert!(ok, "{}", out);
+    assert!(out.contains("primary boot sector restored from backup boot sector"), "{}", out);
+
+    let (ok, out) = run(&image, &["bootcmp"]);
+    std::fs::remove_file(&image).unwrap();
+    assert!(ok, "{}", out);
+    assert!(out.contains("identical"), "{}", out);
+}
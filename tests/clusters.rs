@@ -1,7 +1,13 @@
-use rust_project::device::block_device::MemoryBlockDevice;
+use std::cell::RefCell;
+
+use rust_project::device::block_device::{BlockDevice, BlockDeviceResult, MemoryBlockDevice};
+use rust_project::device::stats::StatsDevice;
 use rust_project::fs::boot_sector::BootSector;
 use rust_project::fs::fat::Fat;
 use rust_project::fs::clusters::{ClusterReader, ClusterError};
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::view::Count;
+use rust_project::testing::{file_entry, ImageFixture};
 
 fn make_boot_sector() -> BootSector {
     BootSector {
@@ -10,7 +16,10 @@ fn make_boot_sector() -> BootSector {
         reserved_sectors: 1,
         fat_count: 1,
         sectors_per_fat: 1,
+        ext_flags: 0,
         root_cluster: 2,
+        total_sectors: 4,
+        fs_info_sector: 0,
     }
 }
 
@@ -81,3 +90,92 @@ fn invalid_cluster_number() {
     let err = reader.read_cluster(1, &mut buf).unwrap_err();
     assert_eq!(err, ClusterError::InvalidCluster);
 }
+
+/// Note la longueur des plages de chaque requête groupée.
+struct Vectored<D: BlockDevice> {
+    base: D,
+    requests: RefCell<Vec<Vec<usize>>>,
+}
+
+impl<D: BlockDevice> BlockDevice for Vectored<D> {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> BlockDeviceResult<()> {
+        self.base.read_at(offset, buf)
+    }
+
+    fn read_vectored_at(&self, requests: &mut [(u64, &mut [u8])]) -> BlockDeviceResult<()> {
+        self.requests.borrow_mut().push(requests.iter().map(|(_, buf)| buf.len()).collect());
+        self.base.read_vectored_at(requests)
+    }
+}
+
+#[test]
+fn contiguous_runs_are_read_together() {
+    let contents: Vec<u8> = (0..2500u32).map(|i| (i % 253) as u8).collect();
+    let device = Vectored {
+        base: ImageFixture::new(16)
+            .directory(&[2], &[file_entry("DATA.BIN", 3, 2500)])
+            .file(&[3, 4, 5, 9, 10], &contents)
+            .device(),
+        requests: RefCell::new(Vec::new()),
+    };
+
+    let fs = FileSystem::mount(&device).unwrap();
+    assert_eq!(fs.read_file("/DATA.BIN", fs.root_cluster()).unwrap(), contents);
+
+    // Une requête pour le fichier, une plage par suite de clusters.
+    let requests = device.requests.borrow();
+    assert_eq!(requests.last().unwrap(), &[3 * 512, 2 * 512]);
+}
+
+#[test]
+fn partial_reads_span_contiguous_clusters() {
+    let contents: Vec<u8> = (0..2048u32).map(|i| (i % 251) as u8).collect();
+    let device = ImageFixture::new(16)
+        .directory(&[2], &[file_entry("DATA.BIN", 3, 2048)])
+        .file(&[3, 4, 8, 9], &contents)
+        .device();
+
+    let fs = FileSystem::mount(&device).unwrap();
+    let tail = fs.tail("/DATA.BIN", fs.root_cluster(), Count::Bytes(1748)).unwrap();
+    assert_eq!(tail, contents[300..]);
+}
+
+#[test]
+fn run_reads_stay_in_data_region() {
+    let img = make_disk_image();
+    let device = MemoryBlockDevice::new(&img);
+    let boot = make_boot_sector();
+    let fat = Fat::new(&device, &boot);
+    let reader = ClusterReader::new(&device, &boot, &fat);
+
+    // Les clusters 2 et 3 se suivent sur le disque.
+    let mut buf = vec![0u8; 1024];
+    reader.read_in_run(2, 0, &mut buf).unwrap();
+    assert_eq!((&buf[..4], &buf[512..516]), (&b"ABCD"[..], &b"EFGH"[..]));
+
+    let mut buf = vec![0u8; 4];
+    assert_eq!(reader.read_in_run(3, 510, &mut buf), Err(ClusterError::InvalidCluster));
+    assert_eq!(reader.read_in_run(2, u64::MAX, &mut buf), Err(ClusterError::InvalidCluster));
+    assert_eq!(reader.read_in_run(4, 0, &mut buf), Err(ClusterError::InvalidCluster));
+}
+
+#[test]
+fn looping_chain_is_rejected_without_reading_data() {
+    let fixture = ImageFixture::new(4096)
+        .directory(&[2], &[file_entry("LOOP.BIN", 3, 100)])
+        .cycle(&[3, 4]);
+    let device = StatsDevice::new(fixture.device());
+    let fs = FileSystem::mount(&device).unwrap();
+    let root = fs.root_cluster();
+    let fat = Fat::new(&device, fs.boot());
+    let reader = ClusterReader::new(&device, fs.boot(), &fat);
+    device.reset();
+
+    let mut out = Vec::new();
+    assert_eq!(reader.read_cluster_chain(3, &mut out), Err(ClusterError::ChainLoop));
+    assert!(out.is_empty());
+    // Le premier cluster revisité arrête le parcours : deux entrées FAT lues.
+    assert_eq!(device.stats().bytes_read, 2 * 4);
+
+    assert!(fs.read_file("/LOOP.BIN", root).is_err());
+}
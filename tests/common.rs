@@ -1,77 +1,69 @@
+// Chaque fichier de test n’utilise qu’une partie de ces helpers.
+#![allow(dead_code)]
+
 use rust_project::fs::boot_sector::BootSector;
+use rust_project::testing::{dir_entry, file_entry, short_entry, ImageFixture};
+
+/// Image de base des tests :
+///
+/// Cluster 2 : racine → contient DIR
+/// Cluster 3 : DIR → contient FILE.TXT
+/// Cluster 4 : FILE.TXT données
+fn fixture() -> ImageFixture {
+    ImageFixture::new(3)
+        .reserved_sectors(1)
+        .fat_count(1)
+        .without_fs_info()
+        .directory(&[2], &[dir_entry("DIR", 3)])
+        // La chaîne de DIR se poursuit sur le cluster de FILE.TXT.
+        .directory(&[3, 4], &[file_entry("FILE.TXT", 4, 123)])
+}
 
 /// Construction d’un BootSector FAT32 simulé
 pub fn make_boot_sector() -> BootSector {
-    BootSector {
-        bytes_per_sector: 512,
-        sectors_per_cluster: 1,
-        reserved_sectors: 1,
-        fat_count: 1,
-        sectors_per_fat: 1,
-        root_cluster: 2,
-    }
+    fixture().boot_sector()
 }
 
-/// Construire une image mémoire avec :
-///
-/// Cluster 2 : racine → contient DIR  
-/// Cluster 3 : DIR → contient FILE.TXT  
-/// Cluster 4 : FILE.TXT données
+/// Construire une image mémoire (voir `fixture`).
 pub fn make_disk_image() -> Vec<u8> {
-    let mut img = vec![0u8; 512]; // Reserved
-
-    // FAT simulée (1 FAT, 512 octets suffisent pour nos 5 clusters)
-    let mut fat = vec![0u8; 512];
-    // cluster 2 → EOC (root)
-    fat[2*4..2*4+4].copy_from_slice(&0x0FFF_FFFFu32.to_le_bytes());
-    // cluster 3 → cluster 4 (DIR → FILE.TXT)
-    fat[3*4..3*4+4].copy_from_slice(&4u32.to_le_bytes());
-    // cluster 4 → EOC (FILE.TXT)
-    fat[4*4..4*4+4].copy_from_slice(&0x0FFF_FFFFu32.to_le_bytes());
-    img.extend_from_slice(&fat);
-
-    // Cluster 2 : root
-    let mut root = vec![0u8; 512];
-    let dir = make_dir_entry("DIR", "", 0x10, 3, 0); // dossier DIR → cluster 3
-    root[0..32].copy_from_slice(&dir);
-    root[32] = 0x00;
-    img.extend_from_slice(&root);
-
-    // Cluster 3 : DIR
-    let mut cluster3 = vec![0u8; 512];
-    let file = make_dir_entry("FILE", "TXT", 0x20, 4, 123); // FILE.TXT → cluster 4
-    cluster3[0..32].copy_from_slice(&file);
-    cluster3[32] = 0x00;
-    img.extend_from_slice(&cluster3);
-
-    // Cluster 4 : données du fichier (rempli de 0)
-    img.extend_from_slice(&vec![0u8; 512]);
-
-    img
+    fixture().build()
 }
 
 /// Helper pour créer une entrée FAT32 (8.3)
-fn make_dir_entry(name: &str, ext: &str, attr: u8, start_cluster: u32, size: u32) -> [u8; 32] {
-    let mut e = [0u8; 32];
-
-    // Nom (8 octets)
-    let mut n = [b' '; 8];
-    n[..name.len()].copy_from_slice(name.as_bytes());
-    e[0..8].copy_from_slice(&n);
+pub fn make_dir_entry(name: &str, ext: &str, attr: u8, start_cluster: u32, size: u32) -> [u8; 32] {
+    if ext.is_empty() {
+        short_entry(name, attr, start_cluster, size)
+    } else {
+        short_entry(&format!("{}.{}", name, ext), attr, start_cluster, size)
+    }
+}
 
-    // Extension (3 octets)
-    let mut x = [b' '; 3];
-    x[..ext.len()].copy_from_slice(ext.as_bytes());
-    e[8..11].copy_from_slice(&x);
+/// Répertoire hôte temporaire et vide, propre au test `name`.
+pub fn host_dir(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("rust_project_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    path
+}
 
-    e[11] = attr;
+/// Générateur pseudo-aléatoire (xorshift64) : les tests de propriétés
+/// restent reproductibles à partir de leur graine.
+pub struct Rng(u64);
 
-    // cluster bas et haut
-    e[20..22].copy_from_slice(&((start_cluster >> 16) as u16).to_le_bytes());
-    e[26..28].copy_from_slice(&(start_cluster as u16).to_le_bytes());
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
 
-    // taille
-    e[28..32].copy_from_slice(&size.to_le_bytes());
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
 
-    e
+    /// Entier dans `0..n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
 }
@@ -0,0 +1,73 @@
+mod common;
+
+use std::error::Error;
+
+use common::{make_disk_image, make_boot_sector};
+
+use rust_project::device::block_device::{BlockDeviceError, MemoryBlockDevice};
+use rust_project::fs::fat::{Fat, FatError};
+use rust_project::fs::fsinfo::FsInfoError;
+use rust_project::fs::clusters::{ClusterError, ClusterReader};
+use rust_project::fs::directory::{DirectoryError, DirectoryReader};
+use rust_project::fs::path::{PathResolver, PathError};
+use rust_project::fs::cat::{Cat, CatError};
+use rust_project::fs::error::FsError;
+
+#[test]
+fn unreadable_directory_is_not_reported_as_not_found() {
+    let mut img = make_disk_image();
+    img.truncate(1024 + 512); // cluster 3 (DIR) hors de l’image
+    let device = MemoryBlockDevice::new(&img);
+    let boot = make_boot_sector();
+    let fat = Fat::new(&device, &boot);
+    let clusters = ClusterReader::new(&device, &boot, &fat);
+    let dir = DirectoryReader::new(&clusters);
+    let resolver = PathResolver::new(&boot, &dir);
+
+    assert_eq!(
+        resolver.resolve("/DIR/FILE.TXT", 2),
+        Err(PathError::Directory {
+            cluster: 3,
+            source: DirectoryError::Cluster(ClusterError::Io(BlockDeviceError::OutOfBounds)),
+        })
+    );
+}
+
+#[test]
+fn cat_keeps_cause_chain() {
+    let mut img = make_disk_image();
+    img[1536 + 26] = 5; // FILE.TXT pointe vers un cluster hors de l’image
+    let device = MemoryBlockDevice::new(&img);
+    let boot = make_boot_sector();
+    let fat = Fat::new(&device, &boot);
+    let clusters = ClusterReader::new(&device, &boot, &fat);
+    let dir = DirectoryReader::new(&clusters);
+    let resolver = PathResolver::new(&boot, &dir);
+    let cat = Cat::new(&resolver, &clusters);
+
+    let err = cat.cat("/DIR/FILE.TXT", 2).unwrap_err();
+    assert!(matches!(err, CatError::Cluster { cluster: 5, .. }));
+
+    let source = err.source().unwrap();
+    assert_eq!(source.to_string(), "cannot read cluster");
+    assert_eq!(source.source().unwrap().to_string(), "read out of bounds");
+}
+
+#[test]
+fn fs_error_context() {
+    let err = FsError::from(CatError::NotFound)
+        .with_cluster(3)
+        .with_path("/DIR/NOPE.TXT");
+
+    assert_eq!(err.to_string(), "/DIR/NOPE.TXT: cluster 3: no such file");
+    assert_eq!(err.path(), Some("/DIR/NOPE.TXT"));
+    assert_eq!(err.cluster(), Some(3));
+    assert_eq!(err.kind(), &FsError::Cat(CatError::NotFound));
+}
+
+#[test]
+fn fat_error_keeps_fs_info_cause() {
+    let err = FatError::FsInfo(FsInfoError::InvalidSignature);
+    assert_eq!(err.to_string(), "cannot update FSInfo sector");
+    assert_eq!(err.source().unwrap().to_string(), "invalid FSInfo signature");
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use common::host_dir;
+
+use rust_project::fs::cat::CatError;
+use rust_project::fs::error::FsError;
+use rust_project::fs::extract::{host_name, ExtractError, ExtractOptions};
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::timestamp::DateTime;
+use rust_project::testing::{dir_entry, file_entry, modified, raw_long_name, ImageFixture};
+
+/// /DOCS/README.TXT (1200 octets, 2024-05-06 07:08:10)
+/// /DOCS/A~1 « a/b » (5 octets)
+/// /BROKEN.BIN (chaîne qui boucle)
+fn make_volume() -> (ImageFixture, Vec<u8>) {
+    let content: Vec<u8> = (0..1200u32).map(|i| (i % 251) as u8).collect();
+    let when = DateTime::parse("2024-05-06 07:08:10").unwrap();
+
+    let root = [dir_entry("DOCS", 3), file_entry("BROKEN.BIN", 8, 2000)];
+    let mut docs = vec![modified(file_entry("README.TXT", 4, 1200), when)];
+    docs.extend(raw_long_name("a/b", file_entry("A~1", 7, 5)));
+
+    let fixture = ImageFixture::new(16)
+        .directory(&[2], &root)
+        .directory(&[3], &docs)
+        .file(&[4, 5, 6], &content)
+        .file(&[7], b"hello")
+        .cycle(&[8]);
+
+    (fixture, content)
+}
+
+#[test]
+fn extracts_tree_and_reports_broken_files() {
+    let (fixture, content) = make_volume();
+    let device = fixture.device();
+    let fs = FileSystem::mount(&device).unwrap();
+    let dest = host_dir("extract_tree");
+
+    let report = fs.extract("/", fs.root_cluster(), &dest, ExtractOptions::default()).unwrap();
+
+    assert_eq!((report.files, report.directories, report.bytes), (2, 1, 1205));
+    assert_eq!(std::fs::read(dest.join("DOCS/README.TXT")).unwrap(), content);
+    assert_eq!(std::fs::read(dest.join("DOCS/a_b")).unwrap(), b"hello");
+    assert!(!dest.join("BROKEN.BIN").exists());
+
+    assert_eq!(report.errors.len(), 1);
+    match &report.errors[0] {
+        ExtractError::Image(e) => assert_eq!(e.path(), Some("/BROKEN.BIN")),
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    // 2024-05-06 07:08:10, interprétée en UTC.
+    let modified = std::fs::metadata(dest.join("DOCS/README.TXT")).unwrap().modified().unwrap();
+    assert_eq!(modified, SystemTime::UNIX_EPOCH + Duration::from_secs(1_714_979_290));
+
+    std::fs::remove_dir_all(&dest).unwrap();
+}
+
+#[test]
+fn extracts_single_file_and_directory() {
+    let device = make_volume().0.device();
+    let fs = FileSystem::mount(&device).unwrap();
+    let dest = host_dir("extract_single");
+
+    let options = ExtractOptions { preserve_times: false };
+    let report = fs.extract("/DOCS/a/b", fs.root_cluster(), &dest, options.clone());
+    // « a/b » ne peut pas être désigné par un chemin : le nom 8.3 le peut.
+    assert!(report.is_err());
+
+    let report = fs.extract("/DOCS/A~1", fs.root_cluster(), &dest, options.clone()).unwrap();
+    assert_eq!(report.files, 1);
+    assert_eq!(std::fs::read(dest.join("a_b")).unwrap(), b"hello");
+
+    let report = fs.extract("/DOCS", fs.root_cluster(), &dest, options).unwrap();
+    assert_eq!((report.files, report.directories), (2, 1));
+    assert!(dest.join("DOCS/README.TXT").is_file());
+
+    std::fs::remove_dir_all(&dest).unwrap();
+}
+
+#[test]
+fn short_chain_is_reported_not_counted() {
+    // Un seul cluster pour 1000 octets déclarés.
+    let (fixture, _) = make_volume();
+    let device = fixture.write(2, 64, &file_entry("SHORT.BIN", 9, 1000)).chain(&[9]).device();
+
+    let fs = FileSystem::mount(&device).unwrap();
+    let dest = host_dir("extract_short");
+    let report = fs.extract("/SHORT.BIN", fs.root_cluster(), &dest, ExtractOptions::default()).unwrap();
+    std::fs::remove_dir_all(&dest).unwrap();
+
+    assert_eq!((report.files, report.bytes), (0, 0));
+    assert_eq!(report.errors.len(), 1);
+    match &report.errors[0] {
+        ExtractError::Image(e) => {
+            assert_eq!(e.path(), Some("/SHORT.BIN"));
+            assert_eq!(e.kind(), &FsError::Cat(CatError::ShortChain { size: 1000, readable: 512 }));
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn host_names_are_sanitized() {
+    assert_eq!(host_name("a/b\0c"), "a_b_c");
+    assert_eq!(host_name(".."), "_");
+    assert_eq!(host_name("Résumé.txt"), "Résumé.txt");
+}
@@ -1,6 +1,12 @@
-use rust_project::device::block_device::MemoryBlockDevice;
-use rust_project::fs::boot_sector::BootSector;
-use rust_project::fs::fat::{Fat, FatError};
+use rust_project::device::block_device::{BlockDevice, BlockDeviceError, MemoryBlockDevice, RamBlockDevice};
+use rust_project::fs::backup::BootSource;
+use rust_project::fs::create::EntryTimes;
+use rust_project::fs::boot_sector::{BootSector, BootSectorError};
+use rust_project::fs::error::FsError;
+use rust_project::fs::fat::{Fat, FatDiff, FatError, VolumeFlags, FAT_EOC, FAT_FREE};
+use rust_project::fs::filesystem::{FileSystem, MountOptions};
+use rust_project::fs::statfs::FreeCount;
+use rust_project::testing::{file_entry, ImageFixture};
 
 fn make_boot_sector() -> BootSector {
     BootSector {
@@ -9,7 +15,10 @@ fn make_boot_sector() -> BootSector {
         reserved_sectors: 1,
         fat_count: 1,
         sectors_per_fat: 1,
+        ext_flags: 0,
         root_cluster: 2,
+        total_sectors: 64,
+        fs_info_sector: 0,
     }
 }
 
@@ -67,3 +76,170 @@ fn invalid_fat_entry() {
 
     assert_eq!(fat.next_cluster(2), Err(FatError::InvalidCluster));
 }
+
+/// Écrase l’entrée d’un cluster dans la copie `copy` de la FAT.
+fn set_entry(image: &mut [u8], fixture: &ImageFixture, copy: u8, cluster: u32, value: u32) {
+    let at = fixture.boot_sector().fat_offset(copy) as usize + cluster as usize * 4;
+    image[at..at + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[test]
+fn active_fat_is_read_and_written_alone() {
+    let fixture = ImageFixture::new(8).chain(&[3, 4]).active_fat(1);
+    let mut image = fixture.build();
+    set_entry(&mut image, &fixture, 0, 3, 0x0BAD_0000);
+    let device = RamBlockDevice::new(image);
+    let boot = fixture.boot_sector();
+    assert!(!boot.mirrored());
+    assert_eq!(boot.active_fat(), 1);
+
+    let fat = Fat::new(&device, &boot);
+    assert_eq!(fat.next_cluster(3), Ok(Some(4)));
+
+    // Sans miroir, seule la copie active est écrite.
+    fat.set_entry(5, 6).unwrap();
+    assert_eq!(fat.entry_in_copy(1, 5), Ok(6));
+    assert_eq!(fat.entry_in_copy(0, 5), Ok(FAT_FREE));
+
+    let mut bad = boot.clone();
+    bad.ext_flags = 0x0082;
+    assert_eq!(bad.validate(None), Err(BootSectorError::InvalidGeometry));
+}
+
+#[test]
+fn diff_lists_entries_and_resync_clears_them() {
+    let fixture = ImageFixture::new(8).fat_count(3).chain(&[3, 4]);
+    let mut image = fixture.build();
+    set_entry(&mut image, &fixture, 0, 3, 7);
+    set_entry(&mut image, &fixture, 2, 9, 0x0FFF_FFF7);
+    let device = RamBlockDevice::new(image);
+    let boot = fixture.boot_sector();
+    let fat = Fat::new(&device, &boot);
+
+    assert_eq!(
+        fat.diff().unwrap(),
+        [
+            FatDiff { cluster: 3, values: vec![7, 4, 4] },
+            FatDiff { cluster: 9, values: vec![0, 0, 0x0FFF_FFF7] },
+        ]
+    );
+
+    assert_eq!(fat.resync(3), Err(FatError::InvalidCopy(3)));
+    fat.resync(1).unwrap();
+    assert!(fat.diff().unwrap().is_empty());
+    assert_eq!(fat.next_cluster(3), Ok(Some(4)));
+}
+
+#[test]
+fn resync_copies_fats_larger_than_a_chunk() {
+    // Plus de 16 Ki entrées : la copie se fait en plusieurs blocs.
+    let fixture = ImageFixture::new(40_000);
+    let mut image = fixture.build();
+    set_entry(&mut image, &fixture, 0, 30_000, FAT_EOC);
+    set_entry(&mut image, &fixture, 0, 39_000, FAT_EOC);
+    let device = RamBlockDevice::new(image);
+    let boot = fixture.boot_sector();
+    let fat = Fat::new(&device, &boot);
+    assert_eq!(fat.diff().unwrap().len(), 2);
+
+    fat.resync(0).unwrap();
+    assert!(fat.diff().unwrap().is_empty());
+    assert_eq!(fat.with_copy(1).next_cluster(39_000), Ok(None));
+}
+
+#[test]
+fn damaged_first_fat_is_read_from_another_copy() {
+    let fixture = ImageFixture::new(8)
+        .file(&[3, 4], &[0x5A; 1000])
+        .directory(&[2], &[file_entry("DATA.BIN", 3, 1000)]);
+    let mut image = fixture.build();
+    set_entry(&mut image, &fixture, 0, 3, FAT_FREE);
+    let device = MemoryBlockDevice::new(&image);
+
+    let fs = FileSystem::mount(&device).unwrap();
+    assert!(fs.read_file("/DATA.BIN", fs.root_cluster()).is_err());
+    let free = fs.statfs(FreeCount::Scan).unwrap().free_clusters;
+
+    let fs = fs.with_fat_copy(1).unwrap();
+    assert_eq!(fs.fat_copy(), 1);
+    assert_eq!(fs.read_file("/DATA.BIN", fs.root_cluster()).unwrap(), [0x5A; 1000]);
+    // Les statistiques et l’accès brut lisent aussi la copie choisie.
+    assert_eq!(fs.statfs(FreeCount::Scan).unwrap().free_clusters, free - 1);
+    assert_eq!(fs.inspect().chain(3).unwrap().clusters, [3, 4]);
+
+    let err = FileSystem::mount(&device).unwrap().with_fat_copy(2).err().unwrap();
+    assert_eq!(err.kind(), &FsError::Fat(FatError::InvalidCopy(2)));
+}
+
+#[test]
+fn volume_flags_live_in_fat_entry_1() {
+    assert_eq!(VolumeFlags::from_entry(FAT_EOC), VolumeFlags::default());
+    assert_eq!(
+        VolumeFlags::from_entry(0x0FFF_FFFF & !0x0C00_0000),
+        VolumeFlags { clean: false, hard_error: true }
+    );
+    let dirty = VolumeFlags { clean: false, hard_error: false };
+    assert_eq!(dirty.to_entry(FAT_EOC), 0x07FF_FFFF);
+    assert_eq!(dirty.to_string(), "not cleanly unmounted");
+
+    let fixture = ImageFixture::new(8);
+    let device = RamBlockDevice::new(fixture.build());
+    let boot = fixture.boot_sector();
+    let fat = Fat::new(&device, &boot);
+    fat.set_volume_flags(dirty).unwrap();
+    assert_eq!(fat.volume_flags(), Ok(dirty));
+    assert_eq!(fat.with_copy(1).volume_flags(), Ok(dirty));
+}
+
+#[test]
+fn writable_mount_is_dirty_until_unmounted() {
+    let device = ImageFixture::sample().device();
+    let writable = MountOptions { writable: true, ..MountOptions::default() };
+    let flags = |device: &RamBlockDevice| FileSystem::mount(device).unwrap().volume_flags().unwrap();
+
+    let fs = FileSystem::mount_with(&device, &writable).unwrap();
+    assert_eq!(fs.volume_flags().unwrap(), VolumeFlags::default());
+    assert!(!flags(&device).clean);
+    fs.unmount().unwrap();
+    assert_eq!(flags(&device), VolumeFlags::default());
+
+    // Sans démontage (coupure), le volume reste marqué, et un volume déjà
+    // marqué le reste après un démontage propre.
+    FileSystem::mount_with(&device, &writable).unwrap();
+    let fs = FileSystem::mount_with(&device, &writable).unwrap();
+    assert!(fs.volume_flags().unwrap().needs_check());
+    fs.unmount().unwrap();
+    assert!(!flags(&device).clean);
+
+    // Un montage en lecture seule n’écrit rien.
+    let image = ImageFixture::sample().build();
+    let read_only = MemoryBlockDevice::new(&image);
+    assert!(FileSystem::mount_with(&read_only, &writable).is_err());
+    FileSystem::mount(&read_only).unwrap().unmount().unwrap();
+}
+
+#[test]
+fn read_only_mount_rejects_writes() {
+    let image = ImageFixture::sample().build();
+    let device = RamBlockDevice::new(image.clone());
+    let contents = |device: &RamBlockDevice| {
+        let mut data = vec![0u8; image.len()];
+        device.read_at(0, &mut data).unwrap();
+        data
+    };
+    let fs = FileSystem::mount(&device).unwrap();
+    let root = fs.root_cluster();
+    let read_only = FsError::Device(BlockDeviceError::ReadOnly);
+
+    let times = EntryTimes::default();
+    assert_eq!(fs.mkdir("/NEW", root, &times).unwrap_err().kind(), &read_only);
+    assert_eq!(fs.write_file("/NEW.TXT", root, b"x", &times).unwrap_err().kind(), &read_only);
+    assert_eq!(fs.set_label("NEW"), Err(read_only.clone()));
+    assert_eq!(fs.resync_fat(0), Err(read_only.clone()));
+    assert_eq!(fs.restore_boot(BootSource::Primary), Err(read_only));
+    assert_eq!(contents(&device), image);
+
+    let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+    fs.mkdir("/NEW", root, &times).unwrap();
+    fs.unmount().unwrap();
+}
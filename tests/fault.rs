@@ -0,0 +1,183 @@
+use std::error::Error;
+use std::time::Duration;
+
+use rust_project::device::block_device::{Access, BlockDevice, BlockDeviceError, RamBlockDevice};
+use rust_project::device::fault::{Fault, FaultDevice, FaultRule, RandomFaults};
+use rust_project::fs::check::{CheckOptions, Checker};
+use rust_project::fs::create::EntryTimes;
+use rust_project::fs::filesystem::{FileSystem, MountOptions};
+use rust_project::fs::statfs::FreeCount;
+use rust_project::fs::format::FormatOptions;
+use rust_project::testing::{explore, formatted, ImageFixture};
+
+/// Vrai si `IoError` figure dans la chaîne des causes.
+fn caused_by_io(error: &(dyn Error + 'static)) -> bool {
+    let mut current = Some(error);
+    while let Some(e) = current {
+        if e.downcast_ref::<BlockDeviceError>() == Some(&BlockDeviceError::IoError) {
+            return true;
+        }
+        current = e.source();
+    }
+    false
+}
+
+/// Volume formaté avec quelques fichiers, pour remplir le premier cluster
+/// de la racine.
+fn populated() -> Vec<u8> {
+    let device = formatted(&FormatOptions::default());
+    let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+    for i in 0..14 {
+        fs.write_file(&format!("/F{}", i), fs.root_cluster(), b"x", &EntryTimes::default())
+            .unwrap();
+    }
+    // Pas de démontage : le volume reste marqué, et le remonter en écriture
+    // n’écrit rien avant l’opération dont on injecte les défauts.
+    device.into_inner()
+}
+
+#[test]
+fn rules_select_accesses() {
+    let device = FaultDevice::new(ImageFixture::sample().device())
+        .rule(FaultRule::new(Fault::Error(BlockDeviceError::IoError)).reads().range(4096..8192).times(1));
+    let fs = FileSystem::mount(&device).unwrap();
+    let root = fs.root_cluster();
+
+    // La FAT (secteurs 8 à 15) est touchée une fois, puis la règle s’épuise.
+    let error = fs.read_file("/DOCS/notes", root).unwrap_err();
+    assert!(caused_by_io(&error), "{}", error);
+    assert_eq!(fs.read_file("/DOCS/notes", root).unwrap().len(), 1100);
+
+    let log = device.log();
+    assert_eq!(device.faults(), 1);
+    let faulty = log.iter().find(|r| r.fault.is_some()).unwrap();
+    assert_eq!(faulty.access, Access::Read);
+    assert!((4096..8192).contains(&faulty.offset));
+
+    let skipped = FaultDevice::new(RamBlockDevice::zeroed(1024))
+        .rule(FaultRule::new(Fault::Error(BlockDeviceError::IoError)).writes().skip(2));
+    assert!(skipped.write_at(0, &[1]).is_ok());
+    assert!(skipped.read_at(0, &mut [0]).is_ok());
+    assert!(skipped.write_at(0, &[1]).is_ok());
+    assert_eq!(skipped.write_at(0, &[1]), Err(BlockDeviceError::IoError));
+}
+
+#[test]
+fn corruption_and_latency() {
+    let device = FaultDevice::new(RamBlockDevice::zeroed(64))
+        .rule(FaultRule::new(Fault::BitFlip { bit: 9 }).writes().times(1))
+        .rule(FaultRule::new(Fault::Torn { written: 2 }).writes().times(1))
+        .rule(FaultRule::new(Fault::Delay(Duration::from_millis(5))).reads())
+        .sleep_with(|_| {});
+
+    device.write_at(0, &[0; 4]).unwrap();
+    assert_eq!(device.write_at(8, &[9; 4]), Err(BlockDeviceError::IoError));
+
+    let mut buf = [0u8; 12];
+    device.read_at(0, &mut buf).unwrap();
+    assert_eq!(buf, [0, 2, 0, 0, 0, 0, 0, 0, 9, 9, 0, 0]);
+    device.read_at(0, &mut buf).unwrap();
+    assert_eq!(device.delayed(), Duration::from_millis(10));
+    assert_eq!(device.faults(), 4);
+}
+
+#[test]
+fn random_schedule_is_reproducible() {
+    let faults = RandomFaults {
+        errors: 50,
+        bit_flips: 50,
+        delays: 20,
+        ..RandomFaults::new(42)
+    };
+    let run = || {
+        let device = FaultDevice::new(ImageFixture::sample().device())
+            .random(faults)
+            .sleep_with(|_| {});
+        explore(&device);
+        device.log()
+    };
+
+    let log = run();
+    assert!(log.iter().any(|r| r.fault.is_some()));
+    assert_eq!(log, run());
+}
+
+#[test]
+fn torn_write_rate_does_not_affect_reads() {
+    let faults = RandomFaults {
+        torn_writes: 1000,
+        ..RandomFaults::new(7)
+    };
+    let device = FaultDevice::new(RamBlockDevice::new(vec![0x5A; 4096])).random(faults);
+
+    let mut buf = [0u8; 512];
+    for offset in (0..4096).step_by(512) {
+        device.read_at(offset, &mut buf).unwrap();
+        assert_eq!(buf, [0x5A; 512]);
+    }
+    assert!(device.log().iter().all(|r| r.fault.is_none()));
+
+    let _ = device.write_at(0, &[1; 8]);
+    assert!(matches!(device.log().last().unwrap().fault, Some(Fault::Torn { .. })));
+}
+
+#[test]
+fn failed_writes_leave_volume_consistent() {
+    let image = populated();
+    let faults = [Fault::Error(BlockDeviceError::IoError), Fault::Torn { written: 3 }];
+
+    for fault in faults {
+        for op in 0..2 {
+            for k in 0.. {
+                let device = FaultDevice::new(RamBlockDevice::new(image.clone()))
+                    .rule(FaultRule::new(fault).writes().skip(k).times(1));
+                let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+                let root = fs.root_cluster();
+                let result = match op {
+                    0 => fs.write_file("/A rather long file name.txt", root, &[1; 5000], &EntryTimes::default()),
+                    _ => fs.mkdir("/Another directory", root, &EntryTimes::default()).map(|_| ()),
+                };
+                if device.faults() == 0 {
+                    result.unwrap();
+                    break;
+                }
+                assert!(result.is_err());
+
+                let base = device.into_inner();
+                let fs = FileSystem::mount(&base).unwrap();
+                let report = Checker::new(&base, fs.boot()).check(&CheckOptions::default()).unwrap();
+                assert!(report.is_clean(), "{:?} at write {}: {:?}", fault, k, report.findings);
+            }
+        }
+    }
+}
+
+#[test]
+fn failed_file_writes_leave_no_entry() {
+    let image = populated();
+    let free = {
+        let device = RamBlockDevice::new(image.clone());
+        FileSystem::mount(&device).unwrap().statfs(FreeCount::Scan).unwrap().free_clusters
+    };
+
+    // Chaque écriture échoue à son tour : FAT, contenu puis répertoire.
+    for k in 0.. {
+        let device = FaultDevice::new(RamBlockDevice::new(image.clone()))
+            .rule(FaultRule::new(Fault::Error(BlockDeviceError::IoError)).writes().skip(k).times(1));
+        let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+        let root = fs.root_cluster();
+        let result = fs.write_file("/LEAK.BIN", root, &[7; 5000], &EntryTimes::default());
+        if device.faults() == 0 {
+            result.unwrap();
+            assert!(k > 10, "only {} writes", k);
+            break;
+        }
+        assert!(result.is_err());
+
+        // Pas d’entrée dont les clusters garderaient d’anciennes données.
+        let base = device.into_inner();
+        let fs = FileSystem::mount(&base).unwrap();
+        assert!(fs.stat("/LEAK.BIN", root).is_err(), "entry left at write {}", k);
+        assert_eq!(fs.statfs(FreeCount::Scan).unwrap().free_clusters, free, "write {}", k);
+    }
+}
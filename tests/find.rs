@@ -0,0 +1,121 @@
+use rust_project::device::block_device::RamBlockDevice;
+use rust_project::fs::directory::EntryType;
+use rust_project::fs::error::FsError;
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::find::{glob_match, FindOptions};
+use rust_project::fs::path::PathError;
+use rust_project::fs::timestamp::DateTime;
+use rust_project::testing::{dir_entry, file_entry, long_name, modified, ImageFixture};
+
+fn day(year: u16, month: u8, day: u8) -> DateTime {
+    DateTime { year, month, day, ..DateTime::default() }
+}
+
+/// /BOOT.LOG (2 Mo déclarés, 2024-01-10)
+/// /LOGS/SYSTEM~1.LOG « system-2024.log » (5 Ko, 2024-03-01)
+/// /LOGS/OLD.TXT (10 octets, 2023-12-31)
+fn make_volume() -> ImageFixture {
+    let boot_log = modified(file_entry("BOOT.LOG", 0, 2 * 1024 * 1024), day(2024, 1, 10));
+    let root = [boot_log, dir_entry("LOGS", 3)];
+    let system = modified(file_entry("SYSTEM~1.LOG", 0, 5 * 1024), day(2024, 3, 1));
+    let mut logs = long_name("system-2024.log", system);
+    logs.push(modified(file_entry("OLD.TXT", 0, 10), day(2023, 12, 31)));
+
+    ImageFixture::new(8).directory(&[2], &root).directory(&[3], &logs)
+}
+
+fn find(fs: &FileSystem<'_, RamBlockDevice>, options: FindOptions) -> Vec<String> {
+    let mut found = Vec::new();
+    fs.find("/", fs.root_cluster(), options, |item| found.push(item.unwrap().path))
+        .unwrap();
+    found
+}
+
+#[test]
+fn glob_patterns() {
+    assert!(glob_match("*.log", "BOOT.LOG", false));
+    assert!(!glob_match("*.log", "BOOT.LOG", true));
+    assert!(glob_match("b??t.*", "BOOT.LOG", false));
+    assert!(glob_match("[a-c]*", "boot", false));
+    assert!(!glob_match("[!a-c]*", "boot", false));
+    assert!(glob_match("*2024*", "system-2024.log", false));
+    assert!(glob_match("[", "[", true));
+    assert!(!glob_match("*.txt", "a.txt.bak", false));
+    assert!(glob_match("**", "", false));
+}
+
+#[test]
+fn long_names_are_read_and_matched() {
+    let device = make_volume().device();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let entries = fs.list(Some("/LOGS"), fs.root_cluster()).unwrap();
+    assert_eq!(entries[0].name, "SYSTEM~1.LOG");
+    assert_eq!(entries[0].long_name.as_deref(), Some("system-2024.log"));
+    assert_eq!(entries[1].long_name, None);
+
+    let options = FindOptions { name: Some("system-*".into()), ..FindOptions::default() };
+    assert_eq!(find(&fs, options), ["/LOGS/system-2024.log"]);
+
+    // Le nom long permet aussi la résolution de chemin.
+    let err = fs.list(Some("/LOGS/system-2024.log"), fs.root_cluster()).unwrap_err();
+    assert_eq!(err.kind(), &FsError::Path(PathError::NotADirectory));
+}
+
+#[test]
+fn type_size_and_date_predicates() {
+    let device = make_volume().device();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let options = FindOptions {
+        name: Some("*.LOG".into()),
+        min_size: Some(1024 * 1024),
+        ..FindOptions::default()
+    };
+    assert_eq!(find(&fs, options), ["/BOOT.LOG"]);
+
+    let options = FindOptions { entry_type: Some(EntryType::Directory), ..FindOptions::default() };
+    assert_eq!(find(&fs, options), ["/LOGS"]);
+
+    let options = FindOptions {
+        entry_type: Some(EntryType::File),
+        modified_after: Some(day(2024, 1, 1)),
+        modified_before: Some(day(2024, 3, 1)),
+        ..FindOptions::default()
+    };
+    assert_eq!(find(&fs, options), ["/BOOT.LOG"]);
+
+    let options = FindOptions { max_size: Some(100), max_depth: Some(1), ..FindOptions::default() };
+    assert_eq!(find(&fs, options), ["/LOGS"]);
+}
+
+#[test]
+fn results_stream_past_unreadable_directories() {
+    // Répertoire pointant hors du volume, à la place de BOOT.LOG.
+    let device = make_volume().write(2, 0, &dir_entry("BAD", 0x0FFF_FFF0)).device();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let mut found = Vec::new();
+    let mut errors = 0;
+    fs.find("/", fs.root_cluster(), FindOptions::default(), |item| match item {
+        Ok(entry) => found.push(entry.path),
+        Err(_) => errors += 1,
+    })
+    .unwrap();
+
+    assert_eq!(errors, 1);
+    assert_eq!(found, ["/BAD", "/LOGS", "/LOGS/system-2024.log", "/LOGS/OLD.TXT"]);
+}
+
+#[test]
+fn dates_parse_and_round_trip() {
+    let date = DateTime::parse("2024-03-01T13:45:10").unwrap();
+    assert_eq!(date.to_string(), "2024-03-01 13:45:10");
+
+    let (d, t) = date.to_fat();
+    assert_eq!(DateTime::from_fat(d, t), Some(date));
+
+    assert_eq!(DateTime::parse("2024-03-01"), Some(day(2024, 3, 1)));
+    assert_eq!(DateTime::parse("2024-13-01"), None);
+    assert_eq!(DateTime::from_fat(0, 0), None);
+}
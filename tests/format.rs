@@ -0,0 +1,122 @@
+use rust_project::device::block_device::{BlockDevice, RamBlockDevice};
+use rust_project::fs::boot_sector::BootSector;
+use rust_project::fs::check::{CheckOptions, Checker};
+use rust_project::fs::clusters::ClusterReader;
+use rust_project::fs::directory::DirectoryReader;
+use rust_project::fs::fat::Fat;
+use rust_project::fs::format::{default_sectors_per_cluster, format, FormatError, FormatOptions, MIN_CLUSTERS};
+use rust_project::fs::fsinfo::FsInfo;
+
+/// Assez grand pour les 65525 clusters de FAT32 avec des clusters de 512 octets.
+const SIZE: u64 = 33 * 1024 * 1024;
+
+#[test]
+fn formatted_volume_mounts_and_is_clean() {
+    let device = RamBlockDevice::zeroed(SIZE as usize);
+    let boot = format(&device, SIZE, &FormatOptions::default()).unwrap();
+
+    assert_eq!(boot, BootSector::read(&device).unwrap());
+    assert_eq!(boot.bytes_per_sector, 512);
+    assert_eq!(boot.sectors_per_cluster, 1);
+    assert_eq!(boot.fat_count, 2);
+    assert_eq!(boot.root_cluster, 2);
+    assert_eq!(boot.total_sectors as u64, SIZE / 512);
+    assert!(boot.cluster_count() as u64 >= MIN_CLUSTERS);
+
+    let fat = Fat::new(&device, &boot);
+    assert_eq!(fat.next_cluster(2), Ok(None));
+    let clusters = ClusterReader::new(&device, &boot, &fat);
+    assert!(DirectoryReader::new(&clusters).read_dir(2).unwrap().is_empty());
+
+    let info = FsInfo::read(&device, &boot).unwrap();
+    assert_eq!(info.free_count, boot.cluster_count() - 1);
+
+    let report = Checker::new(&device, &boot).check(&CheckOptions::default()).unwrap();
+    assert!(report.is_clean(), "{}", report);
+}
+
+#[test]
+fn backup_boot_sector_and_label() {
+    let device = RamBlockDevice::zeroed(SIZE as usize);
+    let options = FormatOptions {
+        label: Some("data".into()),
+        volume_id: 0xCAFE_F00D,
+        ..FormatOptions::default()
+    };
+    format(&device, SIZE, &options).unwrap();
+
+    let mut primary = [0u8; 512];
+    let mut backup = [0u8; 512];
+    device.read_at(0, &mut primary).unwrap();
+    device.read_at(6 * 512, &mut backup).unwrap();
+
+    assert_eq!(primary, backup);
+    assert_eq!(&primary[67..71], &0xCAFE_F00Du32.to_le_bytes());
+    assert_eq!(&primary[71..82], b"DATA       ");
+}
+
+#[test]
+fn invalid_options_are_rejected() {
+    let device = RamBlockDevice::zeroed(SIZE as usize);
+
+    let bad_label = FormatOptions { label: Some("A*B".into()), ..FormatOptions::default() };
+    assert_eq!(format(&device, SIZE, &bad_label), Err(FormatError::InvalidLabel));
+
+    let bad_sector = FormatOptions { bytes_per_sector: 300, ..FormatOptions::default() };
+    assert_eq!(format(&device, SIZE, &bad_sector), Err(FormatError::InvalidSectorSize));
+
+    assert_eq!(
+        format(&device, 32 * 512, &FormatOptions::default()),
+        Err(FormatError::VolumeTooSmall)
+    );
+
+    // Un volume FAT32 compte au moins 65525 clusters, sinon c’est un FAT16.
+    assert_eq!(format(&device, 32 * 1024 * 1024, &FormatOptions::default()), Err(FormatError::VolumeTooSmall));
+    let large_clusters = FormatOptions { sectors_per_cluster: Some(8), ..FormatOptions::default() };
+    assert_eq!(format(&device, SIZE, &large_clusters), Err(FormatError::VolumeTooSmall));
+    let large_sectors = FormatOptions { bytes_per_sector: 4096, ..FormatOptions::default() };
+    assert_eq!(format(&device, SIZE, &large_sectors), Err(FormatError::VolumeTooSmall));
+
+    // Rien n’est écrit au-delà du périphérique.
+    let small = RamBlockDevice::zeroed(SIZE as usize - 512);
+    assert_eq!(
+        format(&small, SIZE, &FormatOptions::default()),
+        Err(FormatError::ExceedsDevice { size: SIZE, device_size: SIZE - 512 })
+    );
+    assert!(small.into_inner().iter().all(|&b| b == 0));
+}
+
+#[test]
+fn microsoft_cluster_size_table() {
+    const MB: u64 = 1024 * 1024;
+    const GB: u64 = 1024 * MB;
+
+    assert_eq!(default_sectors_per_cluster(64 * MB, 512), 1);
+    assert_eq!(default_sectors_per_cluster(2 * GB, 512), 8);
+    assert_eq!(default_sectors_per_cluster(12 * GB, 512), 16);
+    assert_eq!(default_sectors_per_cluster(24 * GB, 512), 32);
+    assert_eq!(default_sectors_per_cluster(64 * GB, 512), 64);
+    assert_eq!(default_sectors_per_cluster(2 * GB, 4096), 1);
+}
+
+/// Vérifie l’image avec `fsck.fat` quand dosfstools est installé ; sans
+/// lui, le test est sauté avec un message.
+#[test]
+fn accepted_by_linux_fsck() {
+    if std::process::Command::new("fsck.fat").arg("--help").output().is_err() {
+        eprintln!("skipping accepted_by_linux_fsck: fsck.fat not found (install dosfstools)");
+        return;
+    }
+
+    let device = RamBlockDevice::zeroed(SIZE as usize);
+    let options = FormatOptions { label: Some("TEST".into()), ..FormatOptions::default() };
+    format(&device, SIZE, &options).unwrap();
+
+    let path = std::env::temp_dir().join(format!("rust_project_mkfs_{}.img", std::process::id()));
+    std::fs::write(&path, device.into_inner()).unwrap();
+
+    let output = std::process::Command::new("fsck.fat").arg("-n").arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+}
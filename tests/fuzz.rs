@@ -0,0 +1,84 @@
+//! Régressions trouvées par le fuzzing (`fuzz/`), et mutations aléatoires
+//! du volume d’exemple.
+//!
+//! Chaque image de `tests/fixtures/fuzz` a provoqué une panique, une boucle
+//! ou une allocation démesurée ; `explore` doit désormais s’y terminer
+//! normalement. L’allocateur de ce binaire refuse les allocations de plus de
+//! 64 Mio, ce qui fait échouer le test au lieu de consommer la mémoire.
+
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::path::Path;
+
+use common::Rng;
+use rust_project::device::block_device::MemoryBlockDevice;
+use rust_project::testing::{explore, ImageFixture};
+
+const ALLOCATION_LIMIT: usize = 64 << 20;
+
+struct Bounded;
+
+unsafe impl GlobalAlloc for Bounded {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() > ALLOCATION_LIMIT {
+            return std::ptr::null_mut();
+        }
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if new_size > ALLOCATION_LIMIT {
+            return std::ptr::null_mut();
+        }
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Bounded = Bounded;
+
+#[test]
+fn fuzz_fixtures_are_handled() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fuzz");
+    let mut count = 0;
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let image = std::fs::read(entry.unwrap().path()).unwrap();
+        explore(&MemoryBlockDevice::new(&image));
+        count += 1;
+    }
+    assert!(count > 0);
+}
+
+#[test]
+fn mutated_sample_is_handled() {
+    let sample = ImageFixture::sample().build();
+    let mut rng = Rng::new(40);
+
+    for _ in 0..500 {
+        let mut image = sample.clone();
+        for _ in 0..1 + rng.below(8) {
+            let at = rng.below(image.len() as u64 - 3) as usize;
+            let value = match rng.below(3) {
+                0 => rng.next_u64() as u32,
+                1 => [0, 1, 0x0FFF_FFF7, 0x0FFF_FFFF, u32::MAX][rng.below(5) as usize],
+                _ => u32::from_le_bytes([image[at], image[at + 1], image[at + 2], image[at + 3]])
+                    ^ (1 << rng.below(32)),
+            };
+            image[at..at + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        explore(&MemoryBlockDevice::new(&image));
+    }
+}
+
+#[test]
+fn truncated_sample_is_handled() {
+    let sample = ImageFixture::sample().build();
+    for len in (0..sample.len()).step_by(509) {
+        explore(&MemoryBlockDevice::new(&sample[..len]));
+    }
+}
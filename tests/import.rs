@@ -0,0 +1,157 @@
+mod common;
+
+use std::fs::File;
+use std::time::{Duration, SystemTime};
+
+use common::host_dir;
+
+use rust_project::fs::check::{CheckOptions, Checker};
+use rust_project::fs::create::CreateError;
+use rust_project::fs::error::FsError;
+use rust_project::fs::filesystem::{FileSystem, MountOptions};
+use rust_project::fs::format::FormatOptions;
+use rust_project::fs::import::{image_name, ImportError, ImportOptions};
+use rust_project::fs::lfn::{encode, exact_short_name, short_alias, LongNameCollector};
+use rust_project::fs::timestamp::DateTime;
+use rust_project::testing::formatted;
+
+#[test]
+fn imports_tree_with_long_names_and_contiguous_files() {
+    let host = host_dir("import_tree");
+    let big: Vec<u8> = (0..5000u32).map(|i| (i % 253) as u8).collect();
+    std::fs::create_dir(host.join("Firmware Files")).unwrap();
+    std::fs::write(host.join("Firmware Files/kernel image.bin"), &big).unwrap();
+    std::fs::write(host.join("README.TXT"), b"read me").unwrap();
+    std::fs::write(host.join("empty"), b"").unwrap();
+
+    let device = formatted(&FormatOptions::default());
+    let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+    let root = fs.root_cluster();
+    let report = fs.import(&host, "/", root, ImportOptions::default()).unwrap();
+    std::fs::remove_dir_all(&host).unwrap();
+
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert_eq!((report.files, report.directories, report.bytes), (3, 1, 5007));
+
+    let names: Vec<_> = fs
+        .list(Some("/"), root)
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.name, e.long_name))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            (String::from("FIRMWA~1"), Some(String::from("Firmware Files"))),
+            (String::from("README.TXT"), None),
+            (String::from("EMPTY"), Some(String::from("empty"))),
+        ]
+    );
+
+    assert_eq!(fs.read_file("/README.TXT", root).unwrap(), b"read me");
+    assert_eq!(fs.read_file("/Firmware Files/kernel image.bin", root).unwrap(), big);
+    assert!(fs.read_file("/empty", root).unwrap().is_empty());
+
+    let stat = fs.stat("/FIRMWA~1/KERNEL~1.BIN", root).unwrap();
+    assert_eq!((stat.cluster_count(), stat.fragments()), (10, 1));
+
+    let check = Checker::new(&device, fs.boot()).check(&CheckOptions::default()).unwrap();
+    assert!(check.is_clean(), "{:?}", check);
+}
+
+#[test]
+fn imports_host_times_and_merges_directories() {
+    let host = host_dir("import_times");
+    std::fs::create_dir(host.join("DATA")).unwrap();
+    std::fs::write(host.join("DATA/A.TXT"), b"a").unwrap();
+    // 2024-05-06 07:08:10 UTC
+    let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_714_979_290);
+    File::options()
+        .write(true)
+        .open(host.join("DATA/A.TXT"))
+        .and_then(|f| f.set_modified(mtime))
+        .unwrap();
+
+    let device = formatted(&FormatOptions::default());
+    let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+    let root = fs.root_cluster();
+    fs.import(&host, "/", root, ImportOptions::default()).unwrap();
+
+    let entry = fs.list(Some("/DATA"), root).unwrap().into_iter().find(|e| e.name == "A.TXT").unwrap();
+    assert_eq!(entry.modified, DateTime::parse("2024-05-06 07:08:10"));
+
+    // Second import : DATA est réutilisé, A.TXT existe déjà.
+    std::fs::write(host.join("DATA/B.TXT"), b"b").unwrap();
+    let report = fs.import(&host, "/", root, ImportOptions { preserve_times: false }).unwrap();
+    std::fs::remove_dir_all(&host).unwrap();
+
+    assert_eq!((report.files, report.directories), (1, 0));
+    assert_eq!(report.errors.len(), 1);
+    match &report.errors[0] {
+        ImportError::Image(e) => {
+            assert_eq!(e.path(), Some("/DATA/A.TXT"));
+            assert_eq!(e.kind(), &FsError::Create(CreateError::AlreadyExists));
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    let b = fs.list(Some("/DATA"), root).unwrap().into_iter().find(|e| e.name == "B.TXT").unwrap();
+    assert_eq!(b.modified, None);
+    assert!(fs.import(&host, "/NOPE", root, ImportOptions::default()).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_are_reported_not_followed() {
+    let host = host_dir("import_symlink");
+    std::fs::create_dir(host.join("SUB")).unwrap();
+    std::fs::write(host.join("SUB/A.TXT"), b"a").unwrap();
+    // Lien vers un ancêtre : le suivre ferait boucler l’import.
+    std::os::unix::fs::symlink(&host, host.join("SUB/LOOP")).unwrap();
+
+    let device = formatted(&FormatOptions::default());
+    let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+    let root = fs.root_cluster();
+    let report = fs.import(&host, "/", root, ImportOptions::default()).unwrap();
+    std::fs::remove_dir_all(&host).unwrap();
+
+    assert_eq!((report.files, report.directories), (1, 1));
+    assert_eq!(report.errors.len(), 1);
+    match &report.errors[0] {
+        ImportError::Host { path, source } => {
+            assert!(path.ends_with("SUB/LOOP"), "{:?}", path);
+            assert_eq!(source.kind(), std::io::ErrorKind::Unsupported);
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn short_aliases_and_long_name_slots() {
+    assert_eq!(exact_short_name("README.TXT"), Some(*b"README  TXT"));
+    assert_eq!(exact_short_name("readme.txt"), None);
+    assert_eq!(exact_short_name("A B"), None);
+    assert_eq!(exact_short_name("TOOLONGNAME"), None);
+
+    assert_eq!(&short_alias("My Document.html", |_| false), b"MYDOCU~1HTM");
+    assert_eq!(&short_alias(".bashrc", |_| false), b"BASHRC~1   ");
+    assert_eq!(&short_alias("a+b.tar.gz", |_| false), b"A_BTAR~1GZ ");
+    let taken = [*b"MYDOCU~1HTM", *b"MYDOCU~2HTM"];
+    assert_eq!(&short_alias("My Document.html", |n| taken.contains(n)), b"MYDOCU~3HTM");
+
+    // 27 caractères : trois fragments, relus à l’identique.
+    let name = "A rather long file name.txt";
+    let short = *b"ARATHE~1TXT";
+    let slots = encode(name, &short).unwrap();
+    assert_eq!(slots.len(), 3);
+    assert_eq!(slots[0][0], 0x43);
+
+    let mut collector = LongNameCollector::new();
+    for slot in &slots {
+        collector.push(slot);
+    }
+    assert_eq!(collector.finish(&short).as_deref(), Some(name));
+
+    assert!(encode("a:b", &short).is_none());
+    assert_eq!(image_name("a:b?.txt. "), "a_b_.txt");
+}
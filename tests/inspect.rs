@@ -0,0 +1,66 @@
+use rust_project::fs::fat::{ChainEnd, Fat, FAT_BAD};
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::inspect::{FatEntryKind, SlotInfo};
+use rust_project::testing::{file_entry, long_name, ImageFixture};
+
+#[test]
+fn fat_entry_meanings() {
+    assert_eq!(FatEntryKind::decode(0), FatEntryKind::Free);
+    assert_eq!(FatEntryKind::decode(1), FatEntryKind::Reserved);
+    assert_eq!(FatEntryKind::decode(0x0FFF_FFF3), FatEntryKind::Reserved);
+    assert_eq!(FatEntryKind::decode(FAT_BAD), FatEntryKind::Bad);
+    assert_eq!(FatEntryKind::decode(0x0FFF_FFF8), FatEntryKind::EndOfChain);
+    assert_eq!(FatEntryKind::decode(0xF000_0005), FatEntryKind::Next(5));
+
+    let device = ImageFixture::new(8).fat_entry(3, 0x0FFF_0000).device();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let entry = fs.inspect().fat_entry(0, 3).unwrap();
+    assert!(!entry.in_range);
+    assert_eq!(entry.to_string(), "FAT[3] = 0x0fff0000: next cluster 268369920 (out of range)");
+    assert_eq!(fs.inspect().fat_entry(1, 2).unwrap().kind, FatEntryKind::EndOfChain);
+}
+
+#[test]
+fn raw_sector_cluster_and_chain() {
+    let device = ImageFixture::new(16).write(5, 0, b"DATA").chain(&[5, 6, 9]).device();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let boot = fs.inspect().sector(0).unwrap();
+    assert_eq!(&boot[510..512], &[0x55, 0xAA]);
+    assert!(fs.inspect().sector(u64::MAX).is_err());
+
+    assert_eq!(&fs.inspect().cluster(5).unwrap()[..4], b"DATA");
+    assert_eq!(fs.inspect().cluster(1).unwrap_err().cluster(), Some(1));
+
+    let chain = fs.inspect().chain(5).unwrap();
+    assert_eq!(chain.clusters, [5, 6, 9]);
+    assert_eq!(chain.to_string(), "5 -> 6 -> 9 -> [end of chain] (3 clusters)");
+
+    Fat::new(&device, fs.boot()).set_entry(9, 6).unwrap();
+    assert_eq!(fs.inspect().chain(5).unwrap().end, ChainEnd::Loop { cluster: 6 });
+}
+
+#[test]
+fn directory_slots_decode() {
+    let slots = long_name("notes.txt", file_entry("NOTES.TXT", 7, 42));
+    let short = slots[1];
+    let device = ImageFixture::new(8).directory(&[2], &slots).device();
+    let fs = FileSystem::mount(&device).unwrap();
+    let root = fs.root_cluster();
+
+    let (_, info) = fs.inspect().slot(root, 0).unwrap();
+    match info {
+        SlotInfo::LongName { ordinal, last, ref text, .. } => {
+            assert_eq!((ordinal, last, text.as_str()), (1, true, "notes.txt"));
+        }
+        other => panic!("unexpected slot {:?}", other),
+    }
+
+    let (slot, info) = fs.inspect().slot(root, 1).unwrap();
+    assert_eq!(slot.raw, short);
+    assert!(info.to_string().starts_with("NOTES.TXT attrs -----A (0x20) cluster 7 size 42"));
+
+    assert_eq!(fs.inspect().slot(root, 2).unwrap().1, SlotInfo::End);
+    assert!(fs.inspect().slot(root, 16).is_err());
+}
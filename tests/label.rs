@@ -0,0 +1,72 @@
+use rust_project::device::block_device::{BlockDevice, RamBlockDevice};
+use rust_project::fs::clusters::ClusterReader;
+use rust_project::fs::directory::DirectoryReader;
+use rust_project::fs::error::FsError;
+use rust_project::fs::fat::Fat;
+use rust_project::fs::filesystem::{FileSystem, MountOptions};
+use rust_project::fs::format::FormatOptions;
+use rust_project::fs::label::LabelError;
+use rust_project::fs::ls::Ls;
+use rust_project::fs::path::PathResolver;
+use rust_project::testing::formatted;
+
+fn labelled(label: Option<&str>) -> RamBlockDevice {
+    formatted(&FormatOptions { label: label.map(Into::into), ..FormatOptions::default() })
+}
+
+fn bpb_label(device: &RamBlockDevice, sector: u64) -> [u8; 11] {
+    let mut raw = [0u8; 11];
+    device.read_at(sector * 512 + 71, &mut raw).unwrap();
+    raw
+}
+
+#[test]
+fn label_from_root_entry_is_hidden_from_ls() {
+    let device = labelled(Some("CARD01"));
+    let fs = FileSystem::mount(&device).unwrap();
+
+    assert_eq!(fs.label().unwrap().as_deref(), Some("CARD01"));
+
+    let boot = fs.boot();
+    let fat = Fat::new(&device, boot);
+    let clusters = ClusterReader::new(&device, boot, &fat);
+    let dirs = DirectoryReader::new(&clusters);
+    let resolver = PathResolver::new(boot, &dirs);
+    assert!(Ls::new(&resolver).list(None, boot.root_cluster).unwrap().is_empty());
+}
+
+#[test]
+fn set_label_updates_root_and_both_boot_sectors() {
+    let device = labelled(None);
+    let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+    assert_eq!(fs.label().unwrap(), None);
+
+    fs.set_label("my disk").unwrap();
+
+    assert_eq!(fs.label().unwrap().as_deref(), Some("MY DISK"));
+    assert_eq!(&bpb_label(&device, 0), b"MY DISK    ");
+    assert_eq!(&bpb_label(&device, 6), b"MY DISK    ");
+
+    fs.set_label("").unwrap();
+    assert_eq!(fs.label().unwrap(), None);
+    assert_eq!(&bpb_label(&device, 0), b"NO NAME    ");
+}
+
+#[test]
+fn falls_back_to_boot_sector_label() {
+    let device = labelled(None);
+    device.write_at(71, b"FROMBPB    ").unwrap();
+
+    let fs = FileSystem::mount(&device).unwrap();
+    assert_eq!(fs.label().unwrap().as_deref(), Some("FROMBPB"));
+}
+
+#[test]
+fn invalid_label_is_rejected() {
+    let device = labelled(Some("KEEP"));
+    let fs = FileSystem::mount_with(&device, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+
+    assert_eq!(fs.set_label("A.B"), Err(FsError::Label(LabelError::InvalidLabel)));
+    assert_eq!(fs.set_label("TWELVE_CHARS"), Err(FsError::Label(LabelError::InvalidLabel)));
+    assert_eq!(fs.label().unwrap().as_deref(), Some("KEEP"));
+}
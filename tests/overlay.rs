@@ -0,0 +1,110 @@
+use rust_project::device::block_device::{BlockDevice, BlockDeviceError, MemoryBlockDevice, RamBlockDevice};
+use rust_project::device::overlay::{OverlayDevice, PatchError};
+use rust_project::fs::check::{CheckOptions, Checker};
+use rust_project::fs::create::EntryTimes;
+use rust_project::fs::filesystem::{FileSystem, MountOptions};
+use rust_project::fs::format::FormatOptions;
+use rust_project::testing::formatted;
+
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+fn read(device: &impl BlockDevice, offset: u64, len: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; len];
+    device.read_at(offset, &mut buf).unwrap();
+    buf
+}
+
+#[test]
+fn writes_stay_in_delta() {
+    let base = pattern(4096);
+    let overlay = OverlayDevice::new(MemoryBlockDevice::new(&base));
+
+    overlay.write_at(500, &[0xAA; 20]).unwrap();
+    overlay.write_at(1024, &[0xBB; 4]).unwrap();
+    overlay.write_at(3000, &[0xCC]).unwrap();
+
+    let mut expected = base.clone();
+    expected[500..520].fill(0xAA);
+    expected[1024..1028].fill(0xBB);
+    expected[3000] = 0xCC;
+    assert_eq!(read(&overlay, 0, 4096), expected);
+    assert_eq!(read(&overlay, 510, 4), expected[510..514]);
+
+    assert_eq!(overlay.dirty_sectors(), 4);
+    assert_eq!(overlay.dirty_ranges(), [0..1536, 2560..3072]);
+    assert_eq!(read(overlay.base(), 0, 4096), base);
+
+    // Une écriture hors de la base échoue sans rien modifier.
+    assert_eq!(overlay.write_at(4000, &[0; 200]), Err(BlockDeviceError::OutOfBounds));
+    assert_eq!(overlay.dirty_sectors(), 4);
+
+    overlay.discard();
+    assert!(!overlay.is_dirty());
+    assert_eq!(read(&overlay, 0, 4096), base);
+}
+
+#[test]
+fn commit_writes_base() {
+    let base = RamBlockDevice::new(pattern(4096));
+    let overlay = OverlayDevice::new(&base);
+    overlay.write_at(3000, &[7; 100]).unwrap();
+
+    overlay.commit().unwrap();
+    assert!(!overlay.is_dirty());
+    assert_eq!(read(&base, 3000, 100), [7; 100]);
+
+    // Base en lecture seule : le delta est conservé.
+    let data = pattern(1024);
+    let readonly = OverlayDevice::new(MemoryBlockDevice::new(&data));
+    readonly.write_at(0, &[1]).unwrap();
+    assert_eq!(readonly.commit(), Err(BlockDeviceError::ReadOnly));
+    assert_eq!(readonly.dirty_sectors(), 1);
+}
+
+#[test]
+fn patch_round_trip() {
+    let base = pattern(8192);
+    let overlay = OverlayDevice::new(MemoryBlockDevice::new(&base));
+    overlay.write_at(100, b"hello").unwrap();
+    overlay.write_at(7000, b"world").unwrap();
+    let patch = overlay.export_patch();
+
+    let other = OverlayDevice::new(MemoryBlockDevice::new(&base));
+    other.load_patch(&patch).unwrap();
+    assert_eq!(other.dirty_ranges(), overlay.dirty_ranges());
+    assert_eq!(read(&other, 0, 8192), read(&overlay, 0, 8192));
+
+    let fresh = OverlayDevice::new(MemoryBlockDevice::new(&base));
+    assert_eq!(fresh.load_patch(b"garbage"), Err(PatchError::InvalidHeader));
+    assert_eq!(fresh.load_patch(&patch[..patch.len() - 1]), Err(PatchError::Truncated));
+    let wide = OverlayDevice::with_sector_size(MemoryBlockDevice::new(&base), 4096);
+    assert_eq!(
+        wide.load_patch(&patch),
+        Err(PatchError::SectorSize { patch: 512, device: 4096 })
+    );
+    let small = OverlayDevice::new(MemoryBlockDevice::new(&base[..4096]));
+    assert!(matches!(small.load_patch(&patch), Err(PatchError::Device { sector: 13, .. })));
+    assert!(!fresh.is_dirty() && !small.is_dirty());
+}
+
+#[test]
+fn filesystem_writes_through_overlay() {
+    let image = formatted(&FormatOptions::default()).into_inner();
+
+    let overlay = OverlayDevice::new(MemoryBlockDevice::new(&image));
+    let fs = FileSystem::mount_with(&overlay, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+    let root = fs.root_cluster();
+    fs.mkdir("/logs", root, &EntryTimes::default()).unwrap();
+    fs.write_file("/logs/boot.log", root, b"ok\n", &EntryTimes::default()).unwrap();
+
+    assert_eq!(fs.read_file("/logs/boot.log", root).unwrap(), b"ok\n");
+    let report = Checker::new(&overlay, fs.boot()).check(&CheckOptions::default()).unwrap();
+    assert!(report.is_clean(), "{:?}", report.findings);
+
+    // L’image d’origine n’a pas changé.
+    let base = MemoryBlockDevice::new(&image);
+    let original = FileSystem::mount(&base).unwrap();
+    assert!(original.list(None, root).unwrap().is_empty());
+}
@@ -0,0 +1,123 @@
+//! Propriétés vérifiées sur des arborescences générées : ce qui est écrit
+//! par `ImageBuilder` se relit à l’identique, et le volume reste sain.
+
+mod common;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use common::Rng;
+use rust_project::device::block_device::RamBlockDevice;
+use rust_project::fs::builder::ImageBuilder;
+use rust_project::fs::check::{CheckOptions, Checker};
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::format::FormatOptions;
+use rust_project::fs::walk::WalkOptions;
+
+/// Taille d’un volume à clusters d’un secteur ; elle croît avec les
+/// clusters pour garder assez de clusters pour FAT32.
+const SIZE: u64 = 33 * 1024 * 1024;
+const SEEDS: u64 = 24;
+
+/// Arborescence générée : répertoires, et fichiers avec leur contenu.
+struct Generated {
+    dirs: BTreeSet<String>,
+    files: BTreeMap<String, Vec<u8>>,
+    builder: ImageBuilder,
+}
+
+/// Nom valide, de style 8.3, minuscule, long ou accentué.
+fn name(rng: &mut Rng) -> String {
+    const STEMS: &[&str] = &["DATA", "readme", "Photo 2024", "été", "a", "LOG_01", "Some long file name"];
+    const EXTS: &[&str] = &["", ".TXT", ".bin", ".tar.gz", ".Jpeg"];
+
+    let stem = STEMS[rng.below(STEMS.len() as u64) as usize];
+    let ext = EXTS[rng.below(EXTS.len() as u64) as usize];
+    format!("{}{}{}", stem, rng.below(100), ext)
+}
+
+fn generate(seed: u64) -> Generated {
+    let mut rng = Rng::new(seed);
+    let spc = [1, 2, 8][rng.below(3) as usize];
+    let options = FormatOptions {
+        sectors_per_cluster: Some(spc),
+        ..FormatOptions::default()
+    };
+    let mut builder = ImageBuilder::new(SIZE * spc as u64).format_options(options).volume_id(seed as u32);
+
+    let mut dirs = BTreeSet::new();
+    let mut files = BTreeMap::new();
+    let mut parents = vec![String::new()];
+    // Noms pris, en majuscules : FAT ignore la casse.
+    let mut taken = BTreeSet::new();
+
+    for _ in 0..1 + rng.below(40) {
+        let parent = parents[rng.below(parents.len() as u64) as usize].clone();
+        let path = format!("{}/{}", parent, name(&mut rng));
+        if !taken.insert(path.to_uppercase()) {
+            continue;
+        }
+
+        if rng.below(3) == 0 {
+            builder = builder.directory(&path);
+            dirs.insert(path.clone());
+            parents.push(path);
+        } else {
+            let len = [0, 1, 511, 512, 513, 4000][rng.below(6) as usize] + rng.below(3);
+            let contents: Vec<u8> = (0..len).map(|_| rng.next_u64() as u8).collect();
+            builder = builder.file(&path, contents.clone());
+            files.insert(path, contents);
+        }
+    }
+
+    Generated { dirs, files, builder }
+}
+
+#[test]
+fn generated_trees_round_trip() {
+    for seed in 0..SEEDS {
+        let generated = generate(seed);
+        let device = RamBlockDevice::zeroed(generated.builder.size() as usize);
+        generated.builder.build(&device).unwrap();
+
+        let fs = FileSystem::mount(&device).unwrap();
+        let root = fs.root_cluster();
+
+        let mut dirs = BTreeSet::new();
+        let mut files = BTreeSet::new();
+        fs.walk("/", root, WalkOptions::default(), |entry| {
+            let entry = entry.unwrap();
+            if entry.entry.entry_type.is_dir() {
+                dirs.insert(entry.path);
+            } else {
+                files.insert(entry.path);
+            }
+        })
+        .unwrap();
+
+        assert_eq!(dirs, generated.dirs, "seed {}", seed);
+        assert!(files.iter().eq(generated.files.keys()), "seed {}", seed);
+        for (path, contents) in &generated.files {
+            assert_eq!(&fs.read_file(path, root).unwrap(), contents, "seed {}: {}", seed, path);
+        }
+
+        let report = Checker::new(&device, fs.boot()).check(&CheckOptions::default()).unwrap();
+        assert!(report.is_clean(), "seed {}: {:?}", seed, report.findings);
+    }
+}
+
+#[test]
+fn generated_files_are_contiguous() {
+    for seed in 0..SEEDS {
+        let generated = generate(seed);
+        let device = RamBlockDevice::zeroed(generated.builder.size() as usize);
+        generated.builder.build(&device).unwrap();
+
+        let fs = FileSystem::mount(&device).unwrap();
+        let cluster_size = fs.boot().cluster_size() as u64;
+        for (path, contents) in &generated.files {
+            let stat = fs.stat(path, fs.root_cluster()).unwrap();
+            assert_eq!(stat.cluster_count(), (contents.len() as u64).div_ceil(cluster_size));
+            assert!(stat.fragments() <= 1, "seed {}: {}", seed, path);
+        }
+    }
+}
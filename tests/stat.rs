@@ -0,0 +1,80 @@
+use rust_project::device::block_device::RamBlockDevice;
+use rust_project::fs::boot_sector::BootSector;
+use rust_project::fs::clusters::{extents, Extent};
+use rust_project::fs::directory::{EntryType, SlotLocation, ATTR_ARCHIVE};
+use rust_project::fs::fat::{ChainEnd, Fat};
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::stat::{attribute_flags, ATTR_READ_ONLY};
+use rust_project::fs::timestamp::DateTime;
+use rust_project::testing::{created, modified, short_entry, ImageFixture};
+
+/// Racine : FRAG.BIN (1300 octets) sur les clusters 3, 4 puis 7.
+fn make_volume() -> (RamBlockDevice, BootSector) {
+    let entry = short_entry("FRAG.BIN", ATTR_READ_ONLY | ATTR_ARCHIVE, 3, 1300);
+    let entry = created(entry, DateTime::parse("2024-01-01 12:00:00").unwrap());
+    let entry = modified(entry, DateTime::parse("2024-02-02").unwrap());
+
+    let fixture = ImageFixture::new(8).directory(&[2], &[entry]).chain(&[3, 4, 7]);
+    (fixture.device(), fixture.boot_sector())
+}
+
+#[test]
+fn fragmented_file_details() {
+    let (device, _) = make_volume();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let stat = fs.stat("/FRAG.BIN", fs.root_cluster()).unwrap();
+
+    assert_eq!(stat.short_name, "FRAG.BIN");
+    assert_eq!(stat.entry_type, EntryType::File);
+    assert_eq!(attribute_flags(stat.attributes), "R----A");
+    assert_eq!(stat.size, 1300);
+    assert_eq!(stat.allocated(), 3 * 512);
+    assert_eq!(stat.extents, [Extent { start: 3, length: 2 }, Extent { start: 7, length: 1 }]);
+    assert_eq!(stat.fragments(), 2);
+    assert_eq!(stat.chain_end, ChainEnd::EndOfChain);
+    assert_eq!(stat.location, Some(SlotLocation { cluster: 2, index: 0 }));
+    assert_eq!(stat.created.unwrap().to_string(), "2024-01-01 12:00:00");
+    assert_eq!(stat.modified.unwrap().to_string(), "2024-02-02 00:00:00");
+
+    let text = stat.to_string();
+    assert!(text.contains("Chain: 3-4 7"), "{}", text);
+    assert!(text.contains("Entry: cluster 2, slot 0"), "{}", text);
+}
+
+#[test]
+fn corrupted_chains_are_reported_not_fatal() {
+    let (device, boot) = make_volume();
+    let fs = FileSystem::mount(&device).unwrap();
+    let fat = Fat::new(&device, &boot);
+
+    fat.set_entry(7, 3).unwrap();
+    let stat = fs.stat("/FRAG.BIN", fs.root_cluster()).unwrap();
+    assert_eq!(stat.chain_end, ChainEnd::Loop { cluster: 3 });
+    assert_eq!(stat.cluster_count(), 3);
+
+    fat.set_entry(4, 0).unwrap();
+    let stat = fs.stat("/FRAG.BIN", fs.root_cluster()).unwrap();
+    assert_eq!(stat.chain_end, ChainEnd::Free { cluster: 4 });
+
+    fat.set_entry(4, 0x0FFF_FFF0).unwrap();
+    let stat = fs.stat("/FRAG.BIN", fs.root_cluster()).unwrap();
+    assert_eq!(stat.chain_end, ChainEnd::InvalidCluster { from: 4, value: 0x0FFF_FFF0 });
+}
+
+#[test]
+fn root_and_helpers() {
+    let (device, _) = make_volume();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let root = fs.stat("/", fs.root_cluster()).unwrap();
+    assert_eq!(root.short_name, "/");
+    assert_eq!(root.location, None);
+    assert_eq!(root.extents, [Extent { start: 2, length: 1 }]);
+
+    assert_eq!(extents(&[9, 10, 11, 5, 12]).len(), 3);
+    assert!(extents(&[]).is_empty());
+
+    let err = fs.stat("/NOPE", fs.root_cluster()).unwrap_err();
+    assert_eq!(err.path(), Some("/NOPE"));
+}
@@ -0,0 +1,51 @@
+use rust_project::fs::fat::{Fat, FAT_BAD};
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::format::FormatOptions;
+use rust_project::fs::fsinfo::FsInfo;
+use rust_project::fs::statfs::FreeCount;
+use rust_project::testing::formatted;
+
+#[test]
+fn empty_volume_stats() {
+    let device = formatted(&FormatOptions::default());
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let stats = fs.statfs(FreeCount::Scan).unwrap();
+    let total = fs.boot().cluster_count();
+
+    assert_eq!(stats.total_clusters, total);
+    assert_eq!(stats.free_clusters, total - 1); // racine
+    assert_eq!(stats.bad_clusters, Some(0));
+    assert_eq!(stats.cluster_size, 512);
+    assert_eq!(stats.used_bytes(), 512);
+}
+
+#[test]
+fn scan_counts_bad_and_used_clusters() {
+    let device = formatted(&FormatOptions::default());
+    let fs = FileSystem::mount(&device).unwrap();
+    let fat = Fat::new(&device, fs.boot());
+    fat.set_entry(10, FAT_BAD).unwrap();
+    fat.allocate().unwrap();
+
+    let stats = fs.statfs(FreeCount::Scan).unwrap();
+
+    assert_eq!(stats.bad_clusters, Some(1));
+    assert_eq!(stats.free_clusters, stats.total_clusters - 3);
+}
+
+#[test]
+fn hint_uses_fs_info_when_plausible() {
+    let device = formatted(&FormatOptions::default());
+    let fs = FileSystem::mount(&device).unwrap();
+    FsInfo { free_count: 100, next_free: 3 }.write(&device, fs.boot()).unwrap();
+
+    let hinted = fs.statfs(FreeCount::Hint).unwrap();
+    assert_eq!(hinted.free_clusters, 100);
+    assert_eq!(hinted.bad_clusters, None);
+
+    // Indication impossible : retour au parcours complet.
+    FsInfo { free_count: u32::MAX - 1, next_free: 3 }.write(&device, fs.boot()).unwrap();
+    let scanned = fs.statfs(FreeCount::Hint).unwrap();
+    assert_eq!(scanned.free_clusters, scanned.total_clusters - 1);
+}
@@ -0,0 +1,89 @@
+use rust_project::device::block_device::{Access, BlockDevice, RamBlockDevice};
+use rust_project::device::stats::{format_trace, parse_trace, replay, IoStats, StatsDevice, TraceError, TraceRecord};
+use rust_project::fs::create::EntryTimes;
+use rust_project::fs::filesystem::{FileSystem, MountOptions};
+use rust_project::testing::ImageFixture;
+
+#[test]
+fn counts_accesses_and_sectors() {
+    let device = StatsDevice::new(RamBlockDevice::zeroed(8192));
+    let mut buf = [0u8; 600];
+
+    device.read_at(0, &mut buf).unwrap();
+    device.read_at(600, &mut buf[..100]).unwrap();
+    device.write_at(4096, &[1; 512]).unwrap();
+    device.write_at(100, &[2; 10]).unwrap();
+
+    assert_eq!(
+        device.stats(),
+        IoStats {
+            reads: 2,
+            writes: 2,
+            bytes_read: 700,
+            bytes_written: 522,
+            sectors: 3,
+            sequential: 1,
+        }
+    );
+    assert_eq!(device.stats().random(), 3);
+    // Sans `with_trace`, rien n’est gardé.
+    assert!(device.trace().is_empty());
+
+    device.reset();
+    assert_eq!(device.stats(), IoStats::default());
+}
+
+#[test]
+fn trace_round_trips_as_text() {
+    let device = StatsDevice::new(RamBlockDevice::zeroed(4096)).with_trace();
+    device.write_at(512, &[7; 16]).unwrap();
+    device.read_at(0, &mut [0u8; 1024]).unwrap();
+
+    let trace = device.trace();
+    assert_eq!(
+        trace,
+        [
+            TraceRecord { access: Access::Write, offset: 512, len: 16 },
+            TraceRecord { access: Access::Read, offset: 0, len: 1024 },
+        ]
+    );
+
+    let text = format_trace(&trace);
+    assert_eq!(text, "W 512 16\nR 0 1024\n");
+    assert_eq!(parse_trace(&text).unwrap(), trace);
+    assert_eq!(parse_trace("R 0 1\n\nX 1 2\n"), Err(TraceError { line: 3 }));
+}
+
+#[test]
+fn replay_reproduces_accesses_without_changing_data() {
+    let recorded = StatsDevice::new(ImageFixture::sample().device()).with_trace();
+    {
+        let fs = FileSystem::mount_with(&recorded, &MountOptions { writable: true, ..MountOptions::default() }).unwrap();
+        let root = fs.root_cluster();
+        fs.read_file("/DOCS/notes", root).unwrap();
+        fs.mkdir("/NEW", root, &EntryTimes::default()).unwrap();
+    }
+    let trace = recorded.trace();
+    assert!(trace.iter().any(|r| r.access == Access::Write));
+
+    let image = recorded.into_inner().into_inner();
+    let target = StatsDevice::new(RamBlockDevice::new(image.clone()));
+    replay(&target, &trace).unwrap();
+
+    assert_eq!(target.stats().writes, trace.iter().filter(|r| r.access == Access::Write).count() as u64);
+    assert_eq!(target.into_inner().into_inner(), image);
+}
+
+#[test]
+fn reset_isolates_one_operation() {
+    let device = StatsDevice::new(ImageFixture::sample().device());
+    let fs = FileSystem::mount(&device).unwrap();
+    assert!(device.stats().reads > 0);
+    device.reset();
+
+    fs.read_file("/DOCS/DEEP/A.BIN", fs.root_cluster()).unwrap();
+    let stats = device.stats();
+    assert_eq!(stats.writes, 0);
+    assert!(stats.bytes_read >= 512, "{}", stats);
+    assert!(stats.sectors >= 1, "{}", stats);
+}
@@ -0,0 +1,111 @@
+use rust_project::fs::check::{CheckOptions, Checker, Issue};
+use rust_project::fs::fat::ChainEnd;
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::testing::{
+    deleted, dir_entry, dot_entries, file_entry, long_name, volume_label, ImageFixture,
+};
+
+#[test]
+fn fragmented_chain_reads_back() {
+    let contents: Vec<u8> = (0..1300u32).map(|i| (i % 251) as u8).collect();
+    let device = ImageFixture::new(16)
+        .directory(&[2], &[file_entry("DATA.BIN", 9, 1300)])
+        .file(&[9, 4, 12], &contents)
+        .device();
+
+    let fs = FileSystem::mount(&device).unwrap();
+    let root = fs.root_cluster();
+    assert_eq!(fs.read_file("/DATA.BIN", root).unwrap(), contents);
+
+    let stat = fs.stat("/DATA.BIN", root).unwrap();
+    assert_eq!(stat.fragments(), 3);
+
+    let report = Checker::new(&device, fs.boot()).check(&CheckOptions::default()).unwrap();
+    assert!(report.is_clean(), "{:?}", report.findings);
+}
+
+#[test]
+fn deleted_entries_and_long_names() {
+    let mut root = vec![volume_label("FIXTURE")];
+    root.push(deleted(file_entry("OLD.TXT", 0, 0)));
+    root.extend(long_name("Rapport annuel.txt", file_entry("RAPPOR~1.TXT", 0, 0)));
+    root.push(dir_entry("SUB", 3));
+
+    let mut sub = dot_entries(3, 0).to_vec();
+    sub.extend(long_name("notes", file_entry("NOTES", 0, 0)));
+
+    let device = ImageFixture::new(4)
+        .directory(&[2], &root)
+        .directory(&[3], &sub)
+        .device();
+
+    let fs = FileSystem::mount(&device).unwrap();
+    let names: Vec<String> = fs
+        .list(Some("/"), fs.root_cluster())
+        .unwrap()
+        .iter()
+        .map(|e| e.display_name().to_string())
+        .collect();
+    assert_eq!(names, ["Rapport annuel.txt", "SUB"]);
+
+    let sub = fs.list(Some("/SUB"), fs.root_cluster()).unwrap();
+    assert_eq!(sub.last().unwrap().long_name.as_deref(), Some("notes"));
+}
+
+#[test]
+fn corrupt_fat_values_are_detected() {
+    let device = ImageFixture::new(8)
+        .directory(
+            &[2],
+            &[
+                file_entry("LOOP.BIN", 3, 2048),
+                file_entry("BAD.BIN", 6, 1024),
+            ],
+        )
+        .cycle(&[3, 4, 5])
+        .chain(&[6])
+        .fat_entry(6, 0x0FFF_FFF7)
+        .device();
+
+    let fs = FileSystem::mount(&device).unwrap();
+    let chain = fs.inspect().chain(3).unwrap();
+    assert_eq!(chain.clusters, [3, 4, 5]);
+    assert_eq!(chain.end, ChainEnd::Loop { cluster: 3 });
+    assert!(fs.read_file("/LOOP.BIN", fs.root_cluster()).is_err());
+
+    let report = Checker::new(&device, fs.boot()).check(&CheckOptions::default()).unwrap();
+    let issues: Vec<&Issue> = report.findings.iter().map(|f| &f.issue).collect();
+    assert!(issues.iter().any(|i| matches!(i, Issue::ChainLoop { cluster: 3, .. })));
+    assert!(issues.iter().any(|i| matches!(i, Issue::BrokenChain { .. })));
+}
+
+#[test]
+fn geometry_follows_fixture() {
+    let fixture = ImageFixture::new(100).sectors_per_cluster(4).fat_count(1);
+    let boot = fixture.boot_sector();
+    assert_eq!(fixture.cluster_size(), 2048);
+    assert_eq!(boot.fat_count, 1);
+
+    let image = fixture.build();
+    assert_eq!(image.len() as u64, boot.total_sectors as u64 * 512);
+    assert_eq!(&image[510..512], &[0x55, 0xAA]);
+
+    let device = fixture.device();
+    let fs = FileSystem::mount(&device).unwrap();
+    let report = Checker::new(&device, fs.boot()).check(&CheckOptions::default()).unwrap();
+    assert!(report.is_clean(), "{:?}", report.findings);
+}
+
+#[test]
+fn sample_is_a_clean_volume() {
+    let device = ImageFixture::sample().device();
+    let fs = FileSystem::mount(&device).unwrap();
+    let root = fs.root_cluster();
+
+    assert_eq!(fs.label().unwrap().as_deref(), Some("SAMPLE"));
+    assert_eq!(fs.read_file("/DOCS/notes", root).unwrap().len(), 1100);
+    assert_eq!(fs.stat("/DOCS/notes", root).unwrap().fragments(), 3);
+
+    let report = Checker::new(&device, fs.boot()).check(&CheckOptions::default()).unwrap();
+    assert!(report.is_clean(), "{:?}", report.findings);
+}
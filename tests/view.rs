@@ -0,0 +1,107 @@
+use std::cell::RefCell;
+
+use rust_project::device::block_device::{BlockDevice, BlockDeviceError, RamBlockDevice};
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::view::{hexdump, Count};
+use rust_project::testing::{file_entry, ImageFixture};
+
+/// Périphérique qui note les offsets lus.
+struct Recording {
+    inner: RamBlockDevice,
+    reads: RefCell<Vec<u64>>,
+}
+
+impl BlockDevice for Recording {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<(), BlockDeviceError> {
+        self.reads.borrow_mut().push(offset);
+        self.inner.read_at(offset, buf)
+    }
+}
+
+/// LOG.TXT : 2000 lignes « line NNNN\n » (20000 octets, clusters 3 à 42).
+fn make_volume() -> (RamBlockDevice, Vec<u8>) {
+    let content: Vec<u8> = (0..2000).flat_map(|i| format!("line {:04}\n", i).into_bytes()).collect();
+    let chain: Vec<u32> = (3..43).collect();
+
+    let device = ImageFixture::new(48)
+        .directory(&[2], &[file_entry("LOG.TXT", 3, content.len() as u32)])
+        .file(&chain, &content)
+        .device();
+    (device, content)
+}
+
+#[test]
+fn head_bytes_and_lines() {
+    let (device, content) = make_volume();
+    let fs = FileSystem::mount(&device).unwrap();
+    let root = fs.root_cluster();
+
+    assert_eq!(fs.head("/LOG.TXT", root, Count::Bytes(7)).unwrap(), b"line 00");
+    assert_eq!(fs.head("/LOG.TXT", root, Count::Lines(2)).unwrap(), b"line 0000\nline 0001\n");
+    assert_eq!(fs.head("/LOG.TXT", root, Count::Lines(5000)).unwrap(), content);
+    assert!(fs.head("/LOG.TXT", root, Count::Lines(0)).unwrap().is_empty());
+}
+
+#[test]
+fn tail_reads_only_last_clusters() {
+    let (inner, content) = make_volume();
+    let device = Recording { inner, reads: RefCell::new(Vec::new()) };
+    let fs = FileSystem::mount(&device).unwrap();
+    let root = fs.root_cluster();
+    let data_start = fs.boot().data_start_sector() * 512;
+    device.reads.borrow_mut().clear();
+
+    let tail = fs.tail("/LOG.TXT", root, Count::Lines(2)).unwrap();
+    assert_eq!(tail, b"line 1998\nline 1999\n");
+
+    // Clusters de données lus : seulement le(s) dernier(s) du fichier.
+    let file_data_start = data_start + 512; // après la racine
+    let lowest = device
+        .reads
+        .borrow()
+        .iter()
+        .copied()
+        .filter(|&offset| offset >= file_data_start)
+        .min()
+        .unwrap();
+    assert!(lowest >= file_data_start + 38 * 512, "read at {:#x}", lowest);
+
+    assert_eq!(fs.tail("/LOG.TXT", root, Count::Bytes(4)).unwrap(), b"999\n");
+    assert_eq!(fs.tail("/LOG.TXT", root, Count::Bytes(1 << 20)).unwrap(), content);
+}
+
+#[test]
+fn hexdump_canonical_format() {
+    let mut data = b"Hello, World!\n".to_vec();
+    data.extend_from_slice(&[0u8; 50]);
+
+    assert_eq!(
+        hexdump(&data, 0),
+        "00000000  48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 0a 00 00  |Hello, World!...|\n\
+         00000010  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|\n\
+         *\n\
+         00000040\n"
+    );
+
+    assert_eq!(
+        hexdump(b"hi\n", 0x20),
+        "00000020  68 69 0a                                          |hi.|\n00000023\n"
+    );
+    assert_eq!(hexdump(&[], 0), "");
+}
+
+#[test]
+fn hexdump_of_file_range() {
+    let (device, _) = make_volume();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    // Plage à cheval sur deux clusters.
+    let text = fs.hexdump("/LOG.TXT", fs.root_cluster(), 510, Some(4)).unwrap();
+    assert_eq!(
+        text,
+        "000001fe  6c 69 6e 65                                       |line|\n00000202\n"
+    );
+
+    let err = fs.hexdump("/", fs.root_cluster(), 0, None).unwrap_err();
+    assert_eq!(err.path(), Some("/"));
+}
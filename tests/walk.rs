@@ -0,0 +1,134 @@
+use rust_project::device::block_device::RamBlockDevice;
+use rust_project::fs::filesystem::FileSystem;
+use rust_project::fs::walk::{WalkOptions, WalkOrder};
+use rust_project::testing::{dir_entry, file_entry, ImageFixture};
+
+/// Volume à clusters de 512 octets :
+///
+/// /A.TXT (100 octets, 1 cluster)
+/// /SUB/B.TXT (600 octets, 2 clusters)
+/// /SUB/DEEP/C.TXT (10 octets, 1 cluster)
+/// /SUB/DEEP/UP → SUB (cycle)
+/// /Z.TXT (0 octet, pas de cluster)
+fn make_tree() -> RamBlockDevice {
+    let root = [file_entry("A.TXT", 3, 100), dir_entry("SUB", 4), file_entry("Z.TXT", 0, 0)];
+    let sub = [file_entry("B.TXT", 5, 600), dir_entry("DEEP", 7)];
+    let deep = [file_entry("C.TXT", 8, 10), dir_entry("UP", 4)];
+
+    ImageFixture::new(16)
+        .directory(&[2], &root)
+        .directory(&[4], &sub)
+        .directory(&[7], &deep)
+        .chain(&[3])
+        .chain(&[5, 6])
+        .chain(&[8])
+        .device()
+}
+
+fn paths(fs: &FileSystem<'_, RamBlockDevice>, options: WalkOptions) -> Vec<String> {
+    let mut paths = Vec::new();
+    fs.walk("/", fs.root_cluster(), options, |item| paths.push(item.unwrap().path))
+        .unwrap();
+    paths
+}
+
+#[test]
+fn depth_first_and_breadth_first_orders() {
+    let device = make_tree();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let dfs = paths(&fs, WalkOptions::default());
+    assert_eq!(
+        dfs,
+        [
+            "/A.TXT",
+            "/SUB",
+            "/SUB/B.TXT",
+            "/SUB/DEEP",
+            "/SUB/DEEP/C.TXT",
+            "/SUB/DEEP/UP",
+            "/Z.TXT",
+        ]
+    );
+
+    let bfs = paths(&fs, WalkOptions { order: WalkOrder::BreadthFirst, max_depth: None });
+    assert_eq!(
+        bfs,
+        [
+            "/A.TXT",
+            "/SUB",
+            "/Z.TXT",
+            "/SUB/B.TXT",
+            "/SUB/DEEP",
+            "/SUB/DEEP/C.TXT",
+            "/SUB/DEEP/UP",
+        ]
+    );
+}
+
+#[test]
+fn max_depth_limits_descent() {
+    let device = make_tree();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let options = WalkOptions { max_depth: Some(1), ..WalkOptions::default() };
+    assert_eq!(paths(&fs, options), ["/A.TXT", "/SUB", "/Z.TXT"]);
+
+    let options = WalkOptions { max_depth: Some(0), ..WalkOptions::default() };
+    assert!(paths(&fs, options).is_empty());
+}
+
+#[test]
+fn du_counts_logical_and_allocated_sizes() {
+    let device = make_tree();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let report = fs.du("/", fs.root_cluster(), None).unwrap();
+    assert!(report.errors.is_empty());
+
+    let summary: Vec<(&str, u64, u64)> = report
+        .entries
+        .iter()
+        .map(|e| (e.path.as_str(), e.logical, e.allocated))
+        .collect();
+    // DEEP : C.TXT + son propre cluster ; UP n’est pas recompté.
+    assert_eq!(
+        summary,
+        [
+            ("/SUB/DEEP", 10, 2 * 512),
+            ("/SUB", 610, 5 * 512),
+            ("/", 710, 7 * 512),
+        ]
+    );
+
+    let root = report.entries.last().unwrap();
+    assert_eq!((root.files, root.directories), (4, 3));
+
+    let shallow = fs.du("/", fs.root_cluster(), Some(0)).unwrap();
+    assert_eq!(shallow.entries.len(), 1);
+    assert_eq!(shallow.entries[0].allocated, 7 * 512);
+}
+
+#[test]
+fn tree_renders_hierarchy_and_rejects_files() {
+    let device = make_tree();
+    let fs = FileSystem::mount(&device).unwrap();
+
+    let text = fs.tree("/", fs.root_cluster(), None).unwrap();
+    assert_eq!(
+        text,
+        "/\n\
+         ├── A.TXT\n\
+         ├── SUB\n\
+         │   ├── B.TXT\n\
+         │   └── DEEP\n\
+         │       ├── C.TXT\n\
+         │       └── UP\n\
+         └── Z.TXT\n\
+         \n\
+         3 directories, 4 files"
+    );
+
+    let err = fs.tree("/A.TXT", fs.root_cluster(), None).unwrap_err();
+    assert_eq!(err.path(), Some("/A.TXT"));
+}